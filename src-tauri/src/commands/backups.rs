@@ -0,0 +1,98 @@
+// Commandes Tauri pour les sauvegardes de la base de données
+use crate::db::backup::{self, DefaultBackupDir};
+use crate::db::{queries, BackupInfo, BackupSettings, DbConnection};
+use tauri::State;
+
+/// Récupère les paramètres des sauvegardes automatiques
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_backup_settings(db: State<DbConnection>) -> Result<BackupSettings, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_backup_settings(&conn).map_err(|e| e.to_string())
+}
+
+/// Met à jour les paramètres des sauvegardes automatiques
+///
+/// # Arguments
+/// * `settings` - Répertoire (absolu, ou vide pour celui par défaut) et intervalle en heures
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn update_backup_settings(
+    settings: BackupSettings,
+    db: State<DbConnection>,
+) -> Result<BackupSettings, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::update_backup_settings(&conn, &settings).map_err(|e| e.to_string())
+}
+
+/// Crée immédiatement une sauvegarde, puis applique la rotation
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+#[tauri::command]
+pub fn create_backup(
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<BackupInfo, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let dir = backup::backup_directory(&conn, &default_dir.path()).map_err(|e| e.to_string())?;
+    let info = backup::create_backup(&conn, &dir).map_err(|e| e.to_string())?;
+    drop(conn); // Libère le lock avant la rotation
+
+    backup::rotate_backups(&dir).map_err(|e| e.to_string())?;
+    Ok(info)
+}
+
+/// Liste les sauvegardes disponibles, de la plus récente à la plus ancienne
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+#[tauri::command]
+pub fn list_backups(
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<Vec<BackupInfo>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let dir = backup::backup_directory(&conn, &default_dir.path()).map_err(|e| e.to_string())?;
+    drop(conn); // Libère le lock avant la lecture des fichiers
+
+    backup::list_backups(&dir).map_err(|e| e.to_string())
+}
+
+/// Restaure une sauvegarde après avoir vérifié sa version de schéma
+///
+/// L'état actuel est sauvegardé avant d'être remplacé.
+///
+/// # Arguments
+/// * `file_name` - Nom du fichier de sauvegarde (voir `list_backups`)
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+///
+/// # Returns
+/// La sauvegarde de l'état précédant la restauration
+#[tauri::command]
+pub fn restore_backup(
+    file_name: String,
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<BackupInfo, String> {
+    let passphrase = db.passphrase();
+    let conn = db.get_connection();
+    let mut conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let dir = backup::backup_directory(&conn, &default_dir.path()).map_err(|e| e.to_string())?;
+    backup::restore_backup(&mut conn, &dir, &file_name, passphrase.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,85 @@
+// Commandes Tauri pour le chiffrement de la base de données (SQLCipher)
+use crate::db::backup::{self, DefaultBackupDir};
+use crate::db::{encryption, DbConnection, EncryptionStatus};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Récupère l'état du chiffrement de la base (prise en charge, chiffrée, verrouillée)
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_encryption_status(db: State<DbConnection>) -> Result<EncryptionStatus, String> {
+    Ok(encryption::encryption_status(&db))
+}
+
+/// Déverrouille la base chiffrée de l'espace actif
+///
+/// # Arguments
+/// * `passphrase` - Phrase secrète de la base
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn unlock_database(passphrase: String, db: State<DbConnection>) -> Result<(), String> {
+    encryption::unlock(&db, &passphrase).map_err(|e| e.to_string())
+}
+
+/// Chiffre la base en clair de l'espace actif, ainsi que ses sauvegardes
+///
+/// # Arguments
+/// * `passphrase` - Nouvelle phrase secrète
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+///
+/// # Returns
+/// Le nombre de sauvegardes chiffrées
+#[tauri::command]
+pub fn encrypt_database(
+    passphrase: String,
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<usize, String> {
+    let dir = backup_dir(&db, &default_dir.path())?;
+
+    encryption::encrypt_database(&db, &passphrase, &dir).map_err(|e| e.to_string())
+}
+
+/// Change la phrase secrète de la base et de ses sauvegardes
+///
+/// # Arguments
+/// * `current_passphrase` - Phrase secrète actuelle
+/// * `new_passphrase` - Nouvelle phrase secrète
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+///
+/// # Returns
+/// Le nombre de sauvegardes mises à jour
+#[tauri::command]
+pub fn change_database_passphrase(
+    current_passphrase: String,
+    new_passphrase: String,
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<usize, String> {
+    let dir = backup_dir(&db, &default_dir.path())?;
+
+    encryption::change_passphrase(&db, &current_passphrase, &new_passphrase, &dir)
+        .map_err(|e| e.to_string())
+}
+
+/// Exporte une copie en clair de la base, par exemple pour un autre outil
+///
+/// # Arguments
+/// * `path` - Fichier de destination (remplacé s'il existe)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn export_decrypted_database(path: String, db: State<DbConnection>) -> Result<(), String> {
+    encryption::export_decrypted(&db, Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// Répertoire des sauvegardes de la base ouverte
+fn backup_dir(db: &DbConnection, default_dir: &Path) -> Result<PathBuf, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    backup::backup_directory(&conn, default_dir).map_err(|e| e.to_string())
+}
@@ -0,0 +1,85 @@
+// Commandes Tauri pour la gestion des objectifs de projet
+use crate::db::{queries, DbConnection, GoalProgress, ProjectGoal, ProjectGoalInput};
+use tauri::State;
+
+/// Récupère les objectifs d'un projet
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_project_goals(
+    project_id: i64,
+    db: State<DbConnection>,
+) -> Result<Vec<ProjectGoal>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_goals_by_project(&conn, project_id).map_err(|e| e.to_string())
+}
+
+/// Fixe un nouvel objectif sur un projet
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `input` - Métrique (pomodoros, minutes), cible et période (day, week, month, total)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn set_project_goal(
+    project_id: i64,
+    input: ProjectGoalInput,
+    db: State<DbConnection>,
+) -> Result<ProjectGoal, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::create_goal(&conn, project_id, &input).map_err(|e| e.to_string())
+}
+
+/// Met à jour un objectif existant
+///
+/// # Arguments
+/// * `id` - ID de l'objectif
+/// * `input` - Nouvelle métrique, cible et période
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn update_project_goal(
+    id: i64,
+    input: ProjectGoalInput,
+    db: State<DbConnection>,
+) -> Result<ProjectGoal, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::update_goal(&conn, id, &input).map_err(|e| e.to_string())
+}
+
+/// Supprime un objectif
+///
+/// # Arguments
+/// * `id` - ID de l'objectif
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn clear_project_goal(id: i64, db: State<DbConnection>) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::delete_goal(&conn, id).map_err(|e| e.to_string())
+}
+
+/// Calcule la progression des objectifs d'un projet sur la période en cours
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_project_goal_progress(
+    project_id: i64,
+    db: State<DbConnection>,
+) -> Result<Vec<GoalProgress>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let now = queries::current_local_time(&conn).map_err(|e| e.to_string())?;
+    queries::get_goal_progress_by_project(&conn, project_id, now).map_err(|e| e.to_string())
+}
@@ -0,0 +1,31 @@
+// Commandes Tauri pour la maintenance de la base de données
+use crate::db::backup::{self, DefaultBackupDir};
+use crate::db::{queries, DbConnection, IntegrityReport};
+use tauri::State;
+
+/// Vérifie l'intégrité et la cohérence de la base
+///
+/// Avec `repair`, une sauvegarde est prise avant de corriger les anomalies
+/// réparables puis de compacter la base.
+///
+/// # Arguments
+/// * `repair` - Corrige les anomalies réparables (par défaut: non)
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+#[tauri::command]
+pub fn check_database(
+    repair: Option<bool>,
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<IntegrityReport, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let repair = repair.unwrap_or(false);
+    if repair {
+        let dir = backup::backup_directory(&conn, &default_dir.path()).map_err(|e| e.to_string())?;
+        backup::create_backup(&conn, &dir).map_err(|e| e.to_string())?;
+    }
+
+    queries::check_database(&conn, repair).map_err(|e| e.to_string())
+}
@@ -0,0 +1,130 @@
+// Commandes Tauri pour la gestion des jalons de projet
+use crate::db::{queries, DbConnection, Milestone, MilestoneBurndown, MilestoneInput, Task};
+use tauri::State;
+
+/// Récupère les jalons d'un projet
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_milestones(project_id: i64, db: State<DbConnection>) -> Result<Vec<Milestone>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_milestones_by_project(&conn, project_id).map_err(|e| e.to_string())
+}
+
+/// Crée un nouveau jalon
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `input` - Nom, description, date de début et échéance du jalon
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn create_milestone(
+    project_id: i64,
+    input: MilestoneInput,
+    db: State<DbConnection>,
+) -> Result<Milestone, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::create_milestone(&conn, project_id, &input).map_err(|e| e.to_string())
+}
+
+/// Met à jour un jalon existant
+///
+/// # Arguments
+/// * `id` - ID du jalon
+/// * `input` - Nouvelles données du jalon
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn update_milestone(
+    id: i64,
+    input: MilestoneInput,
+    db: State<DbConnection>,
+) -> Result<Milestone, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::update_milestone(&conn, id, &input).map_err(|e| e.to_string())
+}
+
+/// Supprime un jalon
+///
+/// # Arguments
+/// * `id` - ID du jalon à supprimer
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn delete_milestone(id: i64, db: State<DbConnection>) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::delete_milestone(&conn, id).map_err(|e| e.to_string())
+}
+
+/// Récupère les tâches liées à un jalon
+///
+/// # Arguments
+/// * `milestone_id` - ID du jalon
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_milestone_tasks(milestone_id: i64, db: State<DbConnection>) -> Result<Vec<Task>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_tasks_by_milestone(&conn, milestone_id).map_err(|e| e.to_string())
+}
+
+/// Lie une tâche à un jalon
+///
+/// # Arguments
+/// * `milestone_id` - ID du jalon
+/// * `task_id` - ID de la tâche
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn add_task_to_milestone(
+    milestone_id: i64,
+    task_id: i64,
+    db: State<DbConnection>,
+) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::add_task_to_milestone(&conn, milestone_id, task_id).map_err(|e| e.to_string())
+}
+
+/// Retire une tâche d'un jalon
+///
+/// # Arguments
+/// * `milestone_id` - ID du jalon
+/// * `task_id` - ID de la tâche
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn remove_task_from_milestone(
+    milestone_id: i64,
+    task_id: i64,
+    db: State<DbConnection>,
+) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::remove_task_from_milestone(&conn, milestone_id, task_id).map_err(|e| e.to_string())
+}
+
+/// Récupère les séries burndown et burnup d'un jalon
+///
+/// # Arguments
+/// * `id` - ID du jalon
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_milestone_burndown(id: i64, db: State<DbConnection>) -> Result<MilestoneBurndown, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    queries::get_milestone_burndown(&conn, id, today).map_err(|e| e.to_string())
+}
@@ -1,12 +1,34 @@
 // Module contenant toutes les commandes Tauri
+pub mod backups;
+pub mod encryption;
+pub mod goals;
+pub mod maintenance;
+pub mod milestones;
 pub mod projects;
+pub mod reports;
+pub mod retention;
 pub mod sessions;
 pub mod settings;
+pub mod streaks;
 pub mod summary;
+pub mod sync;
 pub mod tasks;
+pub mod transfer;
+pub mod workspaces;
 
+pub use backups::*;
+pub use encryption::*;
+pub use goals::*;
+pub use maintenance::*;
+pub use milestones::*;
 pub use projects::*;
+pub use reports::*;
+pub use retention::*;
 pub use sessions::*;
 pub use settings::*;
+pub use streaks::*;
 pub use summary::*;
+pub use sync::*;
 pub use tasks::*;
+pub use transfer::*;
+pub use workspaces::*;
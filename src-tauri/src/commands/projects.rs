@@ -1,8 +1,11 @@
 // Commandes Tauri pour la gestion des projets
-use crate::db::{queries, CreateProjectInput, DbConnection, Project};
+use crate::db::{
+    queries, CreateProjectInput, DbConnection, Project, ProjectDeletionPolicy,
+    ProjectDeletionPreview, ProjectStatus, ProjectTreeNode,
+};
 use tauri::State;
 
-/// Récupère tous les projets
+/// Récupère tous les projets non archivés
 ///
 /// # Arguments
 /// * `db` - État partagé contenant la connexion à la base de données
@@ -14,22 +17,60 @@ pub fn get_projects(db: State<DbConnection>) -> Result<Vec<Project>, String> {
     queries::get_all_projects(&conn).map_err(|e| e.to_string())
 }
 
+/// Récupère les projets ayant un statut donné
+///
+/// # Arguments
+/// * `status` - Statut recherché (active, on_hold, completed, archived)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_projects_by_status(
+    status: ProjectStatus,
+    db: State<DbConnection>,
+) -> Result<Vec<Project>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_projects_by_status(&conn, &status).map_err(|e| e.to_string())
+}
+
+/// Récupère l'arborescence des projets avec les totaux cumulés par sous-arbre
+///
+/// # Arguments
+/// * `include_archived` - Inclut les projets archivés (false par défaut)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_project_tree(
+    include_archived: Option<bool>,
+    db: State<DbConnection>,
+) -> Result<Vec<ProjectTreeNode>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_project_tree(&conn, include_archived.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
 /// Crée un nouveau projet
 ///
 /// # Arguments
 /// * `name` - Nom du projet
 /// * `color` - Couleur du projet (optionnel)
+/// * `parent_project_id` - Projet parent (optionnel)
 /// * `db` - État partagé contenant la connexion à la base de données
 #[tauri::command]
 pub fn create_project(
     name: String,
     color: Option<String>,
+    parent_project_id: Option<i64>,
     db: State<DbConnection>,
 ) -> Result<Project, String> {
     let conn = db.get_connection();
     let conn = conn.lock().map_err(|e| e.to_string())?;
 
-    let input = CreateProjectInput { name, color };
+    let input = CreateProjectInput {
+        name,
+        color,
+        parent_project_id,
+    };
     queries::create_project(&conn, &input).map_err(|e| e.to_string())
 }
 
@@ -53,15 +94,79 @@ pub fn update_project(
     queries::update_project(&conn, id, &name, &color).map_err(|e| e.to_string())
 }
 
-/// Supprime un projet
+/// Déplace un projet et ses descendants sous un nouveau parent
+///
+/// # Arguments
+/// * `id` - ID du projet à déplacer
+/// * `parent_project_id` - Nouveau parent (None pour la racine)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn move_project(
+    id: i64,
+    parent_project_id: Option<i64>,
+    db: State<DbConnection>,
+) -> Result<Project, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::move_project(&conn, id, parent_project_id).map_err(|e| e.to_string())
+}
+
+/// Change le statut d'un projet
+///
+/// Archiver un projet le masque, ainsi que ses tâches et ses sous-projets,
+/// des vues par défaut sans l'exclure des résumés historiques
+///
+/// # Arguments
+/// * `id` - ID du projet
+/// * `status` - Nouveau statut
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn set_project_status(
+    id: i64,
+    status: ProjectStatus,
+    db: State<DbConnection>,
+) -> Result<Project, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::set_project_status(&conn, id, &status).map_err(|e| e.to_string())
+}
+
+/// Calcule le nombre de projets, tâches et sessions affectés par une suppression
 ///
 /// # Arguments
 /// * `id` - ID du projet à supprimer
+/// * `policy` - Sort réservé aux tâches (orphan par défaut)
 /// * `db` - État partagé contenant la connexion à la base de données
 #[tauri::command]
-pub fn delete_project(id: i64, db: State<DbConnection>) -> Result<(), String> {
+pub fn preview_project_deletion(
+    id: i64,
+    policy: Option<ProjectDeletionPolicy>,
+    db: State<DbConnection>,
+) -> Result<ProjectDeletionPreview, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let policy = policy.unwrap_or(ProjectDeletionPolicy::Orphan);
+    queries::preview_project_deletion(&conn, id, &policy).map_err(|e| e.to_string())
+}
+
+/// Supprime un projet et ses sous-projets
+///
+/// # Arguments
+/// * `id` - ID du projet à supprimer
+/// * `policy` - Sort réservé aux tâches : orphan (par défaut), move_tasks ou delete_tasks
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn delete_project(
+    id: i64,
+    policy: Option<ProjectDeletionPolicy>,
+    db: State<DbConnection>,
+) -> Result<ProjectDeletionPreview, String> {
     let conn = db.get_connection();
     let conn = conn.lock().map_err(|e| e.to_string())?;
 
-    queries::delete_project(&conn, id).map_err(|e| e.to_string())
+    let policy = policy.unwrap_or(ProjectDeletionPolicy::Orphan);
+    queries::delete_project(&conn, id, &policy).map_err(|e| e.to_string())
 }
@@ -0,0 +1,97 @@
+// Commandes Tauri pour la génération de rapports de période
+use crate::db::{queries, DbConnection, ReportFormat, SummaryGranularity};
+use crate::reports;
+use tauri::State;
+
+/// Génère le rapport d'une période et l'écrit dans un fichier
+///
+/// Le modèle personnalisé du format est utilisé s'il existe, sinon le modèle intégré.
+///
+/// # Arguments
+/// * `period` - Taille de la période (day, week, month, year)
+/// * `format` - Format de sortie (markdown, html)
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `date` - Jour local de la période (aujourd'hui par défaut, format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn generate_report(
+    period: SummaryGranularity,
+    format: ReportFormat,
+    path: String,
+    date: Option<String>,
+    db: State<DbConnection>,
+) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    let date = match date {
+        Some(date) => queries::parse_input_date(&date).map_err(|e| e.to_string())?,
+        None => today,
+    };
+    let data =
+        queries::get_report_data(&conn, period, date, today).map_err(|e| e.to_string())?;
+    let template = queries::get_report_template(&conn, format)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| reports::default_template(format).to_string());
+    drop(conn); // Libère le lock avant l'écriture du fichier
+
+    let content = reports::render_report(&data, format, &template);
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write report to {}: {}", path, e))?;
+
+    Ok(path)
+}
+
+/// Récupère le modèle de rapport utilisé pour un format (personnalisé ou intégré)
+///
+/// # Arguments
+/// * `format` - Format du rapport
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_report_template(format: ReportFormat, db: State<DbConnection>) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    Ok(queries::get_report_template(&conn, format)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| reports::default_template(format).to_string()))
+}
+
+/// Remplace le modèle de rapport d'un format
+///
+/// # Arguments
+/// * `format` - Format du rapport
+/// * `content` - Contenu du modèle (variables `{{nom}}`)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn set_report_template(
+    format: ReportFormat,
+    content: String,
+    db: State<DbConnection>,
+) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::set_report_template(&conn, format, &content).map_err(|e| e.to_string())
+}
+
+/// Rétablit le modèle intégré d'un format et le retourne
+///
+/// # Arguments
+/// * `format` - Format du rapport
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn reset_report_template(
+    format: ReportFormat,
+    db: State<DbConnection>,
+) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::delete_report_template(&conn, format).map_err(|e| e.to_string())?;
+
+    Ok(reports::default_template(format).to_string())
+}
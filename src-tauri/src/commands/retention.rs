@@ -0,0 +1,71 @@
+// Commandes Tauri pour la rétention et la purge de l'historique
+use crate::db::backup::{self, DefaultBackupDir};
+use crate::db::{queries, DbConnection, PurgeReport, RetentionSettings};
+use tauri::State;
+
+/// Récupère les règles de rétention de l'historique
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_retention_settings(db: State<DbConnection>) -> Result<RetentionSettings, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_retention_settings(&conn).map_err(|e| e.to_string())
+}
+
+/// Met à jour les règles de rétention de l'historique
+///
+/// # Arguments
+/// * `settings` - Durées de rétention en mois (absentes = conservation illimitée)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn update_retention_settings(
+    settings: RetentionSettings,
+    db: State<DbConnection>,
+) -> Result<RetentionSettings, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::update_retention_settings(&conn, &settings).map_err(|e| e.to_string())
+}
+
+/// Calcule ce que supprimerait la purge de l'historique, sans rien modifier
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn preview_purge(db: State<DbConnection>) -> Result<PurgeReport, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    queries::purge_history(&conn, today, false).map_err(|e| e.to_string())
+}
+
+/// Purge l'historique selon les règles de rétention
+///
+/// Une sauvegarde est prise avant la purge.
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+#[tauri::command]
+pub fn purge_history(
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<PurgeReport, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let dir = backup::backup_directory(&conn, &default_dir.path()).map_err(|e| e.to_string())?;
+    backup::create_backup(&conn, &dir).map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    queries::purge_history(&conn, today, true).map_err(|e| e.to_string())
+}
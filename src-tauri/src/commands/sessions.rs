@@ -8,12 +8,14 @@ use tauri::State;
 /// * `task_id` - ID de la tâche associée (optionnel)
 /// * `duration_minutes` - Durée de la session en minutes
 /// * `session_type` - Type de session (work, short_break, long_break)
+/// * `utc_offset_minutes` - Décalage UTC en minutes (celui de la machine par défaut)
 /// * `db` - État partagé contenant la connexion à la base de données
 #[tauri::command]
 pub fn create_session(
     task_id: Option<i64>,
     duration_minutes: i32,
     session_type: SessionType,
+    utc_offset_minutes: Option<i32>,
     db: State<DbConnection>,
 ) -> Result<PomodoroSession, String> {
     let conn = db.get_connection();
@@ -23,6 +25,7 @@ pub fn create_session(
         task_id,
         duration_minutes,
         session_type,
+        utc_offset_minutes,
     };
 
     queries::create_session(&conn, &input).map_err(|e| e.to_string())
@@ -67,7 +70,25 @@ pub fn interrupt_session(id: i64, db: State<DbConnection>) -> Result<PomodoroSes
     queries::interrupt_session(&conn, id).map_err(|e| e.to_string())
 }
 
-/// Récupère les sessions d'une plage de dates
+/// Enregistre la note de concentration (1 à 5) d'une session
+///
+/// # Arguments
+/// * `id` - ID de la session
+/// * `rating` - Note de 1 à 5, ou None pour l'effacer
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn rate_session(
+    id: i64,
+    rating: Option<i32>,
+    db: State<DbConnection>,
+) -> Result<PomodoroSession, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::rate_session(&conn, id, rating).map_err(|e| e.to_string())
+}
+
+/// Récupère les sessions d'une plage de dates (jours locaux)
 ///
 /// # Arguments
 /// * `start_date` - Date de début (format ISO: YYYY-MM-DD)
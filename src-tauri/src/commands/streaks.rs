@@ -0,0 +1,70 @@
+// Commandes Tauri pour l'objectif quotidien, les jours de repos et les séries
+use crate::db::{queries, DbConnection, RestDay, StreakSummary};
+use tauri::State;
+
+/// Récupère la série en cours, la plus longue série et l'historique des jours
+///
+/// L'objectif quotidien et les jours de repos hebdomadaires sont lus dans les paramètres
+///
+/// # Arguments
+/// * `start_date` - Début de l'historique (format ISO: YYYY-MM-DD, première activité par défaut)
+/// * `end_date` - Fin de l'historique (aujourd'hui par défaut)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_streaks(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    db: State<DbConnection>,
+) -> Result<StreakSummary, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    queries::get_streaks(&conn, start_date.as_deref(), end_date.as_deref(), today)
+        .map_err(|e| e.to_string())
+}
+
+/// Récupère les jours de repos ponctuels
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_rest_days(db: State<DbConnection>) -> Result<Vec<RestDay>, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_rest_days(&conn).map_err(|e| e.to_string())
+}
+
+/// Ajoute un jour de repos ponctuel
+///
+/// # Arguments
+/// * `date` - Jour local (format ISO: YYYY-MM-DD)
+/// * `note` - Commentaire optionnel
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn add_rest_day(
+    date: String,
+    note: Option<String>,
+    db: State<DbConnection>,
+) -> Result<RestDay, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::add_rest_day(&conn, &date, note.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Supprime un jour de repos ponctuel
+///
+/// # Arguments
+/// * `date` - Jour local (format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn remove_rest_day(date: String, db: State<DbConnection>) -> Result<(), String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::remove_rest_day(&conn, &date).map_err(|e| e.to_string())
+}
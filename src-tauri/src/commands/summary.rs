@@ -1,23 +1,40 @@
 // Commandes Tauri pour les résumés et analytics
-use crate::db::{queries, DailySummary, DbConnection};
+use crate::db::{
+    queries, ActivityHeatmap, BreakCompliance, DailySummary, DbConnection, PeriodComparison,
+    ProductivityDistribution, ProjectStats, RangeSummary, SummaryGranularity,
+};
 use tauri::State;
 
 /// Récupère le résumé quotidien pour une date donnée
 ///
+/// Les données sont regroupées par jour local, selon le fuseau enregistré
+/// et l'heure de début de journée configurée
+///
 /// # Arguments
-/// * `date` - Date cible (format ISO: YYYY-MM-DD)
+/// * `date` - Jour local cible (format ISO: YYYY-MM-DD)
+/// * `include_breakdown` - Ajoute la répartition par projet et par tâche
 /// * `db` - État partagé contenant la connexion à la base de données
 #[tauri::command]
-pub fn get_daily_summary(date: String, db: State<DbConnection>) -> Result<DailySummary, String> {
+pub fn get_daily_summary(
+    date: String,
+    include_breakdown: Option<bool>,
+    db: State<DbConnection>,
+) -> Result<DailySummary, String> {
     let conn = db.get_connection();
     let conn = conn.lock().map_err(|e| e.to_string())?;
 
-    // Compte les tâches complétées pour cette date
+    // Compte les tâches complétées pour cette date, y compris celles purgées
     let completed_tasks_count: i32 = conn
         .query_row(
-            "SELECT COUNT(*)
-             FROM tasks
-             WHERE DATE(completed_at) = ?1",
+            &format!(
+                "SELECT (SELECT COUNT(*)
+                         FROM tasks
+                         WHERE {} = ?1)
+                        + (SELECT COALESCE(SUM(completed_count), 0)
+                           FROM task_aggregates
+                           WHERE day = ?1)",
+                queries::local_day("completed_at", "completed_utc_offset_minutes")
+            ),
             [&date],
             |row| row.get(0),
         )
@@ -31,11 +48,18 @@ pub fn get_daily_summary(date: String, db: State<DbConnection>) -> Result<DailyS
     let total_focus_minutes =
         queries::calculate_focus_minutes_by_date(&conn, &date).map_err(|e| e.to_string())?;
 
+    let breakdown = if include_breakdown.unwrap_or(false) {
+        Some(queries::get_summary_breakdown(&conn, &date, &date).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
     Ok(DailySummary {
         date,
         completed_tasks_count,
         completed_pomodoros_count,
         total_focus_minutes,
+        breakdown,
     })
 }
 
@@ -44,62 +68,182 @@ pub fn get_daily_summary(date: String, db: State<DbConnection>) -> Result<DailyS
 /// # Arguments
 /// * `start_date` - Date de début (format ISO: YYYY-MM-DD)
 /// * `end_date` - Date de fin (format ISO: YYYY-MM-DD)
+/// * `include_breakdown` - Ajoute la répartition par projet et par tâche de chaque jour
 /// * `db` - État partagé contenant la connexion à la base de données
 #[tauri::command]
 pub fn get_weekly_summary(
     start_date: String,
     end_date: String,
+    include_breakdown: Option<bool>,
     db: State<DbConnection>,
 ) -> Result<Vec<DailySummary>, String> {
     let conn = db.get_connection();
     let conn = conn.lock().map_err(|e| e.to_string())?;
 
-    // Génère une liste de dates dans la plage
-    let mut summaries = Vec::new();
-
-    // Pour simplifier, on utilise SQLite pour générer les dates
-    let mut stmt = conn
-        .prepare(
-            "WITH RECURSIVE dates(date) AS (
-                SELECT DATE(?1)
-                UNION ALL
-                SELECT DATE(date, '+1 day')
-                FROM dates
-                WHERE date < DATE(?2)
-            )
-            SELECT date FROM dates",
+    let summary =
+        queries::get_range_summary(&conn, &start_date, &end_date, SummaryGranularity::Day)
+            .map_err(|e| e.to_string())?;
+
+    let mut breakdowns = if include_breakdown.unwrap_or(false) {
+        Some(
+            queries::get_daily_breakdowns(&conn, &start_date, &end_date)
+                .map_err(|e| e.to_string())?,
         )
-        .map_err(|e| e.to_string())?;
+    } else {
+        None
+    };
+
+    Ok(summary
+        .buckets
+        .into_iter()
+        .map(|bucket| DailySummary {
+            breakdown: breakdowns
+                .as_mut()
+                .map(|b| b.remove(&bucket.period_start).unwrap_or_default()),
+            date: bucket.period_start,
+            completed_tasks_count: bucket.completed_tasks_count,
+            completed_pomodoros_count: bucket.completed_pomodoros_count,
+            total_focus_minutes: bucket.total_focus_minutes,
+        })
+        .collect())
+}
+
+/// Récupère le résumé d'une plage de dates par jour, semaine ISO, mois ou année
+///
+/// # Arguments
+/// * `start_date` - Date de début (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin (format ISO: YYYY-MM-DD)
+/// * `granularity` - Taille des périodes (day, week, month, year)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_range_summary(
+    start_date: String,
+    end_date: String,
+    granularity: SummaryGranularity,
+    db: State<DbConnection>,
+) -> Result<RangeSummary, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_range_summary(&conn, &start_date, &end_date, granularity)
+        .map_err(|e| e.to_string())
+}
 
-    let dates: Vec<String> = stmt
-        .query_map([&start_date, &end_date], |row| row.get(0))
+/// Compare une période (semaine, mois...) à la période équivalente précédente
+///
+/// Une période en cours n'est comparée que sur les jours écoulés.
+///
+/// # Arguments
+/// * `period` - Taille des périodes (day, week, month, year)
+/// * `date` - Jour local de la période (aujourd'hui par défaut, format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_period_comparison(
+    period: SummaryGranularity,
+    date: Option<String>,
+    db: State<DbConnection>,
+) -> Result<PeriodComparison, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
         .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| e.to_string())?;
+        .date();
+    let date = match date {
+        Some(date) => queries::parse_input_date(&date).map_err(|e| e.to_string())?,
+        None => today,
+    };
 
-    // Pour chaque date, calcule le résumé
-    for date in dates {
-        let completed_tasks_count: i32 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM tasks WHERE DATE(completed_at) = ?1",
-                [&date],
-                |row| row.get(0),
-            )
-            .map_err(|e| e.to_string())?;
+    queries::get_period_comparison(&conn, period, date, today).map_err(|e| e.to_string())
+}
 
-        let completed_pomodoros_count =
-            queries::count_completed_sessions_by_date(&conn, &date).map_err(|e| e.to_string())?;
+/// Récupère les statistiques d'un projet (sous-projets inclus)
+///
+/// # Arguments
+/// * `project_id` - ID du projet
+/// * `start_date` - Date de début optionnelle (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin optionnelle (format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_project_stats(
+    project_id: i64,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    db: State<DbConnection>,
+) -> Result<ProjectStats, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
 
-        let total_focus_minutes =
-            queries::calculate_focus_minutes_by_date(&conn, &date).map_err(|e| e.to_string())?;
+    queries::get_project_stats(&conn, project_id, start_date.as_deref(), end_date.as_deref())
+        .map_err(|e| e.to_string())
+}
 
-        summaries.push(DailySummary {
-            date,
-            completed_tasks_count,
-            completed_pomodoros_count,
-            total_focus_minutes,
-        });
-    }
+/// Récupère l'activité quotidienne pour une heatmap (365 derniers jours par défaut)
+///
+/// # Arguments
+/// * `start_date` - Date de début optionnelle (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin optionnelle (aujourd'hui par défaut)
+/// * `project_id` - Filtre optionnel sur un projet (sous-projets inclus)
+/// * `task_id` - Filtre optionnel sur une tâche
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_activity_heatmap(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    project_id: Option<i64>,
+    task_id: Option<i64>,
+    db: State<DbConnection>,
+) -> Result<ActivityHeatmap, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let today = queries::current_local_time(&conn)
+        .map_err(|e| e.to_string())?
+        .date();
+    let end_date = end_date.unwrap_or_else(|| today.format("%Y-%m-%d").to_string());
+    let start_date = start_date.unwrap_or_else(|| {
+        (today - chrono::Duration::days(364)).format("%Y-%m-%d").to_string()
+    });
+
+    queries::get_activity_heatmap(&conn, &start_date, &end_date, project_id, task_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Récupère la répartition de la productivité par heure et jour de la semaine
+///
+/// # Arguments
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+/// * `project_id` - Filtre optionnel sur un projet (sous-projets inclus)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_productivity_distribution(
+    start_date: String,
+    end_date: String,
+    project_id: Option<i64>,
+    db: State<DbConnection>,
+) -> Result<ProductivityDistribution, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::get_productivity_distribution(&conn, &start_date, &end_date, project_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Récupère les indicateurs de respect des pauses sur une plage de dates
+///
+/// # Arguments
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_break_compliance(
+    start_date: String,
+    end_date: String,
+    db: State<DbConnection>,
+) -> Result<BreakCompliance, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
 
-    Ok(summaries)
+    queries::get_break_compliance(&conn, &start_date, &end_date).map_err(|e| e.to_string())
 }
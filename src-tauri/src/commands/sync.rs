@@ -0,0 +1,44 @@
+// Commandes Tauri pour la synchronisation entre appareils
+use crate::db::sync;
+use crate::db::{DbConnection, SyncReport, SyncStatus};
+use tauri::State;
+
+/// Récupère l'état de la synchronisation (appareil, dossier, changements en attente)
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn get_sync_status(db: State<DbConnection>) -> Result<SyncStatus, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    sync::get_sync_status(&conn).map_err(|e| e.to_string())
+}
+
+/// Définit le dossier partagé entre les appareils (Syncthing, etc.)
+///
+/// # Arguments
+/// * `folder` - Chemin absolu du dossier, ou vide pour désactiver la synchronisation
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn set_sync_folder(
+    folder: Option<String>,
+    db: State<DbConnection>,
+) -> Result<SyncStatus, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    sync::set_sync_folder(&conn, folder.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Envoie les modifications locales et applique celles des autres appareils
+///
+/// # Arguments
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn sync_now(db: State<DbConnection>) -> Result<SyncReport, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    sync::sync_now(&conn).map_err(|e| e.to_string())
+}
@@ -0,0 +1,222 @@
+// Commandes Tauri pour l'export et l'import des données
+use crate::db::{
+    queries, CsvExportOptions, DataExport, DbConnection, ImportMode, ImportSummary,
+    TaskImportSummary,
+};
+use crate::formats::{self, FormatError};
+use rusqlite::Connection;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Exporte toutes les données dans un fichier JSON
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn export_data(path: String, db: State<DbConnection>) -> Result<String, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let data = queries::export_data(&conn).map_err(|e| e.to_string())?;
+    drop(conn); // Libère le lock avant l'écriture du fichier
+
+    let json = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    let partial = partial_path(&path);
+    std::fs::write(&partial, json)
+        .and_then(|()| std::fs::rename(&partial, &path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("Failed to write export to {}: {}", path, e)
+        })?;
+
+    Ok(path)
+}
+
+/// Importe un fichier JSON produit par `export_data`
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à lire
+/// * `mode` - Remplacement complet (replace) ou fusion (merge)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn import_data(
+    path: String,
+    mode: ImportMode,
+    db: State<DbConnection>,
+) -> Result<ImportSummary, String> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let data: DataExport =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid export file: {}", e))?;
+
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    queries::import_data(&conn, &data, mode).map_err(|e| e.to_string())
+}
+
+/// Exporte les sessions (avec tâche et projet) dans un fichier CSV
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `options` - Plage de dates, colonnes et séparateurs
+/// * `db` - État partagé contenant la connexion à la base de données
+///
+/// # Returns
+/// Le nombre de sessions exportées
+#[tauri::command]
+pub fn export_sessions_csv(
+    path: String,
+    options: CsvExportOptions,
+    db: State<DbConnection>,
+) -> Result<usize, String> {
+    write_export_file(&db, &path, |conn, writer| {
+        formats::csv::write_sessions_csv(conn, &options, writer)
+    })
+}
+
+/// Exporte les tâches dans un fichier CSV
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `options` - Plage de dates, colonnes et séparateurs
+/// * `db` - État partagé contenant la connexion à la base de données
+///
+/// # Returns
+/// Le nombre de tâches exportées
+#[tauri::command]
+pub fn export_tasks_csv(
+    path: String,
+    options: CsvExportOptions,
+    db: State<DbConnection>,
+) -> Result<usize, String> {
+    write_export_file(&db, &path, |conn, writer| {
+        formats::csv::write_tasks_csv(conn, &options, writer)
+    })
+}
+
+/// Exporte les sessions de travail complétées dans un fichier iCalendar (.ics)
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `start_date` - Jour local de début inclus (format ISO: YYYY-MM-DD)
+/// * `end_date` - Jour local de fin inclus (format ISO: YYYY-MM-DD)
+/// * `db` - État partagé contenant la connexion à la base de données
+///
+/// # Returns
+/// Le nombre d'événements exportés
+#[tauri::command]
+pub fn export_sessions_ics(
+    path: String,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    db: State<DbConnection>,
+) -> Result<usize, String> {
+    write_export_file(&db, &path, |conn, writer| {
+        formats::ical::write_sessions_ics(conn, start_date.as_deref(), end_date.as_deref(), writer)
+    })
+}
+
+/// Importe les tâches VTODO d'un fichier iCalendar
+///
+/// Les tâches déjà importées (même UID) sont mises à jour.
+///
+/// # Arguments
+/// * `path` - Chemin du fichier .ics à lire
+/// * `project_id` - Projet des nouvelles tâches (optionnel)
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn import_ics_tasks(
+    path: String,
+    project_id: Option<i64>,
+    db: State<DbConnection>,
+) -> Result<TaskImportSummary, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    formats::ical::import_ics_todos(&conn, &content, project_id).map_err(|e| e.to_string())
+}
+
+/// Exporte les tâches dans un fichier todo.txt
+///
+/// # Arguments
+/// * `path` - Chemin du fichier à écrire (écrasé s'il existe)
+/// * `include_completed` - Inclut les tâches complétées (par défaut: non)
+/// * `db` - État partagé contenant la connexion à la base de données
+///
+/// # Returns
+/// Le nombre de tâches exportées
+#[tauri::command]
+pub fn export_tasks_todotxt(
+    path: String,
+    include_completed: Option<bool>,
+    db: State<DbConnection>,
+) -> Result<usize, String> {
+    write_export_file(&db, &path, |conn, writer| {
+        formats::todotxt::write_todotxt(conn, include_completed.unwrap_or(false), writer)
+    })
+}
+
+/// Importe un fichier todo.txt
+///
+/// Les tâches de même titre et de même projet sont mises à jour au lieu
+/// d'être dupliquées.
+///
+/// # Arguments
+/// * `path` - Chemin du fichier todo.txt à lire
+/// * `db` - État partagé contenant la connexion à la base de données
+#[tauri::command]
+pub fn import_tasks_todotxt(
+    path: String,
+    db: State<DbConnection>,
+) -> Result<TaskImportSummary, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    formats::todotxt::import_todotxt(&conn, &content).map_err(|e| e.to_string())
+}
+
+/// Écrit un export ligne par ligne dans `<path>.partial`, renommé une fois complet
+///
+/// Un export qui échoue laisse intact le fichier de destination existant.
+fn write_export_file(
+    db: &DbConnection,
+    path: &str,
+    write: impl FnOnce(&Connection, &mut BufWriter<File>) -> Result<usize, FormatError>,
+) -> Result<usize, String> {
+    let conn = db.get_connection();
+    let conn = conn.lock().map_err(|e| e.to_string())?;
+
+    let partial = partial_path(path);
+    let file = File::create(&partial).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+
+    let written = write(&conn, &mut writer).map_err(|e| e.to_string()).and_then(|count| {
+        // Le fichier est vidé et fermé avant d'être renommé
+        let file = writer
+            .into_inner()
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        drop(file);
+        std::fs::rename(&partial, path)
+            .map(|()| count)
+            .map_err(|e| format!("Failed to write {}: {}", path, e))
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written
+}
+
+/// Fichier temporaire d'un export en cours d'écriture
+fn partial_path(path: &str) -> PathBuf {
+    PathBuf::from(format!("{}.partial", path))
+}
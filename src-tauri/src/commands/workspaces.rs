@@ -0,0 +1,71 @@
+// Commandes Tauri pour la gestion des espaces de travail
+use crate::db::backup::DefaultBackupDir;
+use crate::db::workspaces::WorkspaceManager;
+use crate::db::{DbConnection, Workspace, WorkspaceRegistry};
+use tauri::State;
+
+/// Liste les espaces de travail et l'espace actif
+///
+/// # Arguments
+/// * `workspaces` - Gestionnaire des espaces de travail
+#[tauri::command]
+pub fn list_workspaces(workspaces: State<WorkspaceManager>) -> Result<WorkspaceRegistry, String> {
+    Ok(workspaces.registry())
+}
+
+/// Crée un espace de travail avec une base vide
+///
+/// # Arguments
+/// * `name` - Nom de l'espace (unique)
+/// * `workspaces` - Gestionnaire des espaces de travail
+#[tauri::command]
+pub fn create_workspace(
+    name: String,
+    workspaces: State<WorkspaceManager>,
+) -> Result<Workspace, String> {
+    workspaces.create(&name).map_err(|e| e.to_string())
+}
+
+/// Renomme un espace de travail
+///
+/// # Arguments
+/// * `id` - Identifiant de l'espace
+/// * `name` - Nouveau nom (unique)
+/// * `workspaces` - Gestionnaire des espaces de travail
+#[tauri::command]
+pub fn rename_workspace(
+    id: String,
+    name: String,
+    workspaces: State<WorkspaceManager>,
+) -> Result<Workspace, String> {
+    workspaces.rename(&id, &name).map_err(|e| e.to_string())
+}
+
+/// Active un espace de travail : toutes les commandes utilisent ensuite sa base
+///
+/// # Arguments
+/// * `id` - Identifiant de l'espace
+/// * `workspaces` - Gestionnaire des espaces de travail
+/// * `db` - État partagé contenant la connexion à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut
+#[tauri::command]
+pub fn switch_workspace(
+    id: String,
+    workspaces: State<WorkspaceManager>,
+    db: State<DbConnection>,
+    default_dir: State<DefaultBackupDir>,
+) -> Result<Workspace, String> {
+    workspaces
+        .switch(&id, &db, &default_dir)
+        .map_err(|e| e.to_string())
+}
+
+/// Supprime un espace de travail inactif et sa base
+///
+/// # Arguments
+/// * `id` - Identifiant de l'espace
+/// * `workspaces` - Gestionnaire des espaces de travail
+#[tauri::command]
+pub fn delete_workspace(id: String, workspaces: State<WorkspaceManager>) -> Result<(), String> {
+    workspaces.delete(&id).map_err(|e| e.to_string())
+}
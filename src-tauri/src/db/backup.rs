@@ -0,0 +1,461 @@
+// Sauvegardes de la base avec l'API de sauvegarde en ligne de SQLite
+//
+// Une sauvegarde est prise au démarrage puis selon l'intervalle configuré.
+// Les fichiers `tomatotask-AAAAMMJJ-HHMMSS.db` sont ensuite élagués : seule
+// la plus récente de chaque jour, semaine et mois récents est conservée.
+use crate::db::connection::open;
+use crate::db::encryption::is_locked;
+use crate::db::migrations::{get_schema_version, run_migrations};
+use crate::db::models::BackupInfo;
+use crate::db::queries::get_backup_settings;
+use crate::db::sync;
+use crate::db::DbConnection;
+use chrono::{Datelike, Local, NaiveDateTime};
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+/// Préfixe des fichiers de sauvegarde
+const FILE_PREFIX: &str = "tomatotask-";
+
+/// Extension des fichiers de sauvegarde
+const FILE_EXTENSION: &str = ".db";
+
+/// Horodatage local contenu dans le nom des fichiers
+const FILE_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Nombre de jours, semaines et mois pour lesquels une sauvegarde est conservée
+const KEEP_DAILY: usize = 7;
+const KEEP_WEEKLY: usize = 4;
+const KEEP_MONTHLY: usize = 12;
+
+/// Délai entre deux vérifications du planificateur
+const SCHEDULER_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Erreur de sauvegarde ou de restauration
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Sauvegarde introuvable ou incompatible
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Répertoire de sauvegarde par défaut, partagé comme état de l'application
+///
+/// Il dépend de l'espace de travail actif et change avec lui.
+#[derive(Clone)]
+pub struct DefaultBackupDir(Arc<Mutex<PathBuf>>);
+
+impl DefaultBackupDir {
+    pub fn new(path: PathBuf) -> Self {
+        Self(Arc::new(Mutex::new(path)))
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn set(&self, path: PathBuf) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = path;
+    }
+}
+
+/// Retourne le répertoire de sauvegarde configuré, ou celui par défaut
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `default_dir` - Répertoire par défaut de l'application
+pub fn backup_directory(conn: &Connection, default_dir: &Path) -> Result<PathBuf, BackupError> {
+    let settings = get_backup_settings(conn)?;
+    Ok(settings
+        .directory
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_dir.to_path_buf()))
+}
+
+/// Crée une sauvegarde cohérente de la base, même pendant son utilisation
+///
+/// La copie est écrite dans un fichier temporaire puis renommée : un fichier
+/// `.db` du répertoire est toujours une sauvegarde complète. La sauvegarde
+/// d'une base chiffrée est chiffrée avec la même clé.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `dir` - Répertoire des sauvegardes (créé s'il n'existe pas)
+pub fn create_backup(conn: &Connection, dir: &Path) -> Result<BackupInfo, BackupError> {
+    std::fs::create_dir_all(dir)?;
+
+    let path = dir.join(file_name(Local::now().naive_local()));
+    let partial = path.with_extension("db.partial");
+    #[cfg(feature = "sqlcipher")]
+    let copied = crate::db::encryption::export_copy(conn, &partial, None);
+    #[cfg(not(feature = "sqlcipher"))]
+    let copied = conn.backup(rusqlite::DatabaseName::Main, &partial, None);
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e.into());
+    }
+    std::fs::rename(&partial, &path)?;
+
+    backup_info(&path)
+        .ok_or_else(|| BackupError::Invalid(format!("Backup not found: {}", path.display())))
+}
+
+/// Liste les sauvegardes d'un répertoire, de la plus récente à la plus ancienne
+///
+/// # Arguments
+/// * `dir` - Répertoire des sauvegardes
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>, BackupError> {
+    Ok(backup_files(dir)?
+        .iter()
+        .filter_map(|(path, _)| backup_info(path))
+        .collect())
+}
+
+/// Supprime les sauvegardes qui ne sont plus nécessaires à la rotation
+///
+/// Sont conservées les plus récentes de chacun des 7 derniers jours, des
+/// 4 dernières semaines et des 12 derniers mois ayant une sauvegarde.
+///
+/// # Arguments
+/// * `dir` - Répertoire des sauvegardes
+///
+/// # Retourne
+/// Le nombre de sauvegardes supprimées
+pub fn rotate_backups(dir: &Path) -> Result<usize, BackupError> {
+    let files = backup_files(dir)?;
+    let timestamps: Vec<NaiveDateTime> = files.iter().map(|(_, timestamp)| *timestamp).collect();
+    let retained = retained_backups(&timestamps);
+
+    let mut deleted = 0;
+    for (path, timestamp) in &files {
+        if !retained.contains(timestamp) {
+            std::fs::remove_file(path)?;
+            deleted += 1;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Remplace le contenu de la base par celui d'une sauvegarde
+///
+/// La sauvegarde doit provenir de l'application et ne pas avoir un schéma
+/// plus récent que la base actuelle ; un schéma plus ancien est migré après
+/// la restauration. L'état actuel est d'abord sauvegardé pour pouvoir
+/// annuler la restauration.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `dir` - Répertoire des sauvegardes
+/// * `file_name` - Nom du fichier de sauvegarde (tel que retourné par `list_backups`)
+/// * `passphrase` - Phrase secrète de la base, si elle est chiffrée
+///
+/// # Retourne
+/// La sauvegarde de l'état précédant la restauration
+pub fn restore_backup(
+    conn: &mut Connection,
+    dir: &Path,
+    file_name: &str,
+    passphrase: Option<&str>,
+) -> Result<BackupInfo, BackupError> {
+    let path = dir.join(file_name);
+    if parse_file_name(file_name).is_none() || !path.is_file() {
+        return Err(BackupError::Invalid(format!("Backup not found: {}", file_name)));
+    }
+
+    // Travaille sur une copie : la version vérifiée est bien celle restaurée,
+    // même si la sauvegarde de sécurité porte le même nom
+    let copy = path.with_extension("db.restoring");
+    std::fs::copy(&path, &copy)?;
+    let restored = restore_from_copy(conn, dir, file_name, &copy, passphrase);
+    let _ = std::fs::remove_file(&copy);
+
+    restored
+}
+
+fn restore_from_copy(
+    conn: &mut Connection,
+    dir: &Path,
+    file_name: &str,
+    copy: &Path,
+    passphrase: Option<&str>,
+) -> Result<BackupInfo, BackupError> {
+    // Une base chiffrée ne peut être copiée que depuis une base de même clé
+    let backup = open(copy.to_path_buf(), passphrase)?;
+    let backup_version = get_schema_version(&backup)?;
+    let current_version = get_schema_version(conn)?;
+    if backup_version == 0 {
+        return Err(BackupError::Invalid(format!(
+            "{} is not a TomatoTask database",
+            file_name
+        )));
+    }
+    if backup_version > current_version {
+        return Err(BackupError::Invalid(format!(
+            "Backup schema version {} is newer than this app's ({})",
+            backup_version, current_version
+        )));
+    }
+
+    let safety_backup = create_backup(conn, dir)?;
+    Backup::new(&backup, conn)?.run_to_completion(100, Duration::ZERO, None)?;
+    run_migrations(conn)?;
+    // Les changements publiés depuis la sauvegarde portent les numéros suivants
+    sync::renew_device_id(conn)?;
+
+    Ok(safety_backup)
+}
+
+/// Lance le thread des sauvegardes automatiques
+///
+/// Une sauvegarde est prise immédiatement, puis dès que la dernière date de
+/// plus de l'intervalle configuré. Les erreurs sont journalisées sans
+/// interrompre l'application.
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+/// * `default_dir` - Répertoire de sauvegarde par défaut (suit l'espace de travail actif)
+pub fn spawn_backup_scheduler(db: DbConnection, default_dir: DefaultBackupDir) {
+    std::thread::spawn(move || {
+        let mut at_startup = true;
+        loop {
+            match run_scheduled_backup(&db, &default_dir.path(), at_startup) {
+                Ok(Some(backup)) => log::info!("Database backed up to {}", backup.path),
+                Ok(None) => {}
+                Err(e) => log::error!("Automatic backup failed: {}", e),
+            }
+            at_startup = false;
+            std::thread::sleep(SCHEDULER_CHECK_INTERVAL);
+        }
+    });
+}
+
+/// Prend une sauvegarde si elle est due, puis applique la rotation
+fn run_scheduled_backup(
+    db: &DbConnection,
+    default_dir: &Path,
+    at_startup: bool,
+) -> Result<Option<BackupInfo>, BackupError> {
+    // Une base chiffrée est sauvegardée une fois déverrouillée
+    if is_locked(db) {
+        return Ok(None);
+    }
+
+    let conn = db.get_connection();
+    let conn = conn
+        .lock()
+        .map_err(|e| BackupError::Invalid(format!("Failed to lock database connection: {}", e)))?;
+
+    let interval_hours = get_backup_settings(&conn)?.interval_hours;
+    if interval_hours == 0 {
+        return Ok(None);
+    }
+    let dir = backup_directory(&conn, default_dir)?;
+    let latest = backup_files(&dir)?.first().map(|(_, timestamp)| *timestamp);
+    let due = latest.map_or(true, |latest| {
+        Local::now().naive_local() - latest >= chrono::Duration::hours(i64::from(interval_hours))
+    });
+    if !at_startup && !due {
+        return Ok(None);
+    }
+
+    let backup = create_backup(&conn, &dir)?;
+    drop(conn); // Libère le lock avant la rotation
+    rotate_backups(&dir)?;
+
+    Ok(Some(backup))
+}
+
+/// Identifie le jour, la semaine ISO ou le mois d'un horodatage
+type PeriodKey = fn(&NaiveDateTime) -> (i32, u32);
+
+/// Sélectionne les sauvegardes à conserver (la plus récente de chaque période)
+fn retained_backups(timestamps: &[NaiveDateTime]) -> HashSet<NaiveDateTime> {
+    let mut sorted = timestamps.to_vec();
+    sorted.sort_unstable_by_key(|timestamp| Reverse(*timestamp));
+
+    let periods: [(usize, PeriodKey); 3] = [
+        (KEEP_DAILY, |t| (t.year(), t.ordinal())),
+        (KEEP_WEEKLY, |t| (t.iso_week().year(), t.iso_week().week())),
+        (KEEP_MONTHLY, |t| (t.year(), t.month())),
+    ];
+
+    let mut retained = HashSet::new();
+    for (count, period_of) in periods {
+        let mut seen: Vec<(i32, u32)> = Vec::new();
+        for timestamp in &sorted {
+            let period = period_of(timestamp);
+            if seen.last() == Some(&period) {
+                continue;
+            }
+            if seen.len() == count {
+                break;
+            }
+            seen.push(period);
+            retained.insert(*timestamp);
+        }
+    }
+
+    retained
+}
+
+/// Fichiers de sauvegarde d'un répertoire avec leur horodatage, du plus récent au plus ancien
+fn backup_files(dir: &Path) -> Result<Vec<(PathBuf, NaiveDateTime)>, BackupError> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let timestamp = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_file_name);
+        if let Some(timestamp) = timestamp.filter(|_| path.is_file()) {
+            files.push((path, timestamp));
+        }
+    }
+    files.sort_unstable_by_key(|(_, timestamp)| Reverse(*timestamp));
+
+    Ok(files)
+}
+
+/// Décrit un fichier de sauvegarde (None si ce n'en est pas un)
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_str()?;
+    let timestamp = parse_file_name(file_name)?;
+    let schema_version = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| get_schema_version(&conn))
+        .ok()
+        .filter(|version| *version > 0);
+
+    Some(BackupInfo {
+        file_name: file_name.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        created_at: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+        size_bytes: path.metadata().ok()?.len(),
+        schema_version,
+    })
+}
+
+fn file_name(timestamp: NaiveDateTime) -> String {
+    format!(
+        "{}{}{}",
+        FILE_PREFIX,
+        timestamp.format(FILE_TIMESTAMP_FORMAT),
+        FILE_EXTENSION
+    )
+}
+
+fn parse_file_name(file_name: &str) -> Option<NaiveDateTime> {
+    let timestamp = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(FILE_EXTENSION)?;
+    NaiveDateTime::parse_from_str(timestamp, FILE_TIMESTAMP_FORMAT).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tomatotask-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = test_dir("backup-restore");
+        let mut conn = setup_test_db();
+        conn.execute("INSERT INTO tasks (title) VALUES ('Before backup')", []).unwrap();
+        let device_id = |conn: &Connection| -> String {
+            conn.query_row("SELECT device_id FROM sync_state", [], |row| row.get(0))
+                .unwrap()
+        };
+        let device_before = device_id(&conn);
+
+        let backup = create_backup(&conn, &dir).unwrap();
+        assert_eq!(backup.schema_version, Some(get_schema_version(&conn).unwrap()));
+        assert_eq!(list_backups(&dir).unwrap().len(), 1);
+
+        conn.execute("DELETE FROM tasks", []).unwrap();
+        std::fs::write(dir.join("tomatotask-20200101-000000.db"), "not a database").unwrap();
+        assert!(restore_backup(&mut conn, &dir, "tomatotask-20200101-000000.db", None).is_err());
+        assert!(restore_backup(&mut conn, &dir, "../tomatotask.db", None).is_err());
+
+        restore_backup(&mut conn, &dir, &backup.file_name, None).unwrap();
+        let title: String = conn
+            .query_row("SELECT title FROM tasks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "Before backup");
+        assert_ne!(device_id(&conn), device_before);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scheduled_backup_skips_locked_database() {
+        let dir = test_dir("backup-locked");
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("tomatotask.db");
+        // En-tête d'une base SQLCipher (tout sauf l'en-tête SQLite)
+        std::fs::write(&db_path, [0xa5u8; 4096]).unwrap();
+        let backup_dir = dir.join("backups");
+
+        let db = DbConnection::new(db_path).unwrap();
+        assert!(run_scheduled_backup(&db, &backup_dir, true).unwrap().is_none());
+        assert!(!backup_dir.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_keeps_one_backup_per_period() {
+        let at = |month: u32, day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2025, month, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        // Deux sauvegardes par jour du 1er juin au 30 novembre
+        let mut timestamps = Vec::new();
+        let mut day = at(6, 1, 0).date();
+        while day <= at(11, 30, 0).date() {
+            timestamps.push(day.and_hms_opt(9, 0, 0).unwrap());
+            timestamps.push(day.and_hms_opt(18, 0, 0).unwrap());
+            day = day.succ_opt().unwrap();
+        }
+
+        let retained = retained_backups(&timestamps);
+        // 7 jours (24-30 nov.), plus les dimanches 23, 16 et 9 nov. pour les
+        // semaines, plus les derniers jours de juin à octobre pour les mois
+        assert_eq!(retained.len(), 7 + 3 + 5);
+        assert!(retained.contains(&at(11, 30, 18)));
+        assert!(!retained.contains(&at(11, 30, 9)));
+        assert!(retained.contains(&at(11, 24, 18)));
+        assert!(retained.contains(&at(11, 23, 18)));
+        assert!(!retained.contains(&at(11, 22, 18)));
+        assert!(retained.contains(&at(11, 9, 18)));
+        assert!(!retained.contains(&at(11, 2, 18)));
+        assert!(retained.contains(&at(6, 30, 18)));
+        assert!(!retained.contains(&at(6, 29, 18)));
+    }
+}
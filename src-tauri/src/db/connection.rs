@@ -1,11 +1,15 @@
 // Gestionnaire de connexion SQLite
+use crate::db::encryption::is_encrypted;
+use crate::db::migrations::run_migrations;
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
 
 /// Structure encapsulant la connexion SQLite thread-safe
 pub struct DbConnection {
     conn: Arc<Mutex<Connection>>,
+    /// Phrase secrète de la base ouverte, si elle est chiffrée et déverrouillée
+    passphrase: Arc<Mutex<Option<String>>>,
 }
 
 impl DbConnection {
@@ -14,16 +18,90 @@ impl DbConnection {
     /// # Arguments
     /// * `db_path` - Chemin vers le fichier de base de données
     pub fn new(db_path: PathBuf) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-
-        // Active les clés étrangères (désactivées par défaut dans SQLite)
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(open(db_path, None)?)),
+            passphrase: Arc::new(Mutex::new(None)),
+        })
+    }
 
+    /// Ouvre une base chiffrée avec SQLCipher
+    ///
+    /// # Arguments
+    /// * `db_path` - Chemin vers le fichier de base de données
+    /// * `passphrase` - Phrase secrète de la base
+    #[cfg(feature = "sqlcipher")]
+    #[allow(dead_code)]
+    pub fn new_with_passphrase(db_path: PathBuf, passphrase: &str) -> Result<Self> {
         Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
+            conn: Arc::new(Mutex::new(open(db_path, Some(passphrase))?)),
+            passphrase: Arc::new(Mutex::new(Some(passphrase.to_string()))),
         })
     }
 
+    /// Remplace la base ouverte par une autre, migrée au préalable
+    ///
+    /// Les opérations en cours se terminent sur l'ancienne base et les
+    /// suivantes utilisent la nouvelle. En cas d'erreur, la base actuelle
+    /// reste ouverte. Une base chiffrée reste verrouillée (et non migrée)
+    /// jusqu'à `unlock`.
+    ///
+    /// # Arguments
+    /// * `db_path` - Chemin vers le fichier de la nouvelle base
+    pub fn switch_to(&self, db_path: PathBuf) -> Result<()> {
+        self.replace(db_path, None)
+    }
+
+    /// Rouvre la base actuelle avec sa phrase secrète, puis la migre
+    ///
+    /// # Arguments
+    /// * `passphrase` - Phrase secrète de la base
+    pub fn unlock(&self, passphrase: &str) -> Result<()> {
+        let db_path = self
+            .path()
+            .ok_or(rusqlite::Error::InvalidPath(PathBuf::new()))?;
+        self.replace(db_path, Some(passphrase))
+    }
+
+    /// Chemin du fichier de la base ouverte (None pour une base en mémoire)
+    pub fn path(&self) -> Option<PathBuf> {
+        let conn = self.conn.lock().unwrap_or_else(PoisonError::into_inner);
+        conn.path()
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Phrase secrète de la base ouverte, si elle est chiffrée et déverrouillée
+    pub fn passphrase(&self) -> Option<String> {
+        self.passphrase
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn set_passphrase(&self, passphrase: Option<&str>) {
+        *self
+            .passphrase
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = passphrase.map(str::to_string);
+    }
+
+    fn replace(&self, db_path: PathBuf, passphrase: Option<&str>) -> Result<()> {
+        let locked = passphrase.is_none() && is_encrypted(&db_path);
+        let conn = open(db_path, passphrase)?;
+        if !locked {
+            run_migrations(&conn)?;
+        }
+
+        // Un lock empoisonné est récupéré : la connexion est de toute façon remplacée
+        let mut current = self.conn.lock().unwrap_or_else(PoisonError::into_inner);
+        *current = conn;
+        self.set_passphrase(passphrase);
+        drop(current);
+        self.conn.clear_poison();
+
+        Ok(())
+    }
+
     /// Crée une connexion en mémoire (pour les tests)
     #[allow(dead_code)]
     pub fn new_in_memory() -> Result<Self> {
@@ -32,6 +110,7 @@ impl DbConnection {
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            passphrase: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -41,10 +120,26 @@ impl DbConnection {
     }
 }
 
+/// Ouvre une base et active les clés étrangères (désactivées par défaut dans SQLite)
+///
+/// Avec une phrase secrète, la clé SQLCipher est appliquée puis vérifiée par
+/// une première lecture (une mauvaise clé échoue avec `NotADatabase`).
+pub(crate) fn open(db_path: PathBuf, passphrase: Option<&str>) -> Result<Connection> {
+    let conn = Connection::open(db_path)?;
+    if let Some(passphrase) = passphrase {
+        conn.pragma_update(None, "key", passphrase)?;
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))?;
+    }
+    conn.execute("PRAGMA foreign_keys = ON", [])?;
+
+    Ok(conn)
+}
+
 impl Clone for DbConnection {
     fn clone(&self) -> Self {
         Self {
             conn: Arc::clone(&self.conn),
+            passphrase: Arc::clone(&self.passphrase),
         }
     }
 }
@@ -0,0 +1,475 @@
+// Chiffrement de la base au repos avec SQLCipher (feature `sqlcipher`)
+//
+// Une base chiffrée est ouverte verrouillée, puis déverrouillée avec sa
+// phrase secrète. Les copies (chiffrement d'une base existante, sauvegardes,
+// export en clair) passent par `sqlcipher_export` : l'API de sauvegarde de
+// SQLite ne copie qu'entre bases chiffrées avec la même clé.
+//
+// Les fichiers de synchronisation ne sont pas chiffrés : une base chiffrée
+// n'est plus synchronisée, et le fichier de changements de l'appareil est
+// retiré du dossier partagé au chiffrement (voir `sync`).
+use crate::db::backup::{list_backups, BackupError};
+use crate::db::connection::open;
+use crate::db::models::EncryptionStatus;
+use crate::db::sync::{remove_change_file, SyncError};
+use crate::db::DbConnection;
+use rusqlite::{params, Connection, ErrorCode};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::MutexGuard;
+
+/// En-tête des fichiers SQLite en clair
+const PLAIN_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Erreur de chiffrement ou de déverrouillage
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
+    Sync(#[from] SyncError),
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+    /// Opération impossible dans l'état actuel de la base ou de l'application
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Indique si un fichier est une base chiffrée (un fichier absent ou vide ne l'est pas)
+///
+/// # Arguments
+/// * `db_path` - Chemin vers le fichier de base de données
+pub fn is_encrypted(db_path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    let read = std::fs::File::open(db_path).and_then(|mut file| file.read_exact(&mut header));
+    read.is_ok() && &header != PLAIN_HEADER
+}
+
+/// Récupère l'état du chiffrement de la base ouverte
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+pub fn encryption_status(db: &DbConnection) -> EncryptionStatus {
+    let encrypted = db.path().is_some_and(|path| is_encrypted(&path));
+
+    EncryptionStatus {
+        supported: cfg!(feature = "sqlcipher"),
+        encrypted,
+        locked: encrypted && db.passphrase().is_none(),
+    }
+}
+
+/// Déverrouille la base ouverte avec sa phrase secrète, puis la migre
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+/// * `passphrase` - Phrase secrète de la base
+pub fn unlock(db: &DbConnection, passphrase: &str) -> Result<(), EncryptionError> {
+    ensure_supported()?;
+
+    db.unlock(passphrase).map_err(database_error)
+}
+
+/// Chiffre la base ouverte, jusque-là en clair, ainsi que ses sauvegardes
+///
+/// La base est exportée dans un fichier chiffré qui remplace l'original,
+/// puis rouverte avec la phrase secrète. Elle ne peut plus être synchronisée :
+/// la synchronisation est désactivée et le fichier de changements de cet
+/// appareil, en clair, est supprimé du dossier partagé.
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+/// * `passphrase` - Nouvelle phrase secrète (non vide)
+/// * `backup_dir` - Répertoire des sauvegardes de la base
+///
+/// # Retourne
+/// Le nombre de sauvegardes chiffrées
+pub fn encrypt_database(
+    db: &DbConnection,
+    passphrase: &str,
+    backup_dir: &Path,
+) -> Result<usize, EncryptionError> {
+    ensure_supported()?;
+    validate_passphrase(passphrase)?;
+    let path = database_path(db)?;
+    if is_encrypted(&path) {
+        return Err(EncryptionError::Invalid(
+            "The database is already encrypted".to_string(),
+        ));
+    }
+
+    let conn = db.get_connection();
+    let mut conn = lock(&conn)?;
+    remove_change_file(&conn)?;
+    let encrypted = path.with_extension("db.encrypting");
+    export_copy(&conn, &encrypted, Some(passphrase))?;
+
+    // Ferme la base avant de remplacer son fichier, puis la rouvre quoi qu'il arrive
+    *conn = Connection::open_in_memory()?;
+    let replaced = std::fs::rename(&encrypted, &path);
+    let key = replaced.is_ok().then_some(passphrase);
+    *conn = open(path, key)?;
+    db.set_passphrase(key);
+    replaced?;
+    drop(conn); // Libère le lock avant de traiter les sauvegardes
+
+    encrypt_backups(backup_dir, passphrase)
+}
+
+/// Change la phrase secrète de la base et de ses sauvegardes
+///
+/// Les sauvegardes chiffrées avec une autre phrase secrète sont laissées
+/// telles quelles.
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+/// * `current` - Phrase secrète actuelle
+/// * `new` - Nouvelle phrase secrète (non vide)
+/// * `backup_dir` - Répertoire des sauvegardes de la base
+///
+/// # Retourne
+/// Le nombre de sauvegardes mises à jour
+pub fn change_passphrase(
+    db: &DbConnection,
+    current: &str,
+    new: &str,
+    backup_dir: &Path,
+) -> Result<usize, EncryptionError> {
+    ensure_supported()?;
+    validate_passphrase(new)?;
+    let status = encryption_status(db);
+    if !status.encrypted {
+        return Err(EncryptionError::Invalid(
+            "The database is not encrypted".to_string(),
+        ));
+    }
+    if status.locked {
+        return Err(EncryptionError::Invalid(
+            "The database is locked".to_string(),
+        ));
+    }
+    if db.passphrase().as_deref() != Some(current) {
+        return Err(EncryptionError::WrongPassphrase);
+    }
+
+    let conn = db.get_connection();
+    let conn = lock(&conn)?;
+    conn.pragma_update(None, "rekey", new)?;
+    db.set_passphrase(Some(new));
+    drop(conn); // Libère le lock avant de traiter les sauvegardes
+
+    let mut count = 0;
+    for backup in list_backups(backup_dir)? {
+        let path = PathBuf::from(&backup.path);
+        if !is_encrypted(&path) {
+            continue;
+        }
+        if let Ok(backup) = open(path, Some(current)) {
+            backup.pragma_update(None, "rekey", new)?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Exporte une copie en clair de la base ouverte
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+/// * `path` - Fichier de destination (remplacé s'il existe)
+pub fn export_decrypted(db: &DbConnection, path: &Path) -> Result<(), EncryptionError> {
+    ensure_supported()?;
+
+    let conn = db.get_connection();
+    let conn = lock(&conn)?;
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    // Une clé vide produit une copie en clair
+    export_copy(&conn, &partial, Some(""))?;
+    std::fs::rename(&partial, path)?;
+
+    Ok(())
+}
+
+/// Indique si la base ouverte est chiffrée et attend sa phrase secrète
+///
+/// # Arguments
+/// * `db` - Connexion partagée à la base de données
+pub fn is_locked(db: &DbConnection) -> bool {
+    encryption_status(db).locked
+}
+
+/// Copie la base dans un nouveau fichier avec `sqlcipher_export`
+///
+/// Sans clé, la copie est chiffrée avec celle de la base (en clair si la
+/// base l'est) ; une clé vide produit une copie en clair. Le fichier est
+/// supprimé en cas d'erreur.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `path` - Fichier de destination (remplacé s'il existe)
+/// * `key` - Phrase secrète de la copie
+pub(crate) fn export_copy(
+    conn: &Connection,
+    path: &Path,
+    key: Option<&str>,
+) -> rusqlite::Result<()> {
+    let _ = std::fs::remove_file(path);
+    let target = path.to_string_lossy();
+    match key {
+        Some(key) => conn.execute("ATTACH DATABASE ?1 AS export KEY ?2", params![target, key])?,
+        None => conn.execute("ATTACH DATABASE ?1 AS export", params![target])?,
+    };
+
+    let exported = conn.query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()));
+    let detached = conn.execute("DETACH DATABASE export", []);
+    if exported.is_err() || detached.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    exported?;
+    detached?;
+
+    Ok(())
+}
+
+/// Chiffre les sauvegardes en clair d'un répertoire
+fn encrypt_backups(dir: &Path, passphrase: &str) -> Result<usize, EncryptionError> {
+    let mut count = 0;
+    for backup in list_backups(dir)? {
+        let path = PathBuf::from(&backup.path);
+        if is_encrypted(&path) {
+            continue;
+        }
+
+        let source = open(path.clone(), None)?;
+        let encrypted = path.with_extension("db.encrypting");
+        export_copy(&source, &encrypted, Some(passphrase))?;
+        drop(source);
+        std::fs::rename(&encrypted, &path)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn ensure_supported() -> Result<(), EncryptionError> {
+    if cfg!(feature = "sqlcipher") {
+        Ok(())
+    } else {
+        Err(EncryptionError::Invalid(
+            "This build does not support database encryption (sqlcipher feature)".to_string(),
+        ))
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), EncryptionError> {
+    if passphrase.is_empty() {
+        return Err(EncryptionError::Invalid(
+            "Passphrase cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn database_path(db: &DbConnection) -> Result<PathBuf, EncryptionError> {
+    db.path().ok_or_else(|| {
+        EncryptionError::Invalid("An in-memory database cannot be encrypted".to_string())
+    })
+}
+
+fn lock(
+    conn: &std::sync::Mutex<Connection>,
+) -> Result<MutexGuard<'_, Connection>, EncryptionError> {
+    conn.lock()
+        .map_err(|e| EncryptionError::Invalid(format!("Failed to lock database connection: {}", e)))
+}
+
+/// Une base illisible après application de la clé signale une mauvaise phrase secrète
+fn database_error(e: rusqlite::Error) -> EncryptionError {
+    match e {
+        rusqlite::Error::SqliteFailure(ref error, _) if error.code == ErrorCode::NotADatabase => {
+            EncryptionError::WrongPassphrase
+        }
+        e => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "sqlcipher")]
+    use crate::db::backup::{create_backup, restore_backup};
+    use crate::db::migrations::run_migrations;
+    #[cfg(feature = "sqlcipher")]
+    use crate::db::sync;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tomatotask-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    fn count_tasks(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_locked_database_is_detected_and_not_migrated() {
+        let dir = test_dir("encryption-locked");
+        let plain = dir.join("plain.db");
+        let encrypted = dir.join("encrypted.db");
+        run_migrations(&Connection::open(&plain).unwrap()).unwrap();
+        // Un fichier SQLCipher ne commence pas par l'en-tête SQLite
+        std::fs::write(&encrypted, [0xa5u8; 4096]).unwrap();
+        std::fs::write(dir.join("empty.db"), []).unwrap();
+
+        assert!(!is_encrypted(&dir.join("missing.db")));
+        assert!(!is_encrypted(&dir.join("empty.db")));
+        assert!(!is_encrypted(&plain));
+        assert!(is_encrypted(&encrypted));
+
+        let db = DbConnection::new(plain).unwrap();
+        assert!(!is_locked(&db));
+        db.switch_to(encrypted.clone()).unwrap();
+        let status = encryption_status(&db);
+        assert!(status.encrypted && status.locked);
+        assert_eq!(db.path(), Some(encrypted));
+        // Aucune migration n'a été tentée sur la base verrouillée
+        assert!(db
+            .get_connection()
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+            .is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "sqlcipher"))]
+    fn test_encryption_unsupported_without_sqlcipher() {
+        let dir = test_dir("encryption-unsupported");
+        let db_path = dir.join("tomatotask.db");
+        let db = DbConnection::new(db_path.clone()).unwrap();
+        run_migrations(&db.get_connection().lock().unwrap()).unwrap();
+
+        assert!(!encryption_status(&db).supported);
+        assert!(matches!(
+            encrypt_database(&db, "correct horse", &dir),
+            Err(EncryptionError::Invalid(_))
+        ));
+        assert!(matches!(
+            change_passphrase(&db, "correct horse", "battery staple", &dir),
+            Err(EncryptionError::Invalid(_))
+        ));
+        assert!(matches!(
+            unlock(&db, "correct horse"),
+            Err(EncryptionError::Invalid(_))
+        ));
+        assert!(matches!(
+            export_decrypted(&db, &dir.join("export.db")),
+            Err(EncryptionError::Invalid(_))
+        ));
+        assert!(!is_encrypted(&db_path));
+        assert!(!dir.join("export.db").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "sqlcipher")]
+    fn test_encrypt_unlock_and_rekey() {
+        let dir = test_dir("encryption");
+        let db_path = dir.join("tomatotask.db");
+        let backup_dir = dir.join("backups");
+        let db = DbConnection::new(db_path.clone()).unwrap();
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            run_migrations(&conn).unwrap();
+            conn.execute("INSERT INTO tasks (title) VALUES ('Client meeting')", [])
+                .unwrap();
+            create_backup(&conn, &backup_dir).unwrap();
+            sync::set_sync_folder(&conn, dir.join("sync").to_str()).unwrap();
+            sync::sync_now(&conn).unwrap();
+        }
+
+        assert_eq!(
+            encrypt_database(&db, "correct horse", &backup_dir).unwrap(),
+            1
+        );
+        assert!(is_encrypted(&db_path));
+        let backups = list_backups(&backup_dir).unwrap();
+        assert!(backups
+            .iter()
+            .all(|backup| is_encrypted(Path::new(&backup.path))));
+
+        // Les changements seraient écrits en clair dans le dossier partagé
+        {
+            let conn = db.get_connection();
+            let conn = conn.lock().unwrap();
+            let sync_dir = dir.join("sync");
+            let status = sync::get_sync_status(&conn).unwrap();
+            assert_eq!(status.folder, None);
+            assert!(!sync_dir.join(format!("{}.jsonl", status.device_id)).exists());
+            assert!(sync::set_sync_folder(&conn, sync_dir.to_str()).is_err());
+            assert!(sync::sync_folder(&conn, &sync_dir).is_err());
+        }
+
+        // Une nouvelle ouverture reste verrouillée jusqu'à la bonne phrase secrète
+        let reopened = DbConnection::new(db_path.clone()).unwrap();
+        assert!(is_locked(&reopened));
+        assert!(matches!(
+            unlock(&reopened, "wrong"),
+            Err(EncryptionError::WrongPassphrase)
+        ));
+        unlock(&reopened, "correct horse").unwrap();
+        assert_eq!(count_tasks(&reopened.get_connection().lock().unwrap()), 1);
+        drop(reopened);
+
+        assert!(matches!(
+            change_passphrase(&db, "wrong", "battery staple", &backup_dir),
+            Err(EncryptionError::WrongPassphrase)
+        ));
+        assert_eq!(
+            change_passphrase(&db, "correct horse", "battery staple", &backup_dir).unwrap(),
+            1
+        );
+        assert!(DbConnection::new_with_passphrase(db_path.clone(), "battery staple").is_ok());
+
+        // Les sauvegardes restent chiffrées et restaurables
+        {
+            let conn = db.get_connection();
+            let mut conn = conn.lock().unwrap();
+            let backup = create_backup(&conn, &backup_dir).unwrap();
+            assert!(is_encrypted(Path::new(&backup.path)));
+            conn.execute("DELETE FROM tasks", []).unwrap();
+            restore_backup(
+                &mut conn,
+                &backup_dir,
+                &backup.file_name,
+                Some("battery staple"),
+            )
+            .unwrap();
+            assert_eq!(count_tasks(&conn), 1);
+        }
+
+        let export = dir.join("export.db");
+        export_decrypted(&db, &export).unwrap();
+        assert!(!is_encrypted(&export));
+        assert_eq!(count_tasks(&Connection::open(&export).unwrap()), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
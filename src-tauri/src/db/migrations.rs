@@ -1,4 +1,5 @@
 // Gestion des migrations de schéma de base de données
+use crate::db::sync;
 use rusqlite::{Connection, Result};
 
 /// Exécute toutes les migrations nécessaires
@@ -23,11 +24,76 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         set_schema_version(conn, 1)?;
     }
 
+    if current_version < 2 {
+        apply_migration_002(conn)?;
+        set_schema_version(conn, 2)?;
+    }
+
+    if current_version < 3 {
+        apply_migration_003(conn)?;
+        set_schema_version(conn, 3)?;
+    }
+
+    if current_version < 4 {
+        apply_migration_004(conn)?;
+        set_schema_version(conn, 4)?;
+    }
+
+    if current_version < 5 {
+        apply_migration_005(conn)?;
+        set_schema_version(conn, 5)?;
+    }
+
+    if current_version < 6 {
+        apply_migration_006(conn)?;
+        set_schema_version(conn, 6)?;
+    }
+
+    if current_version < 7 {
+        apply_migration_007(conn)?;
+        set_schema_version(conn, 7)?;
+    }
+
+    if current_version < 8 {
+        apply_migration_008(conn)?;
+        set_schema_version(conn, 8)?;
+    }
+
+    if current_version < 9 {
+        apply_migration_009(conn)?;
+        set_schema_version(conn, 9)?;
+    }
+
+    if current_version < 10 {
+        apply_migration_010(conn)?;
+        set_schema_version(conn, 10)?;
+    }
+
+    if current_version < 11 {
+        apply_migration_011(conn)?;
+        set_schema_version(conn, 11)?;
+    }
+
+    if current_version < 12 {
+        apply_migration_012(conn)?;
+        set_schema_version(conn, 12)?;
+    }
+
+    if current_version < 13 {
+        apply_migration_013(conn)?;
+        set_schema_version(conn, 13)?;
+    }
+
+    if current_version < 14 {
+        apply_migration_014(conn)?;
+        set_schema_version(conn, 14)?;
+    }
+
     Ok(())
 }
 
 /// Obtient la version actuelle du schéma
-fn get_schema_version(conn: &Connection) -> Result<i32> {
+pub(crate) fn get_schema_version(conn: &Connection) -> Result<i32> {
     let version: Result<i32> = conn.query_row(
         "SELECT MAX(version) FROM schema_version",
         [],
@@ -157,6 +223,454 @@ fn apply_migration_001(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Migration 002: Statut des projets
+///
+/// Ajoute le statut (active, on_hold, completed, archived) et les dates
+/// de changement de statut à la table projects. Un projet inséré sans date
+/// de statut (import, fixture) prend sa date de création.
+fn apply_migration_002(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE projects ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+            CHECK (status IN ('active', 'on_hold', 'completed', 'archived'))",
+        [],
+    )?;
+
+    // Les projets existants prennent leur date de création comme date de statut
+    conn.execute(
+        "ALTER TABLE projects ADD COLUMN status_changed_at TEXT",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE projects SET status_changed_at = created_at",
+        [],
+    )?;
+    // ALTER TABLE n'accepte pas de valeur par défaut non constante
+    conn.execute(
+        "CREATE TRIGGER projects_default_status_changed_at AFTER INSERT ON projects
+         WHEN NEW.status_changed_at IS NULL
+         BEGIN
+             UPDATE projects SET status_changed_at = COALESCE(NEW.created_at, datetime('now'))
+             WHERE id = NEW.id;
+         END",
+        [],
+    )?;
+
+    conn.execute("ALTER TABLE projects ADD COLUMN completed_at TEXT", [])?;
+    conn.execute("ALTER TABLE projects ADD COLUMN archived_at TEXT", [])?;
+
+    conn.execute(
+        "CREATE INDEX idx_projects_status ON projects(status)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 003: Projets imbriqués
+///
+/// Ajoute `parent_project_id` pour organiser les projets en arborescence.
+/// La suppression d'un parent supprime ses sous-projets (ON DELETE CASCADE),
+/// leurs tâches devenant sans projet.
+fn apply_migration_003(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE projects ADD COLUMN parent_project_id INTEGER
+            REFERENCES projects(id) ON DELETE CASCADE",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX idx_projects_parent ON projects(parent_project_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 004: Objectifs de projet
+///
+/// Crée la table project_goals (cible en Pomodoros ou minutes, par jour,
+/// semaine, mois ou au total)
+fn apply_migration_004(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE project_goals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            metric TEXT NOT NULL,
+            target INTEGER NOT NULL,
+            period TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            CHECK (metric IN ('pomodoros', 'minutes')),
+            CHECK (period IN ('day', 'week', 'month', 'total')),
+            CHECK (target > 0)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX idx_project_goals_project ON project_goals(project_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 005: Jalons de projet
+///
+/// Crée les tables milestones (échéance rattachée à un projet) et
+/// milestone_tasks (tâches liées à un jalon)
+fn apply_migration_005(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE milestones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            description TEXT,
+            start_date TEXT NOT NULL,
+            due_date TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+            CHECK (start_date <= due_date)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE milestone_tasks (
+            milestone_id INTEGER NOT NULL,
+            task_id INTEGER NOT NULL,
+            PRIMARY KEY (milestone_id, task_id),
+            FOREIGN KEY (milestone_id) REFERENCES milestones(id) ON DELETE CASCADE,
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX idx_milestones_project ON milestones(project_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX idx_milestone_tasks_task ON milestone_tasks(task_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 006: Fuseau horaire et début de journée
+///
+/// Enregistre le décalage UTC de chaque session et de chaque complétion de
+/// tâche pour regrouper les données par jour local, et ajoute l'heure de
+/// début de journée configurable. Les lignes existantes prennent le décalage
+/// actuel de la machine, meilleure estimation disponible.
+fn apply_migration_006(conn: &Connection) -> Result<()> {
+    let offset = crate::db::queries::current_utc_offset_minutes();
+
+    conn.execute(
+        "ALTER TABLE pomodoro_sessions ADD COLUMN utc_offset_minutes INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE pomodoro_sessions SET utc_offset_minutes = ?1",
+        [offset],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE tasks ADD COLUMN completed_utc_offset_minutes INTEGER",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE tasks SET completed_utc_offset_minutes = ?1 WHERE completed_at IS NOT NULL",
+        [offset],
+    )?;
+
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN day_start_hour INTEGER NOT NULL DEFAULT 0
+            CHECK (day_start_hour BETWEEN 0 AND 23)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 007: Objectif quotidien et jours de repos
+///
+/// Ajoute l'objectif quotidien et les jours de repos hebdomadaires aux
+/// paramètres, et crée la table rest_days pour les jours de repos ponctuels
+fn apply_migration_007(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN daily_goal_metric TEXT NOT NULL DEFAULT 'pomodoros'
+            CHECK (daily_goal_metric IN ('pomodoros', 'minutes'))",
+        [],
+    )?;
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN daily_goal_target INTEGER NOT NULL DEFAULT 8
+            CHECK (daily_goal_target >= 1)",
+        [],
+    )?;
+    // Liste de jours ISO séparés par des virgules (ex: '6,7')
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN rest_weekdays TEXT NOT NULL DEFAULT ''",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE rest_days (
+            date TEXT PRIMARY KEY,
+            note TEXT
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 008: Note de concentration des sessions
+///
+/// Ajoute une note optionnelle (1 à 5) que l'utilisateur peut donner à une session
+fn apply_migration_008(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE pomodoro_sessions ADD COLUMN focus_rating INTEGER
+            CHECK (focus_rating BETWEEN 1 AND 5)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 009: Modèles de rapports personnalisés
+///
+/// Un modèle par format remplace le modèle intégré à l'application
+fn apply_migration_009(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE report_templates (
+            format TEXT PRIMARY KEY CHECK (format IN ('markdown', 'html')),
+            content TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 010: Échéance, priorité et identifiant externe des tâches
+///
+/// L'identifiant externe (UID iCalendar...) permet de réimporter une tâche
+/// sans créer de doublon
+fn apply_migration_010(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE tasks ADD COLUMN due_date TEXT", [])?;
+    conn.execute(
+        "ALTER TABLE tasks ADD COLUMN priority INTEGER CHECK (priority BETWEEN 1 AND 9)",
+        [],
+    )?;
+    conn.execute("ALTER TABLE tasks ADD COLUMN external_uid TEXT", [])?;
+    conn.execute(
+        "CREATE UNIQUE INDEX idx_tasks_external_uid ON tasks(external_uid)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 011: Étiquettes todo.txt des tâches
+///
+/// Contextes (@contexte) et attributs clé:valeur sans équivalent dans le
+/// modèle, conservés tels quels pour les réexporter
+fn apply_migration_011(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE tasks ADD COLUMN extra_tags TEXT NOT NULL DEFAULT ''",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 012: Paramètres des sauvegardes automatiques
+///
+/// Répertoire (NULL = répertoire par défaut de l'application) et intervalle
+/// entre deux sauvegardes (0 = désactivées)
+fn apply_migration_012(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE settings ADD COLUMN backup_directory TEXT", [])?;
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN backup_interval_hours INTEGER NOT NULL DEFAULT 24
+         CHECK (backup_interval_hours >= 0)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 013: Synchronisation entre appareils
+///
+/// Donne un UUID stable aux projets, tâches et sessions, et crée :
+/// - sync_state: identifiant de l'appareil et dossier de synchronisation (singleton)
+/// - sync_pending: champs modifiés localement depuis la dernière synchronisation
+/// - sync_changes: journal versionné des changements, locaux et reçus
+/// - sync_peers: progression de la lecture des changements de chaque appareil
+///
+/// Toutes les lignes existantes sont marquées à synchroniser.
+fn apply_migration_013(conn: &Connection) -> Result<()> {
+    for table in sync::SYNC_TABLE_NAMES {
+        conn.execute(&format!("ALTER TABLE {table} ADD COLUMN uuid TEXT"), [])?;
+        conn.execute(
+            &format!("UPDATE {table} SET uuid = {}", sync::UUID_EXPRESSION),
+            [],
+        )?;
+        conn.execute(
+            &format!("CREATE UNIQUE INDEX idx_{table}_uuid ON {table}(uuid)"),
+            [],
+        )?;
+    }
+
+    conn.execute(
+        "CREATE TABLE sync_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            device_id TEXT NOT NULL,
+            folder TEXT,
+            applying INTEGER NOT NULL DEFAULT 0,
+            last_exported_seq INTEGER NOT NULL DEFAULT 0,
+            last_synced_at TEXT
+        )",
+        [],
+    )?;
+    conn.execute(
+        &format!(
+            "INSERT INTO sync_state (id, device_id) VALUES (1, {})",
+            sync::UUID_EXPRESSION
+        ),
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE sync_pending (
+            table_name TEXT NOT NULL,
+            row_uuid TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            changed_at TEXT NOT NULL,
+            PRIMARY KEY (table_name, row_uuid, column_name)
+        ) WITHOUT ROWID",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE sync_changes (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL,
+            device_seq INTEGER NOT NULL,
+            table_name TEXT NOT NULL,
+            row_uuid TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            value,
+            changed_at TEXT NOT NULL,
+            base_changed_at TEXT,
+            base_device_id TEXT,
+            UNIQUE (device_id, device_seq)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX idx_sync_changes_field
+         ON sync_changes(table_name, row_uuid, column_name, changed_at)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE sync_peers (
+            device_id TEXT PRIMARY KEY,
+            last_seq INTEGER NOT NULL,
+            last_synced_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Les lignes existantes sont à envoyer, datées de leur dernière modification
+    for (table, modified_at) in [
+        ("projects", "updated_at"),
+        ("tasks", "updated_at"),
+        ("pomodoro_sessions", "COALESCE(completed_at, started_at)"),
+    ] {
+        conn.execute(
+            &format!(
+                "INSERT INTO sync_pending (table_name, row_uuid, column_name, changed_at)
+                 SELECT '{table}', uuid, '*',
+                        COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', {modified_at}),
+                                 strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+                 FROM {table}"
+            ),
+            [],
+        )?;
+    }
+
+    sync::install_triggers(conn)?;
+
+    Ok(())
+}
+
+/// Migration 014: Rétention de l'historique
+///
+/// Ajoute les règles de rétention aux paramètres (NULL = conservation
+/// illimitée) et crée les agrégats journaliers qui remplacent l'historique purgé :
+/// - session_aggregates: sessions de travail regroupées par jour local et par tâche
+///   (avec le projet de la tâche, conservé si la tâche est supprimée)
+/// - task_aggregates: tâches complétées supprimées, par jour local et par projet
+fn apply_migration_014(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN session_retention_months INTEGER
+         CHECK (session_retention_months > 0)",
+        [],
+    )?;
+    conn.execute(
+        "ALTER TABLE settings ADD COLUMN completed_task_retention_months INTEGER
+         CHECK (completed_task_retention_months > 0)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE session_aggregates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            day TEXT NOT NULL,
+            task_id INTEGER,
+            project_id INTEGER,
+            completed_count INTEGER NOT NULL DEFAULT 0,
+            focus_minutes INTEGER NOT NULL DEFAULT 0,
+            interrupted_count INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX idx_session_aggregates_day ON session_aggregates(day)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX idx_session_aggregates_task ON session_aggregates(task_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE task_aggregates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            day TEXT NOT NULL,
+            project_id INTEGER,
+            completed_count INTEGER NOT NULL,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX idx_task_aggregates_day ON task_aggregates(day)",
+        [],
+    )?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +694,8 @@ mod tests {
         assert!(tables.contains(&"tasks".to_string()));
         assert!(tables.contains(&"pomodoro_sessions".to_string()));
         assert!(tables.contains(&"settings".to_string()));
+        assert!(tables.contains(&"project_goals".to_string()));
+        assert!(tables.contains(&"milestones".to_string()));
     }
 
     #[test]
@@ -1,8 +1,12 @@
 // Module de gestion de la base de données SQLite
+pub mod backup;
 pub mod connection;
+pub mod encryption;
 pub mod migrations;
 pub mod models;
 pub mod queries;
+pub mod sync;
+pub mod workspaces;
 
 pub use connection::DbConnection;
 pub use models::*;
@@ -1,5 +1,6 @@
 // Modèles de données Rust pour la base de données
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Représente un projet pour organiser les tâches
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,15 +8,274 @@ pub struct Project {
     pub id: i64,
     pub name: String,
     pub color: Option<String>,
+    pub parent_project_id: Option<i64>,
+    pub status: ProjectStatus,
+    pub status_changed_at: String,
+    pub completed_at: Option<String>,
+    pub archived_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Statut d'un projet
+///
+/// Les projets archivés sont masqués des vues par défaut mais restent
+/// comptabilisés dans les résumés historiques
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    Active,
+    OnHold,
+    Completed,
+    Archived,
+}
+
+impl ProjectStatus {
+    /// Convertit le statut en string pour la base de données
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Active => "active",
+            ProjectStatus::OnHold => "on_hold",
+            ProjectStatus::Completed => "completed",
+            ProjectStatus::Archived => "archived",
+        }
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = String;
+
+    /// Parse une string depuis la base de données
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(ProjectStatus::Active),
+            "on_hold" => Ok(ProjectStatus::OnHold),
+            "completed" => Ok(ProjectStatus::Completed),
+            "archived" => Ok(ProjectStatus::Archived),
+            _ => Err(format!("Invalid project status: {}", s)),
+        }
+    }
+}
+
 /// Input pour créer un nouveau projet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateProjectInput {
     pub name: String,
     pub color: Option<String>,
+    #[serde(default)]
+    pub parent_project_id: Option<i64>,
+}
+
+/// Totaux d'activité d'un projet
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTotals {
+    pub task_count: i32,
+    pub completed_task_count: i32,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+}
+
+/// Nœud de l'arborescence des projets (Area → Project → Sub-project)
+///
+/// `totals` cumule les totaux du projet et de tous ses descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTreeNode {
+    pub project: Project,
+    pub own_totals: ProjectTotals,
+    pub totals: ProjectTotals,
+    pub children: Vec<ProjectTreeNode>,
+}
+
+/// Sort réservé aux tâches d'un projet (et de ses sous-projets) lors de sa suppression
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum ProjectDeletionPolicy {
+    /// Les tâches deviennent sans projet (comportement historique)
+    Orphan,
+    /// Les tâches sont rattachées à un autre projet
+    MoveTasks { target_project_id: i64 },
+    /// Les tâches sont supprimées avec leurs sessions
+    DeleteTasks,
+}
+
+/// Nombre d'éléments affectés par la suppression d'un projet
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDeletionPreview {
+    /// Projet supprimé et ses sous-projets
+    pub project_count: i32,
+    pub task_count: i32,
+    pub session_count: i32,
+}
+
+/// Objectif de focus fixé sur un projet (ex: 8 Pomodoros par semaine)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGoal {
+    pub id: i64,
+    pub project_id: i64,
+    pub metric: GoalMetric,
+    pub target: i32,
+    pub period: GoalPeriod,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input pour créer ou modifier un objectif de projet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGoalInput {
+    pub metric: GoalMetric,
+    pub target: i32,
+    pub period: GoalPeriod,
+}
+
+/// Unité mesurée par un objectif
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalMetric {
+    #[default]
+    Pomodoros,
+    Minutes,
+}
+
+impl GoalMetric {
+    /// Convertit la métrique en string pour la base de données
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalMetric::Pomodoros => "pomodoros",
+            GoalMetric::Minutes => "minutes",
+        }
+    }
+}
+
+impl FromStr for GoalMetric {
+    type Err = String;
+
+    /// Parse une string depuis la base de données
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pomodoros" => Ok(GoalMetric::Pomodoros),
+            "minutes" => Ok(GoalMetric::Minutes),
+            _ => Err(format!("Invalid goal metric: {}", s)),
+        }
+    }
+}
+
+/// Période sur laquelle un objectif est évalué
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalPeriod {
+    Day,
+    Week,
+    Month,
+    Total,
+}
+
+impl GoalPeriod {
+    /// Convertit la période en string pour la base de données
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalPeriod::Day => "day",
+            GoalPeriod::Week => "week",
+            GoalPeriod::Month => "month",
+            GoalPeriod::Total => "total",
+        }
+    }
+}
+
+impl FromStr for GoalPeriod {
+    type Err = String;
+
+    /// Parse une string depuis la base de données
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(GoalPeriod::Day),
+            "week" => Ok(GoalPeriod::Week),
+            "month" => Ok(GoalPeriod::Month),
+            "total" => Ok(GoalPeriod::Total),
+            _ => Err(format!("Invalid goal period: {}", s)),
+        }
+    }
+}
+
+/// État d'avancement d'un objectif par rapport au rythme attendu
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Achieved,
+    OnTrack,
+    Behind,
+    /// Objectif "total" non atteint : sans échéance, il n'a pas de rythme attendu
+    InProgress,
+}
+
+/// Progression d'un objectif sur la période en cours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    pub goal: ProjectGoal,
+    /// Début de la période (None pour un objectif "total")
+    pub period_start: Option<String>,
+    /// Fin de la période, incluse (None pour un objectif "total")
+    pub period_end: Option<String>,
+    pub done: i32,
+    /// Valeur attendue à ce stade de la période pour être dans les temps
+    pub expected: f64,
+    pub percent_complete: f64,
+    pub status: GoalStatus,
+}
+
+/// Jalon d'un projet, avec une échéance et des tâches liées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Milestone {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// Début de la ligne idéale du burndown (format ISO: YYYY-MM-DD)
+    pub start_date: String,
+    pub due_date: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input pour créer ou modifier un jalon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneInput {
+    pub name: String,
+    pub description: Option<String>,
+    /// Date de début (aujourd'hui par défaut à la création)
+    pub start_date: Option<String>,
+    pub due_date: String,
+}
+
+/// Point quotidien des séries burndown / burnup d'un jalon
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BurndownPoint {
+    pub date: String,
+    /// Pomodoros estimés restants en fin de journée (None pour les jours futurs)
+    pub remaining_pomodoros: Option<i32>,
+    /// Pomodoros restants selon la ligne idéale
+    pub ideal_remaining_pomodoros: f64,
+    /// Pomodoros complétés cumulés (burnup, None pour les jours futurs)
+    pub completed_pomodoros: Option<i32>,
+    /// Total estimé du jalon (ligne de périmètre du burnup)
+    pub scope_pomodoros: i32,
+}
+
+/// Séries burndown et burnup d'un jalon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneBurndown {
+    pub milestone: Milestone,
+    pub total_estimated_pomodoros: i32,
+    pub points: Vec<BurndownPoint>,
 }
 
 /// Représente une tâche à accomplir
@@ -32,6 +292,36 @@ pub struct Task {
     pub created_at: String,
     pub updated_at: String,
     pub completed_at: Option<String>,
+    /// Échéance (jour local, format ISO: YYYY-MM-DD)
+    pub due_date: Option<String>,
+    /// Priorité iCalendar (1 = la plus haute ... 9 = la plus basse)
+    pub priority: Option<i32>,
+}
+
+/// Tâche provenant d'un outil externe, identifiée par un UID stable
+#[derive(Debug, Clone)]
+pub struct ExternalTaskInput {
+    pub external_uid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub project_id: Option<i64>,
+    pub due_date: Option<String>,
+    pub priority: Option<i32>,
+    /// Date de complétion (UTC) si la tâche est terminée dans l'outil externe
+    pub completed_at: Option<String>,
+}
+
+/// Bilan d'un import de tâches depuis un format externe
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskImportSummary {
+    pub created_count: i32,
+    pub updated_count: i32,
+    /// Éléments ignorés (sans UID, sans titre ou invalides)
+    pub skipped_count: i32,
+    /// Raison de chaque élément ignoré parce qu'invalide
+    #[serde(default)]
+    pub errors: Vec<String>,
 }
 
 /// Input pour créer une nouvelle tâche
@@ -65,6 +355,10 @@ pub struct PomodoroSession {
     pub duration_minutes: i32,
     pub session_type: SessionType,
     pub interrupted: bool,
+    /// Décalage UTC (en minutes) au démarrage de la session
+    pub utc_offset_minutes: i32,
+    /// Note de concentration donnée par l'utilisateur (1 à 5)
+    pub focus_rating: Option<i32>,
 }
 
 /// Type de session Pomodoro
@@ -104,6 +398,9 @@ pub struct CreateSessionInput {
     pub task_id: Option<i64>,
     pub duration_minutes: i32,
     pub session_type: SessionType,
+    /// Décalage UTC en minutes (celui de la machine par défaut)
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
 }
 
 /// Paramètres de configuration de l'application
@@ -119,6 +416,116 @@ pub struct Settings {
     pub notification_sound: String,
     pub auto_start_breaks: bool,
     pub auto_start_pomodoros: bool,
+    /// Heure (0-23) à laquelle commence une journée pour les résumés
+    #[serde(default)]
+    pub day_start_hour: i32,
+    /// Unité de l'objectif quotidien (pomodoros ou minutes)
+    #[serde(default)]
+    pub daily_goal_metric: GoalMetric,
+    /// Valeur à atteindre chaque jour pour prolonger une série
+    #[serde(default = "default_daily_goal_target")]
+    pub daily_goal_target: i32,
+    /// Jours de repos hebdomadaires (ISO 8601: 1 = lundi ... 7 = dimanche)
+    #[serde(default)]
+    pub rest_weekdays: Vec<u32>,
+}
+
+/// Paramètres des sauvegardes automatiques
+///
+/// Propres à la machine : ils ne font pas partie de `Settings` ni de l'export.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// Répertoire des sauvegardes (None = répertoire par défaut de l'application)
+    pub directory: Option<String>,
+    /// Intervalle entre deux sauvegardes automatiques (0 = désactivées)
+    pub interval_hours: i32,
+}
+
+/// Règles de rétention de l'historique
+///
+/// Propres à la machine : elles ne font pas partie de `Settings` ni de l'export.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionSettings {
+    /// Ancienneté (en mois) au-delà de laquelle les sessions sont regroupées
+    /// en agrégats journaliers (None = conservées en détail)
+    pub session_retention_months: Option<i32>,
+    /// Ancienneté (en mois) au-delà de laquelle les tâches complétées sont
+    /// supprimées (None = conservées)
+    pub completed_task_retention_months: Option<i32>,
+}
+
+/// Résultat d'une purge de l'historique (ou de son aperçu)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    /// Premier jour local conservé en détail pour les sessions (None = règle désactivée)
+    pub session_cutoff: Option<String>,
+    /// Premier jour local de complétion conservé pour les tâches (None = règle désactivée)
+    pub task_cutoff: Option<String>,
+    /// Sessions supprimées, pauses comprises
+    pub deleted_sessions_count: i32,
+    /// Agrégats journaliers créés pour les sessions de travail supprimées
+    pub session_aggregates_count: i32,
+    pub deleted_tasks_count: i32,
+    /// Faux pour un aperçu : rien n'a été modifié
+    pub applied: bool,
+}
+
+/// Fichier de sauvegarde de la base
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    /// Date de la sauvegarde (heure locale)
+    pub created_at: String,
+    pub size_bytes: u64,
+    /// Version du schéma de la sauvegarde (None si le fichier est illisible ou chiffré)
+    pub schema_version: Option<i32>,
+}
+
+/// Objectif quotidien par défaut (8 Pomodoros)
+fn default_daily_goal_target() -> i32 {
+    8
+}
+
+/// Jour de repos ponctuel (vacances, jour férié...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestDay {
+    pub date: String,
+    pub note: Option<String>,
+}
+
+/// État d'un jour vis-à-vis de l'objectif quotidien
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakDay {
+    pub date: String,
+    /// Valeur atteinte dans l'unité de l'objectif
+    pub value: i32,
+    pub goal_met: bool,
+    pub is_rest_day: bool,
+    /// Longueur de la série en cours à la fin de ce jour
+    pub streak_length: i32,
+}
+
+/// Séries de jours ayant atteint l'objectif quotidien
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakSummary {
+    pub daily_goal_metric: GoalMetric,
+    pub daily_goal_target: i32,
+    pub current_streak: i32,
+    /// Premier jour de la série en cours
+    pub current_streak_start: Option<String>,
+    pub longest_streak: i32,
+    pub longest_streak_start: Option<String>,
+    pub longest_streak_end: Option<String>,
+    /// Détail jour par jour de la période demandée
+    pub history: Vec<StreakDay>,
 }
 
 /// Résumé quotidien de productivité
@@ -129,4 +536,611 @@ pub struct DailySummary {
     pub completed_tasks_count: i32,
     pub completed_pomodoros_count: i32,
     pub total_focus_minutes: i32,
+    /// Répartition par projet et par tâche, si demandée
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<SummaryBreakdown>,
+}
+
+/// Temps de focus d'une tâche dans une répartition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskBreakdown {
+    pub task_id: i64,
+    pub title: String,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+}
+
+/// Temps de focus d'un projet (et de ses tâches) dans une répartition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBreakdown {
+    /// None regroupe les tâches sans projet
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+    /// Tâches du projet, par temps de focus décroissant
+    pub tasks: Vec<TaskBreakdown>,
+}
+
+/// Répartition du temps de focus d'une période
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryBreakdown {
+    /// Projets par temps de focus décroissant
+    pub projects: Vec<ProjectBreakdown>,
+    /// Sessions sans tâche associée
+    pub unassigned_pomodoros_count: i32,
+    pub unassigned_focus_minutes: i32,
+}
+
+/// Granularité des périodes d'un résumé
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryGranularity {
+    Day,
+    /// Semaine ISO (du lundi au dimanche)
+    Week,
+    Month,
+    Year,
+}
+
+/// Résumé de productivité d'une période (jour, semaine, mois ou année)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryBucket {
+    /// Premier jour de la période, borné au début de la plage (format ISO: YYYY-MM-DD)
+    pub period_start: String,
+    /// Dernier jour de la période, borné à la fin de la plage
+    pub period_end: String,
+    pub completed_tasks_count: i32,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+}
+
+/// Moyennes par période d'un résumé
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryAverages {
+    pub completed_tasks_count: f64,
+    pub completed_pomodoros_count: f64,
+    pub total_focus_minutes: f64,
+}
+
+/// Évolution d'un indicateur entre deux périodes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDelta {
+    pub current: f64,
+    pub previous: f64,
+    /// Différence absolue (current - previous)
+    pub change: f64,
+    /// Variation relative en pourcentage (None si la période précédente est à zéro)
+    pub percent_change: Option<f64>,
+}
+
+/// Comparaison d'une période avec la période équivalente précédente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodComparison {
+    pub period: SummaryGranularity,
+    pub current_start: String,
+    /// Dernier jour comparé (aujourd'hui si la période est en cours)
+    pub current_end: String,
+    pub previous_start: String,
+    pub previous_end: String,
+    /// La période est en cours : seuls les jours écoulés sont comparés
+    pub partial: bool,
+    pub total_focus_minutes: MetricDelta,
+    pub completed_pomodoros_count: MetricDelta,
+    pub completed_tasks_count: MetricDelta,
+    pub interrupted_count: MetricDelta,
+    /// Sessions interrompues / sessions de travail terminées, entre 0 et 1
+    pub interruption_rate: MetricDelta,
+}
+
+/// Résumé d'une plage de dates découpée selon une granularité
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeSummary {
+    pub start_date: String,
+    pub end_date: String,
+    pub granularity: SummaryGranularity,
+    /// Une entrée par période, y compris les périodes sans activité
+    pub buckets: Vec<SummaryBucket>,
+    pub totals: SummaryBucket,
+    /// Moyennes par période (totaux divisés par le nombre de périodes)
+    pub averages: SummaryAverages,
+}
+
+/// Statistiques d'un projet (et de ses sous-projets) sur une plage de dates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub project_id: i64,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub total_focus_minutes: i32,
+    pub completed_pomodoros_count: i32,
+    pub completed_tasks_count: i32,
+    pub open_tasks_count: i32,
+    /// Part des tâches en cours sur la période qui y ont été complétées, entre 0 et 1
+    ///
+    /// Une tâche est en cours si elle a été créée avant la fin de la période
+    /// et n'était pas déjà complétée à son début. Sans bornes : complétées /
+    /// (complétées + ouvertes).
+    pub completion_rate: f64,
+    /// Pomodoros complétés par tâche travaillée sur la période
+    pub average_pomodoros_per_task: f64,
+    /// Sessions de travail interrompues / sessions de travail terminées, entre 0 et 1
+    pub interruption_rate: f64,
+    pub first_activity_at: Option<String>,
+    pub last_activity_at: Option<String>,
+    pub weekly_velocity: Vec<WeeklyVelocity>,
+}
+
+/// Vélocité d'une semaine (du lundi au dimanche)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyVelocity {
+    /// Lundi de la semaine (format ISO: YYYY-MM-DD)
+    pub week_start: String,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+    pub completed_tasks_count: i32,
+}
+
+/// Activité d'un jour pour la heatmap annuelle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapDay {
+    pub date: String,
+    pub total_focus_minutes: i32,
+    pub completed_pomodoros_count: i32,
+    /// Niveau d'intensité de 0 (aucune activité) à 4 (quartile supérieur)
+    pub intensity: u8,
+}
+
+/// Données d'une heatmap d'activité (style "contributions GitHub")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityHeatmap {
+    pub start_date: String,
+    pub end_date: String,
+    /// Un jour par date de la plage, y compris les jours sans activité
+    pub days: Vec<HeatmapDay>,
+    /// Bornes supérieures (en minutes) des niveaux 1 à 3, calculées sur les
+    /// quartiles des jours actifs ; au-delà, le niveau est 4
+    pub thresholds: Vec<i32>,
+    pub total_focus_minutes: i32,
+    pub completed_pomodoros_count: i32,
+    pub active_days_count: i32,
+}
+
+/// Agrégats d'une tranche horaire ou d'un jour de la semaine
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionBucket {
+    /// Heure (0-23) ou jour ISO de la semaine (1 = lundi ... 7 = dimanche)
+    pub key: u32,
+    pub completed_pomodoros_count: i32,
+    pub total_focus_minutes: i32,
+    pub interrupted_count: i32,
+    /// Sessions interrompues / sessions de travail terminées, entre 0 et 1
+    pub interruption_rate: f64,
+    /// Moyenne des notes de concentration (None si aucune session notée)
+    pub average_focus_rating: Option<f64>,
+}
+
+/// Répartition de la productivité par heure de la journée et jour de la semaine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductivityDistribution {
+    pub start_date: String,
+    pub end_date: String,
+    /// 24 entrées, de 0h à 23h (heure locale)
+    pub by_hour: Vec<DistributionBucket>,
+    /// 7 entrées, du lundi au dimanche
+    pub by_weekday: Vec<DistributionBucket>,
+}
+
+/// Format de sortie d'un rapport
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Markdown,
+    /// Page HTML autonome (styles intégrés, aucune ressource externe)
+    Html,
+}
+
+impl ReportFormat {
+    /// Convertit le format en string pour la base de données
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "markdown",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    /// Parse une string depuis la base de données
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("Invalid report format: {}", s)),
+        }
+    }
+}
+
+/// Tâche complétée listée dans un rapport
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedTaskEntry {
+    pub task_id: i64,
+    pub title: String,
+    pub project_name: Option<String>,
+    /// Jour local de complétion (format ISO: YYYY-MM-DD)
+    pub completed_date: String,
+}
+
+/// Données d'un rapport de période, comparées à la période précédente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportData {
+    /// Totaux et évolutions par rapport à la période précédente
+    pub comparison: PeriodComparison,
+    pub breakdown: SummaryBreakdown,
+    pub completed_tasks: Vec<CompletedTaskEntry>,
+}
+
+/// Respect des pauses sur une plage de dates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakCompliance {
+    pub start_date: String,
+    pub end_date: String,
+    /// Sessions de travail complétées suivies d'une autre session le même jour
+    pub work_sessions_count: i32,
+    /// Sessions de travail suivies d'une pause
+    pub breaks_taken_count: i32,
+    /// Sessions de travail suivies directement d'une autre session de travail
+    pub breaks_skipped_count: i32,
+    /// Pauses prises / (prises + sautées), entre 0 et 1
+    pub break_compliance_rate: f64,
+    /// Pauses ayant duré plus longtemps que prévu avant la reprise du travail
+    pub break_overrun_count: i32,
+    /// Dépassement moyen des pauses en dépassement, en minutes
+    pub average_overrun_minutes: f64,
+    pub total_break_minutes: f64,
+    pub total_focus_minutes: i32,
+    /// Temps de pause / temps de focus
+    pub break_to_work_ratio: f64,
+    pub long_breaks_taken_count: i32,
+    /// Pauses longues attendues (une toutes les `pomodoros_until_long_break` sessions par jour)
+    pub long_breaks_expected_count: i32,
+}
+
+/// Mode d'import d'un export JSON
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Remplace toutes les données (paramètres compris)
+    Replace,
+    /// Ajoute les données importées aux données existantes avec de nouveaux IDs
+    Merge,
+}
+
+/// Export complet des données, lié à la version du schéma qui l'a produit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExport {
+    /// Version du schéma de la base au moment de l'export
+    pub schema_version: i32,
+    pub exported_at: String,
+    pub settings: Settings,
+    pub projects: Vec<ExportedProject>,
+    pub tasks: Vec<ExportedTask>,
+    pub sessions: Vec<ExportedSession>,
+    pub goals: Vec<ExportedGoal>,
+    pub milestones: Vec<ExportedMilestone>,
+    pub rest_days: Vec<RestDay>,
+    pub report_templates: Vec<ExportedReportTemplate>,
+    #[serde(default)]
+    pub session_aggregates: Vec<ExportedSessionAggregate>,
+    #[serde(default)]
+    pub task_aggregates: Vec<ExportedTaskAggregate>,
+}
+
+/// Projet tel qu'exporté (IDs d'origine, remappés à l'import)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedProject {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub parent_project_id: Option<i64>,
+    pub status: ProjectStatus,
+    pub status_changed_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub archived_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Tâche telle qu'exportée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedTask {
+    pub id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub project_id: Option<i64>,
+    pub estimated_pomodoros: i32,
+    pub completed_pomodoros: i32,
+    pub is_completed: bool,
+    pub completed_at: Option<String>,
+    pub completed_utc_offset_minutes: Option<i32>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i32>,
+    #[serde(default)]
+    pub external_uid: Option<String>,
+    /// Contextes et attributs todo.txt, séparés par des espaces
+    #[serde(default)]
+    pub extra_tags: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Session Pomodoro telle qu'exportée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSession {
+    pub id: i64,
+    pub task_id: Option<i64>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub duration_minutes: i32,
+    pub session_type: SessionType,
+    pub interrupted: bool,
+    pub utc_offset_minutes: i32,
+    pub focus_rating: Option<i32>,
+}
+
+/// Objectif de projet tel qu'exporté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedGoal {
+    pub id: i64,
+    pub project_id: i64,
+    pub metric: GoalMetric,
+    pub target: i32,
+    pub period: GoalPeriod,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Jalon tel qu'exporté, avec les IDs de ses tâches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedMilestone {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: String,
+    pub due_date: String,
+    pub task_ids: Vec<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Modèle de rapport personnalisé tel qu'exporté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedReportTemplate {
+    pub format: ReportFormat,
+    pub content: String,
+}
+
+/// Agrégat journalier de sessions purgées tel qu'exporté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSessionAggregate {
+    pub day: String,
+    pub task_id: Option<i64>,
+    /// Projet de la tâche au moment de la purge
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    pub completed_count: i32,
+    pub focus_minutes: i32,
+    pub interrupted_count: i32,
+}
+
+/// Agrégat journalier de tâches purgées tel qu'exporté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedTaskAggregate {
+    pub day: String,
+    pub project_id: Option<i64>,
+    pub completed_count: i32,
+}
+
+/// Nombre d'éléments importés par type
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub projects_count: i32,
+    pub tasks_count: i32,
+    pub sessions_count: i32,
+    pub goals_count: i32,
+    pub milestones_count: i32,
+    pub rest_days_count: i32,
+    pub report_templates_count: i32,
+    /// Agrégats journaliers de l'historique purgé (sessions et tâches)
+    pub aggregates_count: i32,
+}
+
+/// Options d'un export CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvExportOptions {
+    /// Jour local de début inclus (format ISO: YYYY-MM-DD)
+    #[serde(default)]
+    pub start_date: Option<String>,
+    /// Jour local de fin inclus (format ISO: YYYY-MM-DD)
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// Colonnes à exporter, dans l'ordre (toutes si None)
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    /// Séparateur de champs (`;` pour les tableurs en français)
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: char,
+    /// Séparateur décimal (`,` pour les tableurs en français)
+    #[serde(default = "default_csv_decimal_separator")]
+    pub decimal_separator: char,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self {
+            start_date: None,
+            end_date: None,
+            columns: None,
+            delimiter: default_csv_delimiter(),
+            decimal_separator: default_csv_decimal_separator(),
+        }
+    }
+}
+
+/// Séparateur de champs CSV par défaut
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+/// Séparateur décimal CSV par défaut
+fn default_csv_decimal_separator() -> char {
+    '.'
+}
+
+/// Type d'anomalie détectée par la vérification de la base
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssueKind {
+    /// Corruption signalée par `PRAGMA integrity_check` (non réparable, restaurer une sauvegarde)
+    Corruption,
+    /// Référence vers une ligne inexistante
+    ForeignKey,
+    /// `completed_pomodoros` différent du nombre de sessions de travail complétées
+    PomodoroCountMismatch,
+    /// Session terminée avant d'avoir commencé
+    SessionEndsBeforeStart,
+    /// Date de complétion sur une tâche non complétée
+    CompletedAtOnOpenTask,
+    /// Tâche complétée sans date de complétion
+    MissingCompletedAt,
+}
+
+/// Anomalie détectée dans la base
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub table: String,
+    pub row_id: Option<i64>,
+    pub detail: String,
+    /// L'anomalie peut être corrigée automatiquement
+    pub fixable: bool,
+}
+
+/// Résultat d'une vérification (et éventuelle réparation) de la base
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// Anomalies détectées avant réparation
+    pub issues: Vec<IntegrityIssue>,
+    /// Nombre d'anomalies corrigées
+    pub fixed_count: i32,
+    /// La base a été compactée (VACUUM) après réparation
+    pub vacuumed: bool,
+}
+
+/// Espace de travail, avec sa propre base de données
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    /// Identifiant stable, dérivé du nom à la création
+    pub id: String,
+    pub name: String,
+    /// Fichier de la base, relatif au répertoire de données de l'application
+    pub file_name: String,
+    pub created_at: String,
+}
+
+/// Liste des espaces de travail et espace actif
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRegistry {
+    pub active_id: String,
+    pub workspaces: Vec<Workspace>,
+}
+
+/// Conflit entre deux modifications concurrentes d'un même champ
+///
+/// La modification la plus récente est conservée (à égalité de date, celle
+/// de l'appareil dont l'identifiant est le plus grand), sur tous les appareils.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub table: String,
+    /// UUID de la ligne modifiée
+    pub row_uuid: String,
+    pub column: String,
+    pub kept_value: serde_json::Value,
+    pub kept_device_id: String,
+    pub kept_changed_at: String,
+    pub discarded_value: serde_json::Value,
+    pub discarded_device_id: String,
+    pub discarded_changed_at: String,
+}
+
+/// Résultat d'une synchronisation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Changements locaux écrits dans le dossier de synchronisation
+    pub exported_count: i32,
+    /// Changements reçus des autres appareils
+    pub imported_count: i32,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// État de la synchronisation de cet appareil
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub device_id: String,
+    /// Dossier partagé entre les appareils (non configuré si absent)
+    pub folder: Option<String>,
+    /// Champs modifiés localement depuis la dernière synchronisation
+    pub pending_count: i32,
+    pub last_synced_at: Option<String>,
+}
+
+/// État du chiffrement de la base ouverte
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    /// L'application est compilée avec SQLCipher (feature `sqlcipher`)
+    pub supported: bool,
+    pub encrypted: bool,
+    /// La base est chiffrée et attend sa phrase secrète
+    pub locked: bool,
 }
@@ -0,0 +1,317 @@
+// Requêtes SQL pour la gestion des objectifs de projet
+use crate::db::models::{
+    GoalMetric, GoalPeriod, GoalProgress, GoalStatus, ProjectGoal, ProjectGoalInput,
+};
+use crate::db::queries::completed_work;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use rusqlite::{Connection, Result, Row, params};
+
+/// Construit un `ProjectGoal` à partir d'une ligne de project_goals
+fn goal_from_row(row: &Row) -> Result<ProjectGoal> {
+    let metric_str: String = row.get(2)?;
+    let period_str: String = row.get(4)?;
+
+    Ok(ProjectGoal {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        metric: metric_str.parse::<GoalMetric>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+        target: row.get(3)?,
+        period: period_str.parse::<GoalPeriod>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Récupère un objectif par son ID
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `goal_id` - ID de l'objectif
+pub fn get_goal_by_id(conn: &Connection, goal_id: i64) -> Result<ProjectGoal> {
+    conn.query_row(
+        "SELECT id, project_id, metric, target, period, created_at, updated_at
+         FROM project_goals
+         WHERE id = ?1",
+        [goal_id],
+        goal_from_row,
+    )
+}
+
+/// Récupère les objectifs d'un projet
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+pub fn get_goals_by_project(conn: &Connection, project_id: i64) -> Result<Vec<ProjectGoal>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, metric, target, period, created_at, updated_at
+         FROM project_goals
+         WHERE project_id = ?1
+         ORDER BY created_at",
+    )?;
+
+    let goals = stmt.query_map([project_id], goal_from_row)?;
+
+    goals.collect()
+}
+
+/// Crée un objectif pour un projet
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+/// * `input` - Métrique, cible et période de l'objectif
+pub fn create_goal(
+    conn: &Connection,
+    project_id: i64,
+    input: &ProjectGoalInput,
+) -> Result<ProjectGoal> {
+    conn.execute(
+        "INSERT INTO project_goals (project_id, metric, target, period)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            project_id,
+            input.metric.as_str(),
+            &input.target,
+            input.period.as_str(),
+        ],
+    )?;
+
+    let goal_id = conn.last_insert_rowid();
+    get_goal_by_id(conn, goal_id)
+}
+
+/// Met à jour un objectif existant
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `goal_id` - ID de l'objectif
+/// * `input` - Nouvelle métrique, cible et période
+pub fn update_goal(conn: &Connection, goal_id: i64, input: &ProjectGoalInput) -> Result<ProjectGoal> {
+    conn.execute(
+        "UPDATE project_goals
+         SET metric = ?1, target = ?2, period = ?3, updated_at = datetime('now')
+         WHERE id = ?4",
+        params![
+            input.metric.as_str(),
+            &input.target,
+            input.period.as_str(),
+            goal_id,
+        ],
+    )?;
+
+    get_goal_by_id(conn, goal_id)
+}
+
+/// Supprime un objectif
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `goal_id` - ID de l'objectif à supprimer
+pub fn delete_goal(conn: &Connection, goal_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM project_goals WHERE id = ?1", [goal_id])?;
+    Ok(())
+}
+
+/// Calcule la progression des objectifs d'un projet sur leur période en cours
+///
+/// Sont comptées les sessions de travail complétées et non interrompues des
+/// tâches du projet et de ses sous-projets.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+/// * `now` - Heure locale de référence (voir `current_local_time`), qui
+///   détermine la période en cours
+pub fn get_goal_progress_by_project(
+    conn: &Connection,
+    project_id: i64,
+    now: NaiveDateTime,
+) -> Result<Vec<GoalProgress>> {
+    get_goals_by_project(conn, project_id)?
+        .into_iter()
+        .map(|goal| compute_goal_progress(conn, goal, now))
+        .collect()
+}
+
+/// Calcule la progression d'un objectif
+fn compute_goal_progress(
+    conn: &Connection,
+    goal: ProjectGoal,
+    now: NaiveDateTime,
+) -> Result<GoalProgress> {
+    let bounds = period_bounds(&goal.period, now.date());
+    let period_start = bounds.map(|(start, _)| start.format("%Y-%m-%d").to_string());
+    let period_end = bounds.map(|(_, end)| end.format("%Y-%m-%d").to_string());
+
+    let (pomodoros, minutes): (i32, i32) = conn.query_row(
+        &format!(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT ?1
+                UNION
+                SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+            )
+            SELECT COALESCE(SUM(pomodoros), 0), COALESCE(SUM(focus_minutes), 0)
+            FROM {}
+            WHERE project_id IN (SELECT id FROM subtree)
+              AND (?2 IS NULL OR day >= ?2)
+              AND (?3 IS NULL OR day <= ?3)",
+            completed_work()
+        ),
+        params![goal.project_id, &period_start, &period_end],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let done = match goal.metric {
+        GoalMetric::Pomodoros => pomodoros,
+        GoalMetric::Minutes => minutes,
+    };
+    let target = f64::from(goal.target);
+
+    // Part écoulée de la période : un objectif "total" n'a pas d'échéance
+    let elapsed = match bounds {
+        Some((start, end)) => {
+            let start = start.and_hms_opt(0, 0, 0).unwrap_or_default();
+            let length = (end - start.date() + Duration::days(1)).num_seconds() as f64;
+            ((now - start).num_seconds() as f64 / length).clamp(0.0, 1.0)
+        }
+        None => 0.0,
+    };
+    let expected = target * elapsed;
+
+    let status = if done >= goal.target {
+        GoalStatus::Achieved
+    } else if bounds.is_none() {
+        GoalStatus::InProgress
+    } else if f64::from(done) >= expected {
+        GoalStatus::OnTrack
+    } else {
+        GoalStatus::Behind
+    };
+
+    Ok(GoalProgress {
+        period_start,
+        period_end,
+        done,
+        expected,
+        percent_complete: f64::from(done) / target * 100.0,
+        status,
+        goal,
+    })
+}
+
+/// Calcule les bornes (incluses) de la période contenant `today`
+///
+/// Les semaines commencent le lundi (ISO 8601). Retourne None pour "total".
+fn period_bounds(period: &GoalPeriod, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match period {
+        GoalPeriod::Day => Some((today, today)),
+        GoalPeriod::Week => {
+            let start = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+            Some((start, start + Duration::days(6)))
+        }
+        GoalPeriod::Month => {
+            let start = today.with_day(1)?;
+            let next_month = if start.month() == 12 {
+                NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)?
+            } else {
+                NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)?
+            };
+            Some((start, next_month - Duration::days(1)))
+        }
+        GoalPeriod::Total => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id) VALUES ('Task', 1)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add_work_session(conn: &Connection, started_at: &str) {
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+             VALUES (1, ?1, ?1, 25, 'work')",
+            [started_at],
+        )
+        .unwrap();
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_goal_crud() {
+        let conn = setup_test_db();
+        let input = ProjectGoalInput {
+            metric: GoalMetric::Pomodoros,
+            target: 8,
+            period: GoalPeriod::Week,
+        };
+
+        let goal = create_goal(&conn, 1, &input).unwrap();
+        assert_eq!(goal.target, 8);
+
+        let updated = update_goal(&conn, goal.id, &ProjectGoalInput {
+            metric: GoalMetric::Minutes,
+            target: 2400,
+            period: GoalPeriod::Total,
+        })
+        .unwrap();
+        assert_eq!(updated.metric, GoalMetric::Minutes);
+        assert_eq!(updated.period, GoalPeriod::Total);
+
+        delete_goal(&conn, goal.id).unwrap();
+        assert!(get_goals_by_project(&conn, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_weekly_goal_progress() {
+        let conn = setup_test_db();
+        create_goal(&conn, 1, &ProjectGoalInput {
+            metric: GoalMetric::Pomodoros,
+            target: 8,
+            period: GoalPeriod::Week,
+        })
+        .unwrap();
+
+        // Semaine du lundi 2025-11-10 au dimanche 2025-11-16
+        add_work_session(&conn, "2025-11-09 10:00:00");
+        add_work_session(&conn, "2025-11-10 10:00:00");
+        add_work_session(&conn, "2025-11-11 10:00:00");
+
+        let progress = get_goal_progress_by_project(&conn, 1, at("2025-11-11 12:00:00")).unwrap();
+        assert_eq!(progress[0].period_start.as_deref(), Some("2025-11-10"));
+        assert_eq!(progress[0].period_end.as_deref(), Some("2025-11-16"));
+        assert_eq!(progress[0].done, 2);
+        assert_eq!(progress[0].status, GoalStatus::OnTrack);
+
+        let progress = get_goal_progress_by_project(&conn, 1, at("2025-11-15 12:00:00")).unwrap();
+        assert_eq!(progress[0].status, GoalStatus::Behind);
+
+        create_goal(&conn, 1, &ProjectGoalInput {
+            metric: GoalMetric::Pomodoros,
+            target: 10,
+            period: GoalPeriod::Total,
+        })
+        .unwrap();
+        let progress = get_goal_progress_by_project(&conn, 1, at("2025-11-15 12:00:00")).unwrap();
+        let total = progress.iter().find(|p| p.goal.period == GoalPeriod::Total).unwrap();
+        assert_eq!(total.done, 3);
+        assert_eq!(total.status, GoalStatus::InProgress);
+    }
+}
@@ -0,0 +1,305 @@
+// Requêtes SQL de vérification et de réparation de la base
+use crate::db::models::{IntegrityIssue, IntegrityIssueKind, IntegrityReport};
+use rusqlite::{params, Connection, Result};
+
+/// Valeur attendue de `tasks.completed_pomodoros` pour la tâche `t`
+///
+/// Les sessions regroupées par `purge_history` sont comptées via leurs agrégats.
+const COUNTED_POMODOROS: &str = "((SELECT COUNT(*) FROM pomodoro_sessions s
+      WHERE s.task_id = t.id
+        AND s.session_type = 'work'
+        AND s.completed_at IS NOT NULL
+        AND s.interrupted = 0)
+    + (SELECT COALESCE(SUM(a.completed_count), 0) FROM session_aggregates a
+       WHERE a.task_id = t.id))";
+
+/// Référence cassée signalée par `PRAGMA foreign_key_check`
+struct ForeignKeyViolation {
+    table: String,
+    row_id: Option<i64>,
+    parent: String,
+    column: String,
+    /// La colonne accepte NULL : la référence peut être effacée au lieu de supprimer la ligne
+    nullable: bool,
+}
+
+/// Vérifie la base et, si demandé, corrige les anomalies réparables
+///
+/// Les vérifications portent sur le stockage (`PRAGMA integrity_check`), les
+/// clés étrangères et la cohérence des données (compteurs de Pomodoros,
+/// dates de fin de session et de complétion des tâches). Les corrections
+/// sont appliquées dans une seule transaction, suivies d'un VACUUM. Une base
+/// corrompue n'est jamais modifiée : il faut restaurer une sauvegarde.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `repair` - Corrige les anomalies réparables
+pub fn check_database(conn: &Connection, repair: bool) -> Result<IntegrityReport> {
+    let mut issues = check_storage(conn)?;
+    let corrupted = !issues.is_empty();
+
+    let violations = foreign_key_violations(conn)?;
+    issues.extend(violations.iter().map(|violation| IntegrityIssue {
+        kind: IntegrityIssueKind::ForeignKey,
+        table: violation.table.clone(),
+        row_id: violation.row_id,
+        detail: format!(
+            "{} references a missing row in {}",
+            violation.column, violation.parent
+        ),
+        fixable: true,
+    }));
+    issues.extend(check_consistency(conn)?);
+
+    let mut report = IntegrityReport {
+        issues,
+        fixed_count: 0,
+        vacuumed: false,
+    };
+    if !repair || corrupted {
+        return Ok(report);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for violation in &violations {
+        fix_foreign_key(&tx, violation)?;
+    }
+    fix_consistency(&tx)?;
+    tx.commit()?;
+    report.fixed_count = report.issues.iter().filter(|issue| issue.fixable).count() as i32;
+
+    conn.execute_batch("VACUUM")?;
+    report.vacuumed = true;
+
+    Ok(report)
+}
+
+/// Anomalies de stockage signalées par `PRAGMA integrity_check`
+fn check_storage(conn: &Connection) -> Result<Vec<IntegrityIssue>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut issues = Vec::new();
+    for message in messages {
+        let message = message?;
+        if message != "ok" {
+            issues.push(IntegrityIssue {
+                kind: IntegrityIssueKind::Corruption,
+                table: String::new(),
+                row_id: None,
+                detail: message,
+                fixable: false,
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Références cassées, avec la colonne et sa nullabilité
+fn foreign_key_violations(conn: &Connection) -> Result<Vec<ForeignKeyViolation>> {
+    let mut stmt = conn.prepare(
+        "SELECT fk.\"table\", fk.rowid, fk.parent, l.\"from\", i.\"notnull\" = 0
+         FROM pragma_foreign_key_check AS fk
+         JOIN pragma_foreign_key_list(fk.\"table\") AS l ON l.id = fk.fkid AND l.seq = 0
+         JOIN pragma_table_info(fk.\"table\") AS i ON i.name = l.\"from\"
+         ORDER BY fk.\"table\", fk.rowid",
+    )?;
+    let violations = stmt.query_map([], |row| {
+        Ok(ForeignKeyViolation {
+            table: row.get(0)?,
+            row_id: row.get(1)?,
+            parent: row.get(2)?,
+            column: row.get(3)?,
+            nullable: row.get(4)?,
+        })
+    })?;
+
+    violations.collect()
+}
+
+/// Efface la référence cassée, ou supprime la ligne si la colonne est obligatoire
+fn fix_foreign_key(conn: &Connection, violation: &ForeignKeyViolation) -> Result<()> {
+    let sql = if violation.nullable {
+        format!(
+            "UPDATE \"{}\" SET \"{}\" = NULL WHERE rowid = ?1",
+            violation.table, violation.column
+        )
+    } else {
+        format!("DELETE FROM \"{}\" WHERE rowid = ?1", violation.table)
+    };
+    conn.execute(&sql, params![violation.row_id])?;
+
+    Ok(())
+}
+
+/// Anomalies de cohérence entre tâches et sessions
+fn check_consistency(conn: &Connection) -> Result<Vec<IntegrityIssue>> {
+    let checks = [
+        (
+            IntegrityIssueKind::PomodoroCountMismatch,
+            "tasks",
+            format!(
+                "SELECT id, 'completed_pomodoros is ' || COALESCE(completed_pomodoros, 'NULL')
+                            || ' but ' || counted || ' work sessions are completed'
+                 FROM (SELECT t.id, t.completed_pomodoros,
+                              {COUNTED_POMODOROS} AS counted
+                       FROM tasks t)
+                 WHERE completed_pomodoros IS NOT counted
+                 ORDER BY id"
+            ),
+        ),
+        (
+            IntegrityIssueKind::SessionEndsBeforeStart,
+            "pomodoro_sessions",
+            "SELECT id, 'completed_at ' || completed_at || ' is before started_at ' || started_at
+             FROM pomodoro_sessions
+             WHERE julianday(completed_at) < julianday(started_at)
+             ORDER BY id"
+                .to_string(),
+        ),
+        (
+            IntegrityIssueKind::CompletedAtOnOpenTask,
+            "tasks",
+            "SELECT id, 'completed_at is set but the task is not completed'
+             FROM tasks
+             WHERE COALESCE(is_completed, 0) = 0 AND completed_at IS NOT NULL
+             ORDER BY id"
+                .to_string(),
+        ),
+        (
+            IntegrityIssueKind::MissingCompletedAt,
+            "tasks",
+            "SELECT id, 'the task is completed but completed_at is not set'
+             FROM tasks
+             WHERE is_completed = 1 AND completed_at IS NULL
+             ORDER BY id"
+                .to_string(),
+        ),
+    ];
+
+    let mut issues = Vec::new();
+    for (kind, table, sql) in checks {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(IntegrityIssue {
+                kind,
+                table: table.to_string(),
+                row_id: row.get(0)?,
+                detail: row.get(1)?,
+                fixable: true,
+            })
+        })?;
+        for issue in rows {
+            issues.push(issue?);
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Corrige les anomalies détectées par `check_consistency`
+///
+/// Les compteurs sont recalculés depuis les sessions (et leurs agrégats), une session terminée
+/// avant son début reçoit sa durée prévue, et la date de complétion des
+/// tâches est alignée sur leur statut.
+fn fix_consistency(conn: &Connection) -> Result<()> {
+    conn.execute(
+        &format!(
+            "UPDATE tasks AS t
+             SET completed_pomodoros = {COUNTED_POMODOROS},
+                 updated_at = datetime('now')
+             WHERE t.completed_pomodoros IS NOT {COUNTED_POMODOROS}"
+        ),
+        [],
+    )?;
+    conn.execute(
+        "UPDATE pomodoro_sessions
+         SET completed_at = datetime(started_at, '+' || duration_minutes || ' minutes')
+         WHERE julianday(completed_at) < julianday(started_at)",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE tasks
+         SET completed_at = NULL, completed_utc_offset_minutes = NULL
+         WHERE COALESCE(is_completed, 0) = 0 AND completed_at IS NOT NULL",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE tasks
+         SET completed_at = updated_at
+         WHERE is_completed = 1 AND completed_at IS NULL",
+        [],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_check_and_repair() {
+        let conn = setup_test_db();
+        assert!(check_database(&conn, false).unwrap().issues.is_empty());
+
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO tasks (title, completed_pomodoros, completed_at)
+                 VALUES ('Drifted', 3, '2025-11-10 10:00:00');
+             INSERT INTO tasks (title, is_completed) VALUES ('Done', 1);
+             INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, '2025-11-10 09:00:00', '2025-11-10 08:00:00', 25, 'work');
+             INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (42, '2025-11-10 11:00:00', '2025-11-10 11:25:00', 25, 'work');
+             INSERT INTO milestone_tasks (milestone_id, task_id) VALUES (7, 1);
+             PRAGMA foreign_keys = ON;",
+        )
+        .unwrap();
+
+        let report = check_database(&conn, false).unwrap();
+        let kinds: Vec<IntegrityIssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IntegrityIssueKind::ForeignKey,
+                IntegrityIssueKind::ForeignKey,
+                IntegrityIssueKind::PomodoroCountMismatch,
+                IntegrityIssueKind::SessionEndsBeforeStart,
+                IntegrityIssueKind::CompletedAtOnOpenTask,
+                IntegrityIssueKind::MissingCompletedAt,
+            ]
+        );
+        assert_eq!(report.fixed_count, 0);
+
+        let repaired = check_database(&conn, true).unwrap();
+        assert_eq!(repaired.fixed_count, 6);
+        assert!(repaired.vacuumed);
+        assert!(check_database(&conn, false).unwrap().issues.is_empty());
+
+        let (count, ended): (i32, String) = conn
+            .query_row(
+                "SELECT t.completed_pomodoros, s.completed_at
+                 FROM tasks t JOIN pomodoro_sessions s ON s.task_id = t.id
+                 WHERE t.id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(ended, "2025-11-10 09:25:00");
+        let links: i64 = conn
+            .query_row("SELECT COUNT(*) FROM milestone_tasks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(links, 0);
+    }
+}
@@ -0,0 +1,391 @@
+// Requêtes SQL pour la gestion des jalons de projet
+use crate::db::models::{BurndownPoint, Milestone, MilestoneBurndown, MilestoneInput};
+use crate::db::queries::{completed_work, constraint_violation, current_local_time, local_day};
+use chrono::{Duration, NaiveDate};
+use rusqlite::{Connection, Result, Row, params};
+use std::collections::HashMap;
+
+/// Construit un `Milestone` à partir d'une ligne de milestones
+fn milestone_from_row(row: &Row) -> Result<Milestone> {
+    Ok(Milestone {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        start_date: row.get(4)?,
+        due_date: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Récupère un jalon par son ID
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+pub fn get_milestone_by_id(conn: &Connection, milestone_id: i64) -> Result<Milestone> {
+    conn.query_row(
+        "SELECT id, project_id, name, description, start_date, due_date, created_at, updated_at
+         FROM milestones
+         WHERE id = ?1",
+        [milestone_id],
+        milestone_from_row,
+    )
+}
+
+/// Récupère les jalons d'un projet, par échéance croissante
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+pub fn get_milestones_by_project(conn: &Connection, project_id: i64) -> Result<Vec<Milestone>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, name, description, start_date, due_date, created_at, updated_at
+         FROM milestones
+         WHERE project_id = ?1
+         ORDER BY due_date, id",
+    )?;
+
+    let milestones = stmt.query_map([project_id], milestone_from_row)?;
+
+    milestones.collect()
+}
+
+/// Crée un jalon pour un projet
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+/// * `input` - Nom, description, date de début (jour local en cours par défaut) et échéance
+pub fn create_milestone(
+    conn: &Connection,
+    project_id: i64,
+    input: &MilestoneInput,
+) -> Result<Milestone> {
+    let today = current_local_time(conn)?.date().format("%Y-%m-%d").to_string();
+    conn.execute(
+        "INSERT INTO milestones (project_id, name, description, start_date, due_date)
+         VALUES (?1, ?2, ?3, COALESCE(DATE(?4), ?6), DATE(?5))",
+        params![
+            project_id,
+            &input.name,
+            &input.description,
+            &input.start_date,
+            &input.due_date,
+            today,
+        ],
+    )?;
+
+    let milestone_id = conn.last_insert_rowid();
+    get_milestone_by_id(conn, milestone_id)
+}
+
+/// Met à jour un jalon existant
+///
+/// Une date de début absente conserve la date actuelle
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+/// * `input` - Nouvelles données du jalon
+pub fn update_milestone(
+    conn: &Connection,
+    milestone_id: i64,
+    input: &MilestoneInput,
+) -> Result<Milestone> {
+    conn.execute(
+        "UPDATE milestones
+         SET name = ?1, description = ?2,
+             start_date = COALESCE(DATE(?3), start_date),
+             due_date = DATE(?4),
+             updated_at = datetime('now')
+         WHERE id = ?5",
+        params![
+            &input.name,
+            &input.description,
+            &input.start_date,
+            &input.due_date,
+            milestone_id,
+        ],
+    )?;
+
+    get_milestone_by_id(conn, milestone_id)
+}
+
+/// Supprime un jalon (les tâches liées ne sont pas supprimées)
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon à supprimer
+pub fn delete_milestone(conn: &Connection, milestone_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM milestones WHERE id = ?1", [milestone_id])?;
+    Ok(())
+}
+
+/// Lie une tâche à un jalon (sans effet si elle l'est déjà)
+///
+/// La tâche doit appartenir au projet du jalon ou à l'un de ses sous-projets.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+/// * `task_id` - ID de la tâche
+pub fn add_task_to_milestone(conn: &Connection, milestone_id: i64, task_id: i64) -> Result<()> {
+    let in_project: bool = conn.query_row(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT project_id FROM milestones WHERE id = ?1
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )
+        SELECT EXISTS (
+            SELECT 1 FROM tasks WHERE id = ?2 AND project_id IN (SELECT id FROM subtree)
+        )",
+        params![milestone_id, task_id],
+        |row| row.get(0),
+    )?;
+    if !in_project {
+        return Err(constraint_violation(format!(
+            "Task {} does not belong to the project of milestone {}",
+            task_id, milestone_id
+        )));
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO milestone_tasks (milestone_id, task_id) VALUES (?1, ?2)",
+        params![milestone_id, task_id],
+    )?;
+    Ok(())
+}
+
+/// Retire une tâche d'un jalon
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+/// * `task_id` - ID de la tâche
+pub fn remove_task_from_milestone(
+    conn: &Connection,
+    milestone_id: i64,
+    task_id: i64,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM milestone_tasks WHERE milestone_id = ?1 AND task_id = ?2",
+        params![milestone_id, task_id],
+    )?;
+    Ok(())
+}
+
+/// Calcule les séries burndown et burnup d'un jalon
+///
+/// Pour chaque jour de `start_date` à l'échéance (ou jusqu'à aujourd'hui si
+/// elle est dépassée), le restant est la somme par tâche liée de
+/// `estimated_pomodoros` moins les sessions de travail complétées jusqu'à ce
+/// jour local, borné à 0 ; une tâche complétée ne compte plus à partir de sa date
+/// de complétion. La ligne idéale descend linéairement jusqu'à 0 à l'échéance.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+/// * `today` - Jour local en cours : les valeurs réelles des jours suivants sont None
+pub fn get_milestone_burndown(
+    conn: &Connection,
+    milestone_id: i64,
+    today: NaiveDate,
+) -> Result<MilestoneBurndown> {
+    let milestone = get_milestone_by_id(conn, milestone_id)?;
+    let start = parse_date(&milestone.start_date)?;
+    let due = parse_date(&milestone.due_date)?;
+
+    // Tâches liées : (id, estimation, date de complétion)
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.id, t.estimated_pomodoros, {}
+         FROM tasks t
+         JOIN milestone_tasks mt ON mt.task_id = t.id
+         WHERE mt.milestone_id = ?1",
+        local_day("t.completed_at", "t.completed_utc_offset_minutes")
+    ))?;
+    let tasks: Vec<(i64, i32, Option<String>)> = stmt
+        .query_map([milestone_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_>>()?;
+
+    // Sessions de travail complétées par tâche et par jour (historique purgé inclus)
+    let mut stmt = conn.prepare(&format!(
+        "SELECT w.task_id, w.day, SUM(w.pomodoros)
+         FROM {} w
+         JOIN milestone_tasks mt ON mt.task_id = w.task_id
+         WHERE mt.milestone_id = ?1
+         GROUP BY w.task_id, w.day",
+        completed_work()
+    ))?;
+    let mut sessions_by_task: HashMap<i64, Vec<(NaiveDate, i32)>> = HashMap::new();
+    for row in stmt.query_map([milestone_id], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i32>(2)?))
+    })? {
+        let (task_id, date, count) = row?;
+        sessions_by_task
+            .entry(task_id)
+            .or_default()
+            .push((parse_date(&date)?, count));
+    }
+
+    let total_estimated_pomodoros: i32 = tasks.iter().map(|(_, estimated, _)| estimated).sum();
+    let span_days = (due - start).num_days();
+    let end = if today > due { today } else { due };
+
+    let mut points = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let elapsed = (date - start).num_days();
+        let ideal = if elapsed >= span_days {
+            0.0
+        } else {
+            f64::from(total_estimated_pomodoros) * (1.0 - elapsed as f64 / span_days as f64)
+        };
+
+        let (remaining, completed) = if date <= today {
+            let mut remaining = 0;
+            let mut completed = 0;
+            for (task_id, estimated, completed_at) in &tasks {
+                let done: i32 = sessions_by_task
+                    .get(task_id)
+                    .map(|days| days.iter().filter(|(d, _)| *d <= date).map(|(_, c)| c).sum())
+                    .unwrap_or(0);
+                completed += done;
+
+                let is_done = completed_at
+                    .as_deref()
+                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                    .is_some_and(|d| d <= date);
+                if !is_done {
+                    remaining += (estimated - done).max(0);
+                }
+            }
+            (Some(remaining), Some(completed))
+        } else {
+            (None, None)
+        };
+
+        points.push(BurndownPoint {
+            date: date.format("%Y-%m-%d").to_string(),
+            remaining_pomodoros: remaining,
+            ideal_remaining_pomodoros: ideal,
+            completed_pomodoros: completed,
+            scope_pomodoros: total_estimated_pomodoros,
+        });
+        date += Duration::days(1);
+    }
+
+    Ok(MilestoneBurndown {
+        milestone,
+        total_estimated_pomodoros,
+        points,
+    })
+}
+
+/// Parse une date ISO (YYYY-MM-DD) lue en base
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| rusqlite::Error::InvalidQuery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use crate::db::queries::get_tasks_by_milestone;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO projects (name) VALUES ('Launch')", []).unwrap();
+        conn
+    }
+
+    fn input(start_date: &str, due_date: &str) -> MilestoneInput {
+        MilestoneInput {
+            name: "Beta".to_string(),
+            description: None,
+            start_date: Some(start_date.to_string()),
+            due_date: due_date.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_milestone_crud_and_tasks() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Task', 1)", []).unwrap();
+
+        let milestone = create_milestone(&conn, 1, &input("2025-11-01", "2025-11-30")).unwrap();
+        assert_eq!(milestone.due_date, "2025-11-30");
+
+        add_task_to_milestone(&conn, milestone.id, 1).unwrap();
+        add_task_to_milestone(&conn, milestone.id, 1).unwrap();
+        assert_eq!(get_tasks_by_milestone(&conn, milestone.id).unwrap().len(), 1);
+
+        remove_task_from_milestone(&conn, milestone.id, 1).unwrap();
+        assert!(get_tasks_by_milestone(&conn, milestone.id).unwrap().is_empty());
+
+        // Tâche d'un sous-projet acceptée, tâche d'un autre projet refusée
+        conn.execute("INSERT INTO projects (name, parent_project_id) VALUES ('Docs', 1)", [])
+            .unwrap();
+        conn.execute("INSERT INTO projects (name) VALUES ('Other')", []).unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Guide', 2)", []).unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Elsewhere', 3)", [])
+            .unwrap();
+        add_task_to_milestone(&conn, milestone.id, 2).unwrap();
+        assert!(add_task_to_milestone(&conn, milestone.id, 3).is_err());
+
+        assert!(create_milestone(&conn, 1, &input("2025-12-01", "2025-11-30")).is_err());
+
+        delete_milestone(&conn, milestone.id).unwrap();
+        assert!(get_milestones_by_project(&conn, 1).unwrap().is_empty());
+
+        let mut undated = input("2025-11-01", "2099-12-31");
+        undated.start_date = None;
+        let undated = create_milestone(&conn, 1, &undated).unwrap();
+        let today = current_local_time(&conn).unwrap().date().format("%Y-%m-%d").to_string();
+        assert_eq!(undated.start_date, today);
+    }
+
+    #[test]
+    fn test_milestone_burndown() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, estimated_pomodoros) VALUES ('A', 1, 3)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, estimated_pomodoros, is_completed, completed_at)
+             VALUES ('B', 1, 1, 1, '2025-11-03 12:00:00')",
+            [],
+        )
+        .unwrap();
+        let milestone = create_milestone(&conn, 1, &input("2025-11-01", "2025-11-05")).unwrap();
+        add_task_to_milestone(&conn, milestone.id, 1).unwrap();
+        add_task_to_milestone(&conn, milestone.id, 2).unwrap();
+
+        for started_at in ["2025-11-02 09:00:00", "2025-11-02 10:00:00"] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, ?1, ?1, 25, 'work')",
+                [started_at],
+            )
+            .unwrap();
+        }
+
+        let today = NaiveDate::from_ymd_opt(2025, 11, 3).unwrap();
+        let burndown = get_milestone_burndown(&conn, milestone.id, today).unwrap();
+        assert_eq!(burndown.total_estimated_pomodoros, 4);
+        assert_eq!(burndown.points.len(), 5);
+
+        let remaining: Vec<Option<i32>> =
+            burndown.points.iter().map(|p| p.remaining_pomodoros).collect();
+        assert_eq!(remaining, vec![Some(4), Some(2), Some(1), None, None]);
+        assert_eq!(burndown.points[1].completed_pomodoros, Some(2));
+        assert_eq!(burndown.points[0].ideal_remaining_pomodoros, 4.0);
+        assert_eq!(burndown.points[2].ideal_remaining_pomodoros, 2.0);
+        assert_eq!(burndown.points[4].ideal_remaining_pomodoros, 0.0);
+    }
+}
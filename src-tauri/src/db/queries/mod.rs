@@ -1,10 +1,111 @@
 // Module contenant toutes les requêtes SQL préparées
+pub mod goals;
+pub mod maintenance;
+pub mod milestones;
 pub mod projects;
+pub mod reports;
+pub mod retention;
 pub mod sessions;
 pub mod settings;
+pub mod stats;
+pub mod streaks;
+pub mod summary;
 pub mod tasks;
+pub mod transfer;
 
+pub use goals::*;
+pub use maintenance::*;
+pub use milestones::*;
 pub use projects::*;
+pub use reports::*;
+pub use retention::*;
 pub use sessions::*;
 pub use settings::*;
+pub use stats::*;
+pub use streaks::*;
+pub use summary::*;
 pub use tasks::*;
+pub use transfer::*;
+
+/// Construit une erreur de contrainte SQLite avec un message explicite
+///
+/// Utilisée pour les règles métier vérifiées côté Rust (cycles, validations)
+/// afin qu'elles remontent comme les contraintes du schéma
+pub(crate) fn constraint_violation(message: impl Into<String>) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+        Some(message.into()),
+    )
+}
+
+/// Parse une date ISO (YYYY-MM-DD) fournie par l'appelant
+pub(crate) fn parse_input_date(date: &str) -> rusqlite::Result<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| constraint_violation(format!("Invalid date: {}", date)))
+}
+
+/// Modificateur SQL convertissant un horodatage UTC en "heure locale logique"
+///
+/// Applique le décalage UTC enregistré avec la ligne (`offset_column`, en
+/// minutes) puis recule du début de journée configuré (`settings.day_start_hour`),
+/// de sorte qu'une session à 01:30 avec un début de journée à 04:00 compte
+/// pour la veille.
+pub(crate) fn local_time_modifier(offset_column: &str) -> String {
+    format!(
+        "(COALESCE({offset_column}, 0)
+          - (SELECT day_start_hour FROM settings WHERE id = 1) * 60) || ' minutes'"
+    )
+}
+
+/// Modificateur SQL convertissant un horodatage UTC en heure locale réelle
+///
+/// Contrairement à `local_time_modifier`, ne tient pas compte du début de
+/// journée : à utiliser pour l'heure de la journée, pas pour le jour
+pub(crate) fn utc_offset_modifier(offset_column: &str) -> String {
+    format!("COALESCE({offset_column}, 0) || ' minutes'")
+}
+
+/// Expression SQL donnant le jour local (YYYY-MM-DD) d'un horodatage UTC
+///
+/// Voir `local_time_modifier` pour la prise en compte du fuseau et du début de journée
+pub(crate) fn local_day(timestamp_column: &str, offset_column: &str) -> String {
+    format!(
+        "DATE({timestamp_column}, {})",
+        local_time_modifier(offset_column)
+    )
+}
+
+/// Sous-requête des sessions de travail complétées et non interrompues
+///
+/// Réunit les sessions détaillées et les agrégats journaliers laissés par
+/// `purge_history`, pour que les résumés restent justes sur les périodes
+/// purgées. Colonnes : `day` (jour local), `task_id`, `project_id`,
+/// `pomodoros` et `focus_minutes` ; une ligne peut compter plusieurs sessions.
+/// Le projet est celui de la tâche, ou celui conservé dans l'agrégat quand la
+/// tâche a été supprimée.
+pub(crate) fn completed_work() -> String {
+    format!(
+        "(SELECT {} AS day, task_id,
+                 (SELECT t.project_id FROM tasks t WHERE t.id = pomodoro_sessions.task_id)
+                     AS project_id,
+                 1 AS pomodoros, duration_minutes AS focus_minutes
+          FROM pomodoro_sessions
+          WHERE completed_at IS NOT NULL
+            AND interrupted = 0
+            AND session_type = 'work'
+          UNION ALL
+          SELECT day, task_id,
+                 CASE WHEN task_id IS NULL THEN project_id
+                      ELSE (SELECT t.project_id FROM tasks t WHERE t.id = session_aggregates.task_id)
+                 END,
+                 completed_count, focus_minutes
+          FROM session_aggregates
+          WHERE completed_count > 0)",
+        local_day("started_at", "utc_offset_minutes")
+    )
+}
+
+/// Décalage UTC actuel de la machine, en minutes (ex: 120 pour UTC+2)
+pub fn current_utc_offset_minutes() -> i32 {
+    chrono::Local::now().offset().local_minus_utc() / 60
+}
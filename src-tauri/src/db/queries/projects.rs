@@ -1,27 +1,69 @@
 // Requêtes SQL pour la gestion des projets
-use crate::db::models::{CreateProjectInput, Project};
-use rusqlite::{Connection, Result, params};
+use crate::db::models::{
+    CreateProjectInput, Project, ProjectDeletionPolicy, ProjectDeletionPreview, ProjectStatus,
+    ProjectTotals, ProjectTreeNode,
+};
+use crate::db::queries::{completed_work, constraint_violation};
+use rusqlite::{Connection, Result, Row, params};
+use std::collections::HashMap;
 
-/// Récupère tous les projets
+/// Colonnes sélectionnées pour construire un `Project`
+const PROJECT_COLUMNS: &str = "id, name, color, status, status_changed_at, completed_at,
+                               archived_at, created_at, updated_at, parent_project_id";
+
+/// Construit un `Project` à partir d'une ligne sélectionnée avec `PROJECT_COLUMNS`
+fn project_from_row(row: &Row) -> Result<Project> {
+    let status_str: String = row.get(3)?;
+    let status = status_str.parse::<ProjectStatus>()
+        .map_err(|_| rusqlite::Error::InvalidQuery)?;
+
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        parent_project_id: row.get(9)?,
+        status,
+        status_changed_at: row.get(4)?,
+        completed_at: row.get(5)?,
+        archived_at: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// Récupère tous les projets non archivés
+///
+/// Les projets archivés restent accessibles via `get_projects_by_status`
 ///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
 pub fn get_all_projects(conn: &Connection) -> Result<Vec<Project>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, color, created_at, updated_at
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS}
          FROM projects
-         ORDER BY created_at DESC",
-    )?;
+         WHERE status != 'archived'
+         ORDER BY created_at DESC"
+    ))?;
 
-    let projects = stmt.query_map([], |row| {
-        Ok(Project {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            color: row.get(2)?,
-            created_at: row.get(3)?,
-            updated_at: row.get(4)?,
-        })
-    })?;
+    let projects = stmt.query_map([], project_from_row)?;
+
+    projects.collect()
+}
+
+/// Récupère les projets ayant un statut donné
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `status` - Statut recherché
+pub fn get_projects_by_status(conn: &Connection, status: &ProjectStatus) -> Result<Vec<Project>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS}
+         FROM projects
+         WHERE status = ?1
+         ORDER BY status_changed_at DESC, created_at DESC"
+    ))?;
+
+    let projects = stmt.query_map([status.as_str()], project_from_row)?;
 
     projects.collect()
 }
@@ -33,19 +75,13 @@ pub fn get_all_projects(conn: &Connection) -> Result<Vec<Project>> {
 /// * `project_id` - ID du projet
 pub fn get_project_by_id(conn: &Connection, project_id: i64) -> Result<Project> {
     conn.query_row(
-        "SELECT id, name, color, created_at, updated_at
-         FROM projects
-         WHERE id = ?1",
+        &format!(
+            "SELECT {PROJECT_COLUMNS}
+             FROM projects
+             WHERE id = ?1"
+        ),
         [project_id],
-        |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        },
+        project_from_row,
     )
 }
 
@@ -53,11 +89,12 @@ pub fn get_project_by_id(conn: &Connection, project_id: i64) -> Result<Project>
 ///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
-/// * `input` - Données du projet à créer
+/// * `input` - Données du projet à créer (avec un parent optionnel)
 pub fn create_project(conn: &Connection, input: &CreateProjectInput) -> Result<Project> {
     conn.execute(
-        "INSERT INTO projects (name, color) VALUES (?1, ?2)",
-        params![&input.name, &input.color],
+        "INSERT INTO projects (name, color, parent_project_id, status_changed_at)
+         VALUES (?1, ?2, ?3, datetime('now'))",
+        params![&input.name, &input.color, &input.parent_project_id],
     )?;
 
     let project_id = conn.last_insert_rowid();
@@ -85,18 +122,357 @@ pub fn update_project(
     get_project_by_id(conn, project_id)
 }
 
-/// Supprime un projet
+/// Change le statut d'un projet
+///
+/// Met à jour `status_changed_at`, renseigne `completed_at` lors du passage
+/// à "completed" (conservé si le projet est ensuite archivé) et `archived_at`
+/// lors de l'archivage. Revenir à "active" ou "on_hold" efface ces dates.
 ///
-/// Note: Les tâches associées auront leur project_id mis à NULL (ON DELETE SET NULL)
+/// L'archivage s'applique à tous les descendants du projet ; désarchiver un
+/// projet applique le nouveau statut à ses descendants encore archivés.
 ///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
-/// * `project_id` - ID du projet à supprimer
-pub fn delete_project(conn: &Connection, project_id: i64) -> Result<()> {
-    conn.execute("DELETE FROM projects WHERE id = ?1", [project_id])?;
+/// * `project_id` - ID du projet
+/// * `status` - Nouveau statut
+pub fn set_project_status(
+    conn: &Connection,
+    project_id: i64,
+    status: &ProjectStatus,
+) -> Result<Project> {
+    let previous = get_project_by_id(conn, project_id)?;
+    let tx = conn.unchecked_transaction()?;
+
+    update_project_status(&tx, project_id, status)?;
+
+    let cascade = *status == ProjectStatus::Archived
+        || previous.status == ProjectStatus::Archived;
+    if cascade {
+        for descendant_id in get_descendant_project_ids(&tx, project_id)? {
+            let descendant = get_project_by_id(&tx, descendant_id)?;
+            if *status == ProjectStatus::Archived || descendant.status == ProjectStatus::Archived {
+                update_project_status(&tx, descendant_id, status)?;
+            }
+        }
+    }
+
+    tx.commit()?;
+    get_project_by_id(conn, project_id)
+}
+
+/// Applique un statut à un seul projet (sans cascade)
+fn update_project_status(conn: &Connection, project_id: i64, status: &ProjectStatus) -> Result<()> {
+    conn.execute(
+        "UPDATE projects
+         SET status = ?1,
+             status_changed_at = CASE WHEN status = ?1 THEN status_changed_at ELSE datetime('now') END,
+             completed_at = CASE
+                 WHEN ?1 = 'completed' THEN COALESCE(completed_at, datetime('now'))
+                 WHEN ?1 = 'archived' THEN completed_at
+                 ELSE NULL
+             END,
+             archived_at = CASE
+                 WHEN ?1 = 'archived' THEN COALESCE(archived_at, datetime('now'))
+                 ELSE NULL
+             END,
+             updated_at = datetime('now')
+         WHERE id = ?2",
+        params![status.as_str(), project_id],
+    )?;
     Ok(())
 }
 
+/// Déplace un projet (et tout son sous-arbre) sous un nouveau parent
+///
+/// Refuse de rattacher un projet à lui-même ou à l'un de ses descendants
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet à déplacer
+/// * `parent_project_id` - Nouveau parent (None pour placer le projet à la racine)
+pub fn move_project(
+    conn: &Connection,
+    project_id: i64,
+    parent_project_id: Option<i64>,
+) -> Result<Project> {
+    if let Some(parent_id) = parent_project_id {
+        if parent_id == project_id || get_ancestor_project_ids(conn, parent_id)?.contains(&project_id) {
+            return Err(constraint_violation(format!(
+                "Cannot move project {} under {}: this would create a cycle",
+                project_id, parent_id
+            )));
+        }
+    }
+
+    conn.execute(
+        "UPDATE projects SET parent_project_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![parent_project_id, project_id],
+    )?;
+
+    get_project_by_id(conn, project_id)
+}
+
+/// Récupère les IDs de tous les descendants d'un projet (enfants, petits-enfants...)
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet racine (exclu du résultat)
+pub fn get_descendant_project_ids(conn: &Connection, project_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE descendants(id) AS (
+            SELECT id FROM projects WHERE parent_project_id = ?1
+            UNION
+            SELECT p.id FROM projects p JOIN descendants d ON p.parent_project_id = d.id
+        )
+        SELECT id FROM descendants",
+    )?;
+
+    let ids = stmt.query_map([project_id], |row| row.get(0))?;
+
+    ids.collect()
+}
+
+/// Récupère les IDs des ancêtres d'un projet, du parent direct jusqu'à la racine
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet (exclu du résultat)
+pub fn get_ancestor_project_ids(conn: &Connection, project_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE ancestors(id, depth) AS (
+            SELECT parent_project_id, 1 FROM projects
+            WHERE id = ?1 AND parent_project_id IS NOT NULL
+            UNION
+            SELECT p.parent_project_id, a.depth + 1 FROM projects p JOIN ancestors a ON p.id = a.id
+            WHERE p.parent_project_id IS NOT NULL
+        )
+        SELECT id FROM ancestors ORDER BY depth",
+    )?;
+
+    let ids = stmt.query_map([project_id], |row| row.get(0))?;
+
+    ids.collect()
+}
+
+/// Construit l'arborescence des projets avec des totaux cumulés
+///
+/// Les totaux de chaque nœud incluent ceux de tous ses descendants. Les
+/// sessions comptées sont les sessions de travail complétées et non interrompues.
+/// Les tâches et sessions purgées par `purge_history` restent comptées via
+/// leurs agrégats.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `include_archived` - Inclut les projets archivés
+pub fn get_project_tree(conn: &Connection, include_archived: bool) -> Result<Vec<ProjectTreeNode>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS}
+         FROM projects
+         WHERE ?1 OR status != 'archived'
+         ORDER BY created_at DESC"
+    ))?;
+    let projects: Vec<Project> = stmt
+        .query_map([include_archived], project_from_row)?
+        .collect::<Result<_>>()?;
+
+    let mut own_totals = get_own_project_totals(conn)?;
+
+    // Regroupe les projets par parent ; un parent masqué fait remonter l'enfant à la racine
+    let visible: Vec<i64> = projects.iter().map(|p| p.id).collect();
+    let mut children_of: HashMap<Option<i64>, Vec<Project>> = HashMap::new();
+    for project in projects {
+        let parent = project.parent_project_id.filter(|id| visible.contains(id));
+        children_of.entry(parent).or_default().push(project);
+    }
+
+    Ok(build_tree_level(None, &mut children_of, &mut own_totals))
+}
+
+/// Construit récursivement un niveau de l'arborescence
+fn build_tree_level(
+    parent: Option<i64>,
+    children_of: &mut HashMap<Option<i64>, Vec<Project>>,
+    own_totals: &mut HashMap<i64, ProjectTotals>,
+) -> Vec<ProjectTreeNode> {
+    let projects = children_of.remove(&parent).unwrap_or_default();
+
+    projects
+        .into_iter()
+        .map(|project| {
+            let children = build_tree_level(Some(project.id), children_of, own_totals);
+            let own = own_totals.remove(&project.id).unwrap_or_default();
+
+            let mut totals = own.clone();
+            for child in &children {
+                totals.task_count += child.totals.task_count;
+                totals.completed_task_count += child.totals.completed_task_count;
+                totals.completed_pomodoros_count += child.totals.completed_pomodoros_count;
+                totals.total_focus_minutes += child.totals.total_focus_minutes;
+            }
+
+            ProjectTreeNode {
+                project,
+                own_totals: own,
+                totals,
+                children,
+            }
+        })
+        .collect()
+}
+
+/// Calcule les totaux propres à chaque projet (sans ses descendants)
+fn get_own_project_totals(conn: &Connection) -> Result<HashMap<i64, ProjectTotals>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.id,
+                (SELECT COUNT(*) FROM tasks t WHERE t.project_id = p.id) + COALESCE(a.tasks, 0),
+                (SELECT COUNT(*) FROM tasks t WHERE t.project_id = p.id AND t.is_completed = 1)
+                    + COALESCE(a.tasks, 0),
+                COALESCE(w.pomodoros, 0),
+                COALESCE(w.minutes, 0)
+         FROM projects p
+         LEFT JOIN (SELECT project_id, SUM(completed_count) AS tasks
+                    FROM task_aggregates
+                    GROUP BY project_id) a ON a.project_id = p.id
+         LEFT JOIN (SELECT project_id, SUM(pomodoros) AS pomodoros, SUM(focus_minutes) AS minutes
+                    FROM {}
+                    GROUP BY project_id) w ON w.project_id = p.id",
+        completed_work()
+    ))?;
+
+    let totals = stmt.query_map([], |row| {
+        Ok((
+            row.get(0)?,
+            ProjectTotals {
+                task_count: row.get(1)?,
+                completed_task_count: row.get(2)?,
+                completed_pomodoros_count: row.get(3)?,
+                total_focus_minutes: row.get(4)?,
+            },
+        ))
+    })?;
+
+    totals.collect()
+}
+
+/// Calcule ce qu'affecterait la suppression d'un projet, sans rien modifier
+///
+/// Compte le projet et ses sous-projets, leurs tâches et les sessions de
+/// ces tâches. Vérifie aussi que la politique est applicable.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet à supprimer
+/// * `policy` - Sort réservé aux tâches
+pub fn preview_project_deletion(
+    conn: &Connection,
+    project_id: i64,
+    policy: &ProjectDeletionPolicy,
+) -> Result<ProjectDeletionPreview> {
+    // Vérifie que le projet existe
+    get_project_by_id(conn, project_id)?;
+
+    let mut subtree = get_descendant_project_ids(conn, project_id)?;
+    subtree.push(project_id);
+
+    if let ProjectDeletionPolicy::MoveTasks { target_project_id } = policy {
+        if subtree.contains(target_project_id) {
+            return Err(constraint_violation(format!(
+                "Cannot move tasks to project {}: it is deleted along with project {}",
+                target_project_id, project_id
+            )));
+        }
+        get_project_by_id(conn, *target_project_id)?;
+    }
+
+    let (task_count, session_count) = conn.query_row(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )
+        SELECT
+            (SELECT COUNT(*) FROM tasks WHERE project_id IN (SELECT id FROM subtree)),
+            (SELECT COUNT(*) FROM pomodoro_sessions s JOIN tasks t ON t.id = s.task_id
+             WHERE t.project_id IN (SELECT id FROM subtree))",
+        [project_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(ProjectDeletionPreview {
+        project_count: subtree.len() as i32,
+        task_count,
+        session_count,
+    })
+}
+
+/// Supprime un projet et ses sous-projets en appliquant une politique à leurs tâches
+///
+/// La suppression est effectuée dans une transaction : en cas d'erreur,
+/// rien n'est modifié.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet à supprimer
+/// * `policy` - Orphan (project_id mis à NULL), MoveTasks ou DeleteTasks
+///
+/// # Retourne
+/// Le nombre de projets, tâches et sessions affectés
+pub fn delete_project(
+    conn: &Connection,
+    project_id: i64,
+    policy: &ProjectDeletionPolicy,
+) -> Result<ProjectDeletionPreview> {
+    let tx = conn.unchecked_transaction()?;
+    let preview = preview_project_deletion(&tx, project_id, policy)?;
+
+    let subtree = "WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )";
+
+    match policy {
+        // Les tâches sont détachées par ON DELETE SET NULL
+        ProjectDeletionPolicy::Orphan => {}
+        ProjectDeletionPolicy::MoveTasks { target_project_id } => {
+            tx.execute(
+                &format!(
+                    "{subtree}
+                    UPDATE tasks
+                    SET project_id = ?2, updated_at = datetime('now')
+                    WHERE project_id IN (SELECT id FROM subtree)"
+                ),
+                params![project_id, target_project_id],
+            )?;
+        }
+        ProjectDeletionPolicy::DeleteTasks => {
+            tx.execute(
+                &format!(
+                    "{subtree}
+                    DELETE FROM pomodoro_sessions
+                    WHERE task_id IN (
+                        SELECT id FROM tasks WHERE project_id IN (SELECT id FROM subtree)
+                    )"
+                ),
+                [project_id],
+            )?;
+            tx.execute(
+                &format!(
+                    "{subtree}
+                    DELETE FROM tasks WHERE project_id IN (SELECT id FROM subtree)"
+                ),
+                [project_id],
+            )?;
+        }
+    }
+
+    // Les sous-projets sont supprimés par ON DELETE CASCADE
+    tx.execute("DELETE FROM projects WHERE id = ?1", [project_id])?;
+    tx.commit()?;
+
+    Ok(preview)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +491,7 @@ mod tests {
         let input = CreateProjectInput {
             name: "Work".to_string(),
             color: Some("#FF0000".to_string()),
+            parent_project_id: None,
         };
 
         let project = create_project(&conn, &input).unwrap();
@@ -132,16 +509,199 @@ mod tests {
         let input1 = CreateProjectInput {
             name: "Project 1".to_string(),
             color: None,
+            parent_project_id: None,
         };
         let input2 = CreateProjectInput {
             name: "Project 2".to_string(),
             color: Some("#00FF00".to_string()),
+            parent_project_id: None,
         };
 
         create_project(&conn, &input1).unwrap();
         create_project(&conn, &input2).unwrap();
+        // Insertion sans date de statut (import, fixture)
+        conn.execute("INSERT INTO projects (name) VALUES ('Project 3')", []).unwrap();
+
+        let projects = get_all_projects(&conn).unwrap();
+        assert_eq!(projects.len(), 3);
+        let imported = projects.iter().find(|p| p.name == "Project 3").unwrap();
+        assert_eq!(imported.status_changed_at, imported.created_at);
+    }
+
+    #[test]
+    fn test_archived_projects_hidden_by_default() {
+        let conn = setup_test_db();
+
+        let kept = create_project(&conn, &CreateProjectInput {
+            name: "Kept".to_string(),
+            color: None,
+            parent_project_id: None,
+        })
+        .unwrap();
+        let archived = create_project(&conn, &CreateProjectInput {
+            name: "Old".to_string(),
+            color: None,
+            parent_project_id: None,
+        })
+        .unwrap();
+        assert_eq!(kept.status, ProjectStatus::Active);
+
+        let archived = set_project_status(&conn, archived.id, &ProjectStatus::Archived).unwrap();
+        assert_eq!(archived.status, ProjectStatus::Archived);
+        assert!(archived.archived_at.is_some());
 
         let projects = get_all_projects(&conn).unwrap();
-        assert_eq!(projects.len(), 2);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].id, kept.id);
+
+        let listed = get_projects_by_status(&conn, &ProjectStatus::Archived).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, archived.id);
+
+        let restored = set_project_status(&conn, archived.id, &ProjectStatus::Active).unwrap();
+        assert!(restored.archived_at.is_none());
+        assert_eq!(get_all_projects(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_completed_at_kept_when_archiving() {
+        let conn = setup_test_db();
+        let project = create_project(&conn, &CreateProjectInput {
+            name: "Done".to_string(),
+            color: None,
+            parent_project_id: None,
+        })
+        .unwrap();
+
+        let completed = set_project_status(&conn, project.id, &ProjectStatus::Completed).unwrap();
+        assert!(completed.completed_at.is_some());
+
+        let archived = set_project_status(&conn, project.id, &ProjectStatus::Archived).unwrap();
+        assert_eq!(archived.completed_at, completed.completed_at);
+    }
+
+    fn create_child(conn: &Connection, name: &str, parent_project_id: Option<i64>) -> Project {
+        create_project(conn, &CreateProjectInput {
+            name: name.to_string(),
+            color: None,
+            parent_project_id,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_move_project_rejects_cycles() {
+        let conn = setup_test_db();
+        let area = create_child(&conn, "Area", None);
+        let project = create_child(&conn, "Project", Some(area.id));
+        let sub = create_child(&conn, "Sub-project", Some(project.id));
+
+        assert_eq!(get_ancestor_project_ids(&conn, sub.id).unwrap(), vec![project.id, area.id]);
+        let mut descendants = get_descendant_project_ids(&conn, area.id).unwrap();
+        descendants.sort();
+        assert_eq!(descendants, vec![project.id, sub.id]);
+
+        assert!(move_project(&conn, area.id, Some(sub.id)).is_err());
+        assert!(move_project(&conn, area.id, Some(area.id)).is_err());
+
+        let moved = move_project(&conn, sub.id, None).unwrap();
+        assert_eq!(moved.parent_project_id, None);
+        assert!(move_project(&conn, area.id, Some(sub.id)).is_ok());
+    }
+
+    #[test]
+    fn test_archive_and_delete_cascade_to_descendants() {
+        let conn = setup_test_db();
+        let area = create_child(&conn, "Area", None);
+        let project = create_child(&conn, "Project", Some(area.id));
+        let sub = create_child(&conn, "Sub-project", Some(project.id));
+
+        set_project_status(&conn, area.id, &ProjectStatus::Archived).unwrap();
+        assert_eq!(get_project_by_id(&conn, sub.id).unwrap().status, ProjectStatus::Archived);
+        assert!(get_all_projects(&conn).unwrap().is_empty());
+
+        set_project_status(&conn, area.id, &ProjectStatus::Active).unwrap();
+        assert_eq!(get_project_by_id(&conn, sub.id).unwrap().status, ProjectStatus::Active);
+
+        delete_project(&conn, area.id, &ProjectDeletionPolicy::Orphan).unwrap();
+        assert!(get_project_by_id(&conn, sub.id).is_err());
+    }
+
+    #[test]
+    fn test_project_tree_rolls_up_totals() {
+        let conn = setup_test_db();
+        let area = create_child(&conn, "Area", None);
+        let project = create_child(&conn, "Project", Some(area.id));
+
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, is_completed) VALUES ('Task', ?1, 1)",
+            [project.id],
+        )
+        .unwrap();
+        let task_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+             VALUES (?1, datetime('now'), datetime('now'), 25, 'work')",
+            [task_id],
+        )
+        .unwrap();
+
+        let tree = get_project_tree(&conn, false).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].project.id, area.id);
+        assert_eq!(tree[0].own_totals, ProjectTotals::default());
+        assert_eq!(tree[0].totals.total_focus_minutes, 25);
+        assert_eq!(tree[0].totals.completed_task_count, 1);
+        assert_eq!(tree[0].children[0].own_totals.completed_pomodoros_count, 1);
+    }
+
+    fn create_task_with_session(conn: &Connection, project_id: i64) -> i64 {
+        conn.execute(
+            "INSERT INTO tasks (title, project_id) VALUES ('Task', ?1)",
+            [project_id],
+        )
+        .unwrap();
+        let task_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+             VALUES (?1, datetime('now'), datetime('now'), 25, 'work')",
+            [task_id],
+        )
+        .unwrap();
+        task_id
+    }
+
+    #[test]
+    fn test_delete_project_policies() {
+        let conn = setup_test_db();
+        let target = create_child(&conn, "Target", None);
+        let doomed = create_child(&conn, "Doomed", None);
+        let sub = create_child(&conn, "Doomed sub", Some(doomed.id));
+        create_task_with_session(&conn, doomed.id);
+        let moved_task = create_task_with_session(&conn, sub.id);
+
+        let policy = ProjectDeletionPolicy::MoveTasks { target_project_id: target.id };
+        let preview = preview_project_deletion(&conn, doomed.id, &policy).unwrap();
+        assert_eq!(
+            preview,
+            ProjectDeletionPreview { project_count: 2, task_count: 2, session_count: 2 }
+        );
+
+        let invalid = ProjectDeletionPolicy::MoveTasks { target_project_id: sub.id };
+        assert!(delete_project(&conn, doomed.id, &invalid).is_err());
+        assert!(get_project_by_id(&conn, doomed.id).is_ok());
+
+        assert_eq!(delete_project(&conn, doomed.id, &policy).unwrap(), preview);
+        let project_id: Option<i64> = conn
+            .query_row("SELECT project_id FROM tasks WHERE id = ?1", [moved_task], |row| row.get(0))
+            .unwrap();
+        assert_eq!(project_id, Some(target.id));
+
+        let deleted = delete_project(&conn, target.id, &ProjectDeletionPolicy::DeleteTasks).unwrap();
+        assert_eq!(deleted.task_count, 2);
+        let remaining: i32 = conn
+            .query_row("SELECT COUNT(*) FROM pomodoro_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0);
     }
 }
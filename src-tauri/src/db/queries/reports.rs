@@ -0,0 +1,173 @@
+// Requêtes SQL pour les rapports de période et leurs modèles
+use crate::db::models::{CompletedTaskEntry, ReportData, ReportFormat, SummaryGranularity};
+use crate::db::queries::{get_period_comparison, get_summary_breakdown, local_day};
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Result, params};
+
+/// Rassemble les données du rapport de la période contenant `date`
+///
+/// La période précédente (semaine, mois... juste avant) sert de comparaison ;
+/// une période en cours n'est couverte que jusqu'à `today`.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `period` - Taille de la période (jour, semaine ISO, mois ou année)
+/// * `date` - Jour local quelconque de la période
+/// * `today` - Jour local courant
+pub fn get_report_data(
+    conn: &Connection,
+    period: SummaryGranularity,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> Result<ReportData> {
+    let comparison = get_period_comparison(conn, period, date, today)?;
+
+    Ok(ReportData {
+        breakdown: get_summary_breakdown(conn, &comparison.current_start, &comparison.current_end)?,
+        completed_tasks: get_completed_tasks_in_range(
+            conn,
+            &comparison.current_start,
+            &comparison.current_end,
+        )?,
+        comparison,
+    })
+}
+
+/// Liste les tâches complétées sur une plage de jours locaux, par ordre chronologique
+fn get_completed_tasks_in_range(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<CompletedTaskEntry>> {
+    let task_day = local_day("t.completed_at", "t.completed_utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.id, t.title, p.name, {task_day} AS day
+         FROM tasks t
+         LEFT JOIN projects p ON p.id = t.project_id
+         WHERE t.is_completed = 1 AND {task_day} BETWEEN ?1 AND ?2
+         ORDER BY t.completed_at, t.id"
+    ))?;
+    let tasks = stmt.query_map(params![start_date, end_date], |row| {
+        Ok(CompletedTaskEntry {
+            task_id: row.get(0)?,
+            title: row.get(1)?,
+            project_name: row.get(2)?,
+            completed_date: row.get(3)?,
+        })
+    })?;
+
+    tasks.collect()
+}
+
+/// Récupère le modèle personnalisé d'un format (None si le modèle intégré est utilisé)
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `format` - Format du rapport
+pub fn get_report_template(conn: &Connection, format: ReportFormat) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT content FROM report_templates WHERE format = ?1",
+        [format.as_str()],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Enregistre le modèle personnalisé d'un format
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `format` - Format du rapport
+/// * `content` - Contenu du modèle
+pub fn set_report_template(conn: &Connection, format: ReportFormat, content: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO report_templates (format, content) VALUES (?1, ?2)
+         ON CONFLICT(format) DO UPDATE SET content = ?2, updated_at = CURRENT_TIMESTAMP",
+        params![format.as_str(), content],
+    )?;
+
+    Ok(())
+}
+
+/// Supprime le modèle personnalisé d'un format (retour au modèle intégré)
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `format` - Format du rapport
+pub fn delete_report_template(conn: &Connection, format: ReportFormat) -> Result<()> {
+    conn.execute(
+        "DELETE FROM report_templates WHERE format = ?1",
+        [format.as_str()],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_report_data_with_previous_period() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, is_completed, completed_at)
+             VALUES ('Ship v2', 1, 1, '2025-11-13 16:00:00')",
+            [],
+        )
+        .unwrap();
+        for (started_at, interrupted) in [
+            ("2025-11-05 09:00:00", false),
+            ("2025-11-12 09:00:00", false),
+            ("2025-11-13 09:00:00", false),
+            ("2025-11-13 10:00:00", true),
+        ] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type, interrupted)
+                 VALUES (1, ?1, ?1, 25, 'work', ?2)",
+                params![started_at, interrupted],
+            )
+            .unwrap();
+        }
+
+        let date = NaiveDate::from_ymd_opt(2025, 11, 14).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let report = get_report_data(&conn, SummaryGranularity::Week, date, today).unwrap();
+        let comparison = &report.comparison;
+        assert_eq!(comparison.current_start, "2025-11-10");
+        assert_eq!(comparison.current_end, "2025-11-16");
+        assert_eq!(comparison.completed_pomodoros_count.current, 2.0);
+        assert_eq!(comparison.previous_start, "2025-11-03");
+        assert_eq!(comparison.completed_pomodoros_count.previous, 1.0);
+        assert_eq!(comparison.interrupted_count.current, 1.0);
+        assert_eq!(comparison.interrupted_count.previous, 0.0);
+        assert_eq!(report.breakdown.projects[0].total_focus_minutes, 50);
+        assert_eq!(report.completed_tasks.len(), 1);
+        assert_eq!(report.completed_tasks[0].completed_date, "2025-11-13");
+    }
+
+    #[test]
+    fn test_report_template_override() {
+        let conn = setup_test_db();
+        assert_eq!(get_report_template(&conn, ReportFormat::Markdown).unwrap(), None);
+
+        set_report_template(&conn, ReportFormat::Markdown, "# {{start_date}}").unwrap();
+        set_report_template(&conn, ReportFormat::Markdown, "## {{start_date}}").unwrap();
+        assert_eq!(
+            get_report_template(&conn, ReportFormat::Markdown).unwrap().as_deref(),
+            Some("## {{start_date}}")
+        );
+
+        delete_report_template(&conn, ReportFormat::Markdown).unwrap();
+        assert_eq!(get_report_template(&conn, ReportFormat::Markdown).unwrap(), None);
+    }
+}
@@ -0,0 +1,309 @@
+// Requêtes SQL de rétention et de purge de l'historique
+use crate::db::models::PurgeReport;
+use crate::db::queries::{constraint_violation, get_retention_settings, local_day};
+use crate::db::sync;
+use chrono::{Months, NaiveDate};
+use rusqlite::{Connection, Result, params};
+
+/// Applique les règles de rétention, ou calcule leur effet sans rien modifier
+///
+/// Tout se fait dans une seule transaction :
+/// - les sessions dont le jour local précède la limite sont supprimées, les
+///   sessions de travail étant d'abord regroupées dans `session_aggregates`
+///   (par jour local et par tâche, avec le projet de la tâche) ;
+/// - les tâches complétées avant leur limite sont supprimées, après avoir été
+///   comptées dans `task_aggregates` (par jour local et par projet) ; leurs
+///   sessions de travail encore conservées sont regroupées de la même façon,
+///   pour rester comptées dans leur projet.
+///
+/// Les résumés, séries et heatmaps lisent ces agrégats et restent donc
+/// inchangés. La purge n'est pas synchronisée : chaque appareil applique ses
+/// propres règles. En aperçu (`apply` faux), la transaction est annulée.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `today` - Jour local courant, à partir duquel les limites sont calculées
+/// * `apply` - Vrai pour appliquer la purge, faux pour un aperçu
+pub fn purge_history(conn: &Connection, today: NaiveDate, apply: bool) -> Result<PurgeReport> {
+    let settings = get_retention_settings(conn)?;
+    let session_cutoff = settings
+        .session_retention_months
+        .map(|months| retention_cutoff(today, months))
+        .transpose()?;
+    let task_cutoff = settings
+        .completed_task_retention_months
+        .map(|months| retention_cutoff(today, months))
+        .transpose()?;
+
+    let mut report = PurgeReport {
+        session_cutoff: session_cutoff.clone(),
+        task_cutoff: task_cutoff.clone(),
+        deleted_sessions_count: 0,
+        session_aggregates_count: 0,
+        deleted_tasks_count: 0,
+        applied: apply,
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    sync::set_tracking_suspended(&tx, true)?;
+
+    if let Some(cutoff) = &session_cutoff {
+        let session_day = local_day("started_at", "utc_offset_minutes");
+        let condition = format!("{session_day} < ?1");
+        report.session_aggregates_count += collapse_work_sessions(&tx, &condition, cutoff)?;
+        report.deleted_sessions_count += tx.execute(
+            &format!("DELETE FROM pomodoro_sessions WHERE {condition}"),
+            params![cutoff],
+        )? as i32;
+    }
+
+    if let Some(cutoff) = &task_cutoff {
+        let task_day = local_day("completed_at", "completed_utc_offset_minutes");
+        // Sans tâche, une session perdrait son projet
+        let condition = format!(
+            "session_type = 'work'
+             AND task_id IN (SELECT id FROM tasks WHERE is_completed = 1 AND {task_day} < ?1)"
+        );
+        report.session_aggregates_count += collapse_work_sessions(&tx, &condition, cutoff)?;
+        report.deleted_sessions_count += tx.execute(
+            &format!("DELETE FROM pomodoro_sessions WHERE {condition}"),
+            params![cutoff],
+        )? as i32;
+
+        tx.execute(
+            &format!(
+                "INSERT INTO task_aggregates (day, project_id, completed_count)
+                 SELECT {task_day} AS day, project_id, COUNT(*)
+                 FROM tasks
+                 WHERE is_completed = 1 AND {task_day} < ?1
+                 GROUP BY day, project_id"
+            ),
+            params![cutoff],
+        )?;
+        report.deleted_tasks_count = tx.execute(
+            &format!("DELETE FROM tasks WHERE is_completed = 1 AND {task_day} < ?1"),
+            params![cutoff],
+        )? as i32;
+    }
+
+    sync::set_tracking_suspended(&tx, false)?;
+    if apply {
+        tx.commit()?;
+    } else {
+        tx.rollback()?;
+    }
+
+    Ok(report)
+}
+
+/// Regroupe dans `session_aggregates` les sessions de travail vérifiant `condition`
+///
+/// Les sessions sont regroupées par jour local et par tâche, avec le projet de
+/// la tâche. `condition` est une expression SQL sur `pomodoro_sessions`
+/// utilisant `?1`. Retourne le nombre d'agrégats créés.
+fn collapse_work_sessions(conn: &Connection, condition: &str, cutoff: &str) -> Result<i32> {
+    let session_day = local_day("started_at", "utc_offset_minutes");
+    let count = conn.execute(
+        &format!(
+            "INSERT INTO session_aggregates (day, task_id, project_id, completed_count,
+                                             focus_minutes, interrupted_count)
+             SELECT {session_day} AS day, task_id,
+                    (SELECT t.project_id FROM tasks t WHERE t.id = pomodoro_sessions.task_id),
+                    SUM(completed_at IS NOT NULL AND interrupted = 0),
+                    SUM(CASE WHEN completed_at IS NOT NULL AND interrupted = 0
+                             THEN duration_minutes ELSE 0 END),
+                    SUM(interrupted = 1)
+             FROM pomodoro_sessions
+             WHERE session_type = 'work' AND {condition}
+             GROUP BY day, task_id
+             HAVING SUM(completed_at IS NOT NULL AND interrupted = 0) > 0
+                 OR SUM(interrupted = 1) > 0"
+        ),
+        params![cutoff],
+    )?;
+
+    Ok(count as i32)
+}
+
+/// Premier jour local conservé pour une durée de rétention en mois
+fn retention_cutoff(today: NaiveDate, months: i32) -> Result<String> {
+    u32::try_from(months)
+        .ok()
+        .filter(|months| *months > 0)
+        .and_then(|months| today.checked_sub_months(Months::new(months)))
+        .map(|cutoff| cutoff.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| {
+            constraint_violation(format!("Invalid retention period: {} months", months))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use crate::db::models::{RetentionSettings, SummaryGranularity};
+    use crate::db::queries::{
+        calculate_focus_minutes_by_date, count_completed_sessions_by_date, get_period_comparison,
+        get_project_stats, get_range_summary, get_summary_breakdown, update_retention_settings,
+    };
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, is_completed, completed_at)
+             VALUES ('Old spec', 1, 1, '2023-03-02 18:00:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Ongoing', 1)", []).unwrap();
+        for (task_id, started_at, interrupted) in [
+            (1, "2023-03-01 09:00:00", 0),
+            (1, "2023-03-01 10:00:00", 0),
+            (2, "2023-03-01 11:00:00", 1),
+            (2, "2025-11-10 09:00:00", 0),
+        ] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes,
+                                                session_type, interrupted)
+                 VALUES (?1, ?2, ?2, 25, 'work', ?3)",
+                params![task_id, started_at, interrupted],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type)
+             VALUES ('2023-03-01 09:25:00', '2023-03-01 09:30:00', 5, 'short_break')",
+            [],
+        )
+        .unwrap();
+        update_retention_settings(
+            &conn,
+            &RetentionSettings {
+                session_retention_months: Some(24),
+                completed_task_retention_months: Some(12),
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_preview_changes_nothing() {
+        let conn = setup_test_db();
+        let today = NaiveDate::from_ymd_opt(2025, 11, 15).unwrap();
+
+        let report = purge_history(&conn, today, false).unwrap();
+        assert!(!report.applied);
+        assert_eq!(report.session_cutoff.as_deref(), Some("2023-11-15"));
+        assert_eq!(report.task_cutoff.as_deref(), Some("2024-11-15"));
+        assert_eq!(report.deleted_sessions_count, 4);
+        assert_eq!(report.session_aggregates_count, 2);
+        assert_eq!(report.deleted_tasks_count, 1);
+
+        let (sessions, aggregates): (i32, i32) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM pomodoro_sessions),
+                        (SELECT COUNT(*) FROM session_aggregates)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((sessions, aggregates), (5, 0));
+    }
+
+    #[test]
+    fn test_summaries_unchanged_after_purge() {
+        let conn = setup_test_db();
+        let today = NaiveDate::from_ymd_opt(2025, 11, 15).unwrap();
+        let range = |conn: &Connection| {
+            get_range_summary(conn, "2023-01-01", "2025-12-31", SummaryGranularity::Month).unwrap()
+        };
+        let march = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+
+        let summary_before = range(&conn);
+        let comparison_before =
+            get_period_comparison(&conn, SummaryGranularity::Month, march, today).unwrap();
+        let breakdown_before = get_summary_breakdown(&conn, "2023-03-01", "2023-03-01").unwrap();
+        let stats_before = get_project_stats(&conn, 1, None, None).unwrap();
+
+        let report = purge_history(&conn, today, true).unwrap();
+        assert!(report.applied);
+
+        let summary_after = range(&conn);
+        assert_eq!(summary_after.totals, summary_before.totals);
+        assert_eq!(summary_after.buckets, summary_before.buckets);
+        assert_eq!(summary_after.totals.completed_tasks_count, 1);
+        assert_eq!(summary_after.totals.completed_pomodoros_count, 3);
+
+        let comparison_after =
+            get_period_comparison(&conn, SummaryGranularity::Month, march, today).unwrap();
+        assert_eq!(comparison_after.interrupted_count, comparison_before.interrupted_count);
+        assert_eq!(comparison_after.interrupted_count.current, 1.0);
+
+        // La tâche supprimée n'apparaît plus, mais ses sessions restent dans son projet
+        let breakdown_after = get_summary_breakdown(&conn, "2023-03-01", "2023-03-01").unwrap();
+        assert_eq!(breakdown_after.unassigned_pomodoros_count, 0);
+        assert_eq!(breakdown_after.projects.len(), 1);
+        let (project_before, project_after) =
+            (&breakdown_before.projects[0], &breakdown_after.projects[0]);
+        assert_eq!(project_after.project_id, project_before.project_id);
+        assert_eq!(project_after.completed_pomodoros_count, 2);
+        assert_eq!(project_after.total_focus_minutes, 50);
+        assert!(project_after.tasks.is_empty());
+
+        // Résumé quotidien et statistiques du projet
+        assert_eq!(count_completed_sessions_by_date(&conn, "2023-03-01").unwrap(), 2);
+        assert_eq!(calculate_focus_minutes_by_date(&conn, "2023-03-01").unwrap(), 50);
+        let stats_after = get_project_stats(&conn, 1, None, None).unwrap();
+        assert_eq!(stats_after.completed_pomodoros_count, 3);
+        assert_eq!(stats_after.total_focus_minutes, stats_before.total_focus_minutes);
+        assert_eq!(stats_after.completed_tasks_count, stats_before.completed_tasks_count);
+        assert_eq!(stats_after.completion_rate, stats_before.completion_rate);
+        assert_eq!(stats_after.interruption_rate, stats_before.interruption_rate);
+        assert_eq!(stats_after.weekly_velocity, stats_before.weekly_velocity);
+        assert_eq!(stats_after.first_activity_at.as_deref(), Some("2023-03-01"));
+
+        // Une seconde purge ne trouve plus rien
+        let report = purge_history(&conn, today, true).unwrap();
+        assert_eq!(report.deleted_sessions_count, 0);
+        assert_eq!(report.deleted_tasks_count, 0);
+    }
+
+    #[test]
+    fn test_task_purge_keeps_recent_sessions_in_project() {
+        let conn = setup_test_db();
+        let today = NaiveDate::from_ymd_opt(2025, 11, 15).unwrap();
+        // Tâche purgée (limite 2024-11-15) dont la session est encore conservée
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, is_completed, completed_at)
+             VALUES ('Review', 1, 1, '2024-06-01 18:00:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes,
+                                            session_type)
+             VALUES (3, '2024-05-31 09:00:00', '2024-05-31 09:25:00', 25, 'work')",
+            [],
+        )
+        .unwrap();
+        let stats = |conn: &Connection| {
+            get_project_stats(conn, 1, Some("2024-01-01"), Some("2024-12-31")).unwrap()
+        };
+        let before = stats(&conn);
+        assert_eq!((before.total_focus_minutes, before.completed_pomodoros_count), (25, 1));
+
+        let report = purge_history(&conn, today, true).unwrap();
+        assert_eq!(report.deleted_tasks_count, 2);
+        assert_eq!(report.deleted_sessions_count, 5);
+
+        let after = stats(&conn);
+        assert_eq!((after.total_focus_minutes, after.completed_pomodoros_count), (25, 1));
+        assert_eq!(after.completed_tasks_count, before.completed_tasks_count);
+        assert_eq!(after.weekly_velocity, before.weekly_velocity);
+    }
+}
@@ -1,5 +1,6 @@
 // Requêtes SQL pour la gestion des sessions Pomodoro
 use crate::db::models::{CreateSessionInput, PomodoroSession, SessionType};
+use crate::db::queries::{completed_work, current_utc_offset_minutes, local_day};
 use rusqlite::{Connection, Result, params};
 
 /// Crée une nouvelle session Pomodoro
@@ -9,12 +10,13 @@ use rusqlite::{Connection, Result, params};
 /// * `input` - Données de la session à créer
 pub fn create_session(conn: &Connection, input: &CreateSessionInput) -> Result<PomodoroSession> {
     conn.execute(
-        "INSERT INTO pomodoro_sessions (task_id, started_at, duration_minutes, session_type)
-         VALUES (?1, datetime('now'), ?2, ?3)",
+        "INSERT INTO pomodoro_sessions (task_id, started_at, duration_minutes, session_type, utc_offset_minutes)
+         VALUES (?1, datetime('now'), ?2, ?3, ?4)",
         params![
             &input.task_id,
             &input.duration_minutes,
             input.session_type.as_str(),
+            input.utc_offset_minutes.unwrap_or_else(current_utc_offset_minutes),
         ],
     )?;
 
@@ -54,6 +56,25 @@ pub fn interrupt_session(conn: &Connection, session_id: i64) -> Result<PomodoroS
     get_session_by_id(conn, session_id)
 }
 
+/// Enregistre (ou efface) la note de concentration d'une session
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `session_id` - ID de la session
+/// * `rating` - Note de 1 à 5, ou None pour l'effacer
+pub fn rate_session(
+    conn: &Connection,
+    session_id: i64,
+    rating: Option<i32>,
+) -> Result<PomodoroSession> {
+    conn.execute(
+        "UPDATE pomodoro_sessions SET focus_rating = ?1 WHERE id = ?2",
+        params![rating, session_id],
+    )?;
+
+    get_session_by_id(conn, session_id)
+}
+
 /// Récupère une session par son ID
 ///
 /// # Arguments
@@ -61,7 +82,8 @@ pub fn interrupt_session(conn: &Connection, session_id: i64) -> Result<PomodoroS
 /// * `session_id` - ID de la session
 fn get_session_by_id(conn: &Connection, session_id: i64) -> Result<PomodoroSession> {
     conn.query_row(
-        "SELECT id, task_id, started_at, completed_at, duration_minutes, session_type, interrupted
+        "SELECT id, task_id, started_at, completed_at, duration_minutes, session_type, interrupted,
+                utc_offset_minutes, focus_rating
          FROM pomodoro_sessions
          WHERE id = ?1",
         [session_id],
@@ -78,6 +100,8 @@ fn get_session_by_id(conn: &Connection, session_id: i64) -> Result<PomodoroSessi
                 duration_minutes: row.get(4)?,
                 session_type,
                 interrupted: row.get::<_, i32>(6)? != 0,
+                utc_offset_minutes: row.get(7)?,
+                focus_rating: row.get(8)?,
             })
         },
     )
@@ -85,6 +109,8 @@ fn get_session_by_id(conn: &Connection, session_id: i64) -> Result<PomodoroSessi
 
 /// Récupère toutes les sessions d'une plage de dates
 ///
+/// Les dates sont des jours locaux (voir `local_day`)
+///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
 /// * `start_date` - Date de début (format ISO: YYYY-MM-DD)
@@ -94,12 +120,14 @@ pub fn get_sessions_by_date_range(
     start_date: &str,
     end_date: &str,
 ) -> Result<Vec<PomodoroSession>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, task_id, started_at, completed_at, duration_minutes, session_type, interrupted
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, task_id, started_at, completed_at, duration_minutes, session_type, interrupted,
+                utc_offset_minutes, focus_rating
          FROM pomodoro_sessions
-         WHERE DATE(started_at) BETWEEN ?1 AND ?2
+         WHERE {} BETWEEN ?1 AND ?2
          ORDER BY started_at DESC",
-    )?;
+        local_day("started_at", "utc_offset_minutes")
+    ))?;
 
     let sessions = stmt.query_map(params![start_date, end_date], |row| {
         let session_type_str: String = row.get(5)?;
@@ -114,6 +142,8 @@ pub fn get_sessions_by_date_range(
             duration_minutes: row.get(4)?,
             session_type,
             interrupted: row.get::<_, i32>(6)? != 0,
+            utc_offset_minutes: row.get(7)?,
+            focus_rating: row.get(8)?,
         })
     })?;
 
@@ -122,17 +152,17 @@ pub fn get_sessions_by_date_range(
 
 /// Compte les sessions complétées pour une date donnée
 ///
+/// Les sessions regroupées par `purge_history` sont comptées via leurs agrégats.
+///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
-/// * `date` - Date cible (format ISO: YYYY-MM-DD)
+/// * `date` - Jour local cible (format ISO: YYYY-MM-DD)
 pub fn count_completed_sessions_by_date(conn: &Connection, date: &str) -> Result<i32> {
     conn.query_row(
-        "SELECT COUNT(*)
-         FROM pomodoro_sessions
-         WHERE DATE(started_at) = ?1
-           AND completed_at IS NOT NULL
-           AND interrupted = 0
-           AND session_type = 'work'",
+        &format!(
+            "SELECT COALESCE(SUM(pomodoros), 0) FROM {} WHERE day = ?1",
+            completed_work()
+        ),
         [date],
         |row| row.get(0),
     )
@@ -140,22 +170,20 @@ pub fn count_completed_sessions_by_date(conn: &Connection, date: &str) -> Result
 
 /// Calcule le total de minutes de focus pour une date
 ///
+/// Les sessions regroupées par `purge_history` sont comptées via leurs agrégats.
+///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
-/// * `date` - Date cible (format ISO: YYYY-MM-DD)
+/// * `date` - Jour local cible (format ISO: YYYY-MM-DD)
 pub fn calculate_focus_minutes_by_date(conn: &Connection, date: &str) -> Result<i32> {
-    let result: Option<i32> = conn.query_row(
-        "SELECT SUM(duration_minutes)
-         FROM pomodoro_sessions
-         WHERE DATE(started_at) = ?1
-           AND completed_at IS NOT NULL
-           AND interrupted = 0
-           AND session_type = 'work'",
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(focus_minutes), 0) FROM {} WHERE day = ?1",
+            completed_work()
+        ),
         [date],
         |row| row.get(0),
-    )?;
-
-    Ok(result.unwrap_or(0))
+    )
 }
 
 #[cfg(test)]
@@ -177,6 +205,7 @@ mod tests {
             task_id: None,
             duration_minutes: 25,
             session_type: SessionType::Work,
+            utc_offset_minutes: None,
         };
 
         let session = create_session(&conn, &input).unwrap();
@@ -196,6 +225,7 @@ mod tests {
             task_id: None,
             duration_minutes: 25,
             session_type: SessionType::Work,
+            utc_offset_minutes: None,
         };
 
         let session = create_session(&conn, &input).unwrap();
@@ -204,4 +234,33 @@ mod tests {
         assert!(interrupted.completed_at.is_some());
         assert!(interrupted.interrupted);
     }
+
+    #[test]
+    fn test_sessions_bucketed_by_local_day() {
+        let conn = setup_test_db();
+
+        // 23:30 à Paris en hiver (UTC+1) : 22:30 UTC, le jour local est le 10
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type, utc_offset_minutes)
+             VALUES ('2025-11-10 22:30:00', '2025-11-10 22:55:00', 25, 'work', 60)",
+            [],
+        )
+        .unwrap();
+        // 01:30 heure locale le 11 (00:30 UTC)
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type, utc_offset_minutes)
+             VALUES ('2025-11-11 00:30:00', '2025-11-11 00:55:00', 25, 'work', 60)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(count_completed_sessions_by_date(&conn, "2025-11-10").unwrap(), 1);
+        assert_eq!(count_completed_sessions_by_date(&conn, "2025-11-11").unwrap(), 1);
+
+        // Avec une journée qui commence à 04:00, la session de 01:30 compte pour le 10
+        conn.execute("UPDATE settings SET day_start_hour = 4 WHERE id = 1", []).unwrap();
+        assert_eq!(count_completed_sessions_by_date(&conn, "2025-11-10").unwrap(), 2);
+        assert_eq!(calculate_focus_minutes_by_date(&conn, "2025-11-10").unwrap(), 50);
+        assert_eq!(get_sessions_by_date_range(&conn, "2025-11-11", "2025-11-11").unwrap().len(), 0);
+    }
 }
@@ -1,5 +1,7 @@
 // Requêtes SQL pour la gestion des paramètres
-use crate::db::models::Settings;
+use crate::db::models::{BackupSettings, GoalMetric, RetentionSettings, Settings};
+use crate::db::queries::constraint_violation;
+use chrono::{Duration, NaiveDateTime};
 use rusqlite::{Connection, Result, params};
 
 /// Récupère les paramètres de l'application
@@ -12,11 +14,15 @@ pub fn get_settings(conn: &Connection) -> Result<Settings> {
     conn.query_row(
         "SELECT work_duration, short_break_duration, long_break_duration,
                 pomodoros_until_long_break, language, theme, notification_sound,
-                auto_start_breaks, auto_start_pomodoros
+                auto_start_breaks, auto_start_pomodoros, day_start_hour,
+                daily_goal_metric, daily_goal_target, rest_weekdays
          FROM settings
          WHERE id = 1",
         [],
         |row| {
+            let metric_str: String = row.get(10)?;
+            let rest_weekdays_str: String = row.get(12)?;
+
             Ok(Settings {
                 work_duration: row.get(0)?,
                 short_break_duration: row.get(1)?,
@@ -27,6 +33,14 @@ pub fn get_settings(conn: &Connection) -> Result<Settings> {
                 notification_sound: row.get(6)?,
                 auto_start_breaks: row.get::<_, i32>(7)? != 0,
                 auto_start_pomodoros: row.get::<_, i32>(8)? != 0,
+                day_start_hour: row.get(9)?,
+                daily_goal_metric: metric_str.parse::<GoalMetric>()
+                    .map_err(|_| rusqlite::Error::InvalidQuery)?,
+                daily_goal_target: row.get(11)?,
+                rest_weekdays: rest_weekdays_str
+                    .split(',')
+                    .filter_map(|day| day.trim().parse().ok())
+                    .collect(),
             })
         },
     )
@@ -38,6 +52,19 @@ pub fn get_settings(conn: &Connection) -> Result<Settings> {
 /// * `conn` - Connexion à la base de données
 /// * `settings` - Nouveaux paramètres à enregistrer
 pub fn update_settings(conn: &Connection, settings: &Settings) -> Result<Settings> {
+    if let Some(day) = settings.rest_weekdays.iter().find(|day| !(1..=7).contains(*day)) {
+        return Err(constraint_violation(format!(
+            "Invalid rest weekday: {} (expected 1 = Monday to 7 = Sunday)",
+            day
+        )));
+    }
+    let rest_weekdays = settings
+        .rest_weekdays
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
     conn.execute(
         "UPDATE settings
          SET work_duration = ?1,
@@ -49,6 +76,10 @@ pub fn update_settings(conn: &Connection, settings: &Settings) -> Result<Setting
              notification_sound = ?7,
              auto_start_breaks = ?8,
              auto_start_pomodoros = ?9,
+             day_start_hour = ?10,
+             daily_goal_metric = ?11,
+             daily_goal_target = ?12,
+             rest_weekdays = ?13,
              updated_at = datetime('now')
          WHERE id = 1",
         params![
@@ -61,12 +92,150 @@ pub fn update_settings(conn: &Connection, settings: &Settings) -> Result<Setting
             &settings.notification_sound,
             if settings.auto_start_breaks { 1 } else { 0 },
             if settings.auto_start_pomodoros { 1 } else { 0 },
+            &settings.day_start_hour,
+            settings.daily_goal_metric.as_str(),
+            &settings.daily_goal_target,
+            rest_weekdays,
         ],
     )?;
 
     get_settings(conn)
 }
 
+/// Récupère les paramètres des sauvegardes automatiques
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn get_backup_settings(conn: &Connection) -> Result<BackupSettings> {
+    conn.query_row(
+        "SELECT backup_directory, backup_interval_hours FROM settings WHERE id = 1",
+        [],
+        |row| {
+            Ok(BackupSettings {
+                directory: row.get(0)?,
+                interval_hours: row.get(1)?,
+            })
+        },
+    )
+}
+
+/// Met à jour les paramètres des sauvegardes automatiques
+///
+/// Un répertoire vide revient au répertoire par défaut.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `settings` - Nouveaux paramètres
+pub fn update_backup_settings(
+    conn: &Connection,
+    settings: &BackupSettings,
+) -> Result<BackupSettings> {
+    if settings.interval_hours < 0 {
+        return Err(constraint_violation(format!(
+            "Invalid backup interval: {} hours",
+            settings.interval_hours
+        )));
+    }
+    let directory = settings
+        .directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|directory| !directory.is_empty());
+    if let Some(directory) = directory.filter(|d| !std::path::Path::new(d).is_absolute()) {
+        return Err(constraint_violation(format!(
+            "Backup directory must be an absolute path: {}",
+            directory
+        )));
+    }
+
+    conn.execute(
+        "UPDATE settings
+         SET backup_directory = ?1,
+             backup_interval_hours = ?2,
+             updated_at = datetime('now')
+         WHERE id = 1",
+        params![directory, settings.interval_hours],
+    )?;
+
+    get_backup_settings(conn)
+}
+
+/// Récupère les règles de rétention de l'historique
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn get_retention_settings(conn: &Connection) -> Result<RetentionSettings> {
+    conn.query_row(
+        "SELECT session_retention_months, completed_task_retention_months
+         FROM settings WHERE id = 1",
+        [],
+        |row| {
+            Ok(RetentionSettings {
+                session_retention_months: row.get(0)?,
+                completed_task_retention_months: row.get(1)?,
+            })
+        },
+    )
+}
+
+/// Met à jour les règles de rétention de l'historique
+///
+/// Les règles ne sont appliquées que par `purge_history`.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `settings` - Nouvelles règles (None = conservation illimitée)
+pub fn update_retention_settings(
+    conn: &Connection,
+    settings: &RetentionSettings,
+) -> Result<RetentionSettings> {
+    for months in [
+        settings.session_retention_months,
+        settings.completed_task_retention_months,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if months <= 0 {
+            return Err(constraint_violation(format!(
+                "Invalid retention period: {} months",
+                months
+            )));
+        }
+    }
+
+    conn.execute(
+        "UPDATE settings
+         SET session_retention_months = ?1,
+             completed_task_retention_months = ?2,
+             updated_at = datetime('now')
+         WHERE id = 1",
+        params![
+            settings.session_retention_months,
+            settings.completed_task_retention_months
+        ],
+    )?;
+
+    get_retention_settings(conn)
+}
+
+/// Heure locale actuelle, reculée du début de journée configuré
+///
+/// `.date()` donne ainsi le jour "logique" en cours : avec un début de
+/// journée à 04:00, il est encore la veille à 02:00.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn current_local_time(conn: &Connection) -> Result<NaiveDateTime> {
+    let day_start_hour: i64 = conn.query_row(
+        "SELECT day_start_hour FROM settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(chrono::Local::now().naive_local() - Duration::hours(day_start_hour))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,10 +268,17 @@ mod tests {
         settings.work_duration = 30;
         settings.language = "fr".to_string();
         settings.theme = "dark".to_string();
+        settings.day_start_hour = 4;
+        settings.rest_weekdays = vec![6, 7];
 
         let updated = update_settings(&conn, &settings).unwrap();
         assert_eq!(updated.work_duration, 30);
         assert_eq!(updated.language, "fr");
         assert_eq!(updated.theme, "dark");
+        assert_eq!(updated.day_start_hour, 4);
+        assert_eq!(updated.rest_weekdays, vec![6, 7]);
+
+        settings.rest_weekdays = vec![8];
+        assert!(update_settings(&conn, &settings).is_err());
     }
 }
@@ -0,0 +1,792 @@
+// Requêtes SQL d'analyse et de statistiques
+use crate::db::models::{
+    ActivityHeatmap, BreakCompliance, DistributionBucket, HeatmapDay, ProductivityDistribution,
+    ProjectStats, WeeklyVelocity,
+};
+use crate::db::queries::{
+    completed_work, constraint_violation, local_day, parse_input_date, utc_offset_modifier,
+};
+use chrono::Duration;
+use rusqlite::{Connection, Result, params};
+use std::collections::HashMap;
+
+/// Sous-arbre d'un projet (le projet ?1 et tous ses descendants)
+const PROJECT_SUBTREE_CTE: &str = "WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )";
+
+/// Agrégats des sessions de travail d'un projet
+struct WorkSessionTotals {
+    /// Sessions complétées et non interrompues
+    completed: i32,
+    focus_minutes: i32,
+    /// Tâches distinctes ayant au moins une session complétée
+    worked_tasks: i32,
+    /// Sessions complétées de ces tâches (hors tâches purgées)
+    worked_tasks_completed: i32,
+    /// Sessions terminées (complétées ou interrompues)
+    finished: i32,
+    interrupted: i32,
+    first_started_at: Option<String>,
+    last_ended_at: Option<String>,
+}
+
+/// Calcule les statistiques d'un projet et de ses sous-projets
+///
+/// Les bornes de dates (jours locaux) sont optionnelles et incluses. Les tâches complétées
+/// sont filtrées sur `completed_at`, les sessions sur `started_at` ; les tâches
+/// ouvertes sont celles qui le sont actuellement. Un projet inconnu est une erreur.
+///
+/// Les agrégats laissés par `purge_history` sont inclus ; pour les jours
+/// purgés, les dates de première et dernière activité sont des jours locaux.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `project_id` - ID du projet
+/// * `start_date` - Date de début (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin (format ISO: YYYY-MM-DD)
+pub fn get_project_stats(
+    conn: &Connection,
+    project_id: i64,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<ProjectStats> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM projects WHERE id = ?1)",
+        [project_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(constraint_violation(format!("Unknown project {}", project_id)));
+    }
+
+    // Les tâches en cours sur la période : créées avant sa fin (jour UTC, faute
+    // de décalage enregistré à la création) et non complétées avant son début
+    // (les tâches purgées ont été complétées sur la période)
+    let task_day = local_day("completed_at", "completed_utc_offset_minutes");
+    let (completed_tasks_count, open_tasks_count, active_tasks_count): (i32, i32, i32) =
+        conn.query_row(
+            &format!(
+                "{PROJECT_SUBTREE_CTE},
+                purged(count) AS (
+                    SELECT COALESCE(SUM(completed_count), 0)
+                    FROM task_aggregates
+                    WHERE project_id IN (SELECT id FROM subtree)
+                      AND (?2 IS NULL OR day >= ?2)
+                      AND (?3 IS NULL OR day <= ?3)
+                )
+                SELECT
+                    COALESCE(SUM(is_completed = 1
+                        AND (?2 IS NULL OR {task_day} >= ?2)
+                        AND (?3 IS NULL OR {task_day} <= ?3)), 0) + (SELECT count FROM purged),
+                    COALESCE(SUM(is_completed = 0), 0),
+                    COALESCE(SUM((?3 IS NULL OR DATE(created_at) <= ?3)
+                        AND (COALESCE(is_completed, 0) = 0 OR ?2 IS NULL OR {task_day} >= ?2)), 0)
+                        + (SELECT count FROM purged)
+                FROM tasks
+                WHERE project_id IN (SELECT id FROM subtree)"
+            ),
+            params![project_id, start_date, end_date],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+    let session_day = local_day("s.started_at", "s.utc_offset_minutes");
+    let sessions = conn.query_row(
+        &format!(
+            "{PROJECT_SUBTREE_CTE},
+            work AS (
+                SELECT s.task_id, t.project_id, {session_day} AS day,
+                       s.completed_at IS NOT NULL AND s.interrupted = 0 AS completed,
+                       CASE WHEN s.completed_at IS NOT NULL AND s.interrupted = 0
+                            THEN s.duration_minutes ELSE 0 END AS focus_minutes,
+                       s.completed_at IS NOT NULL AS finished,
+                       s.interrupted = 1 AS interrupted,
+                       s.started_at, COALESCE(s.completed_at, s.started_at) AS ended_at
+                FROM pomodoro_sessions s
+                JOIN tasks t ON t.id = s.task_id
+                WHERE s.session_type = 'work'
+                UNION ALL
+                SELECT a.task_id,
+                       CASE WHEN a.task_id IS NULL THEN a.project_id
+                            ELSE (SELECT t.project_id FROM tasks t WHERE t.id = a.task_id)
+                       END,
+                       a.day, a.completed_count, a.focus_minutes,
+                       a.completed_count + a.interrupted_count, a.interrupted_count,
+                       a.day, a.day
+                FROM session_aggregates a
+            )
+            SELECT
+                COALESCE(SUM(completed), 0),
+                COALESCE(SUM(focus_minutes), 0),
+                COUNT(DISTINCT CASE WHEN completed > 0 THEN task_id END),
+                COALESCE(SUM(CASE WHEN task_id IS NOT NULL THEN completed ELSE 0 END), 0),
+                COALESCE(SUM(finished), 0),
+                COALESCE(SUM(interrupted), 0),
+                MIN(started_at),
+                MAX(ended_at)
+            FROM work
+            WHERE project_id IN (SELECT id FROM subtree)
+              AND (?2 IS NULL OR day >= ?2)
+              AND (?3 IS NULL OR day <= ?3)"
+        ),
+        params![project_id, start_date, end_date],
+        |row| {
+            Ok(WorkSessionTotals {
+                completed: row.get(0)?,
+                focus_minutes: row.get(1)?,
+                worked_tasks: row.get(2)?,
+                worked_tasks_completed: row.get(3)?,
+                finished: row.get(4)?,
+                interrupted: row.get(5)?,
+                first_started_at: row.get(6)?,
+                last_ended_at: row.get(7)?,
+            })
+        },
+    )?;
+
+    Ok(ProjectStats {
+        project_id,
+        start_date: start_date.map(str::to_string),
+        end_date: end_date.map(str::to_string),
+        total_focus_minutes: sessions.focus_minutes,
+        completed_pomodoros_count: sessions.completed,
+        completed_tasks_count,
+        open_tasks_count,
+        completion_rate: ratio(completed_tasks_count, active_tasks_count),
+        average_pomodoros_per_task: ratio(sessions.worked_tasks_completed, sessions.worked_tasks),
+        interruption_rate: ratio(sessions.interrupted, sessions.finished),
+        first_activity_at: sessions.first_started_at,
+        last_activity_at: sessions.last_ended_at,
+        weekly_velocity: get_project_weekly_velocity(conn, project_id, start_date, end_date)?,
+    })
+}
+
+/// Calcule la vélocité hebdomadaire d'un projet et de ses sous-projets
+///
+/// Seules les semaines ayant une activité sont retournées, par ordre
+/// chronologique. Les agrégats de l'historique purgé sont inclus.
+fn get_project_weekly_velocity(
+    conn: &Connection,
+    project_id: i64,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<Vec<WeeklyVelocity>> {
+    // DATE(x, 'weekday 0', '-6 days') donne le lundi de la semaine de x
+    let task_day = local_day("completed_at", "completed_utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "{PROJECT_SUBTREE_CTE},
+        work AS (
+            SELECT DATE(day, 'weekday 0', '-6 days') AS week_start,
+                   SUM(pomodoros) AS pomodoros,
+                   SUM(focus_minutes) AS minutes
+            FROM {}
+            WHERE project_id IN (SELECT id FROM subtree)
+              AND (?2 IS NULL OR day >= ?2)
+              AND (?3 IS NULL OR day <= ?3)
+            GROUP BY week_start
+        ),
+        done AS (
+            SELECT DATE(day, 'weekday 0', '-6 days') AS week_start,
+                   SUM(completed) AS tasks
+            FROM (SELECT {task_day} AS day, project_id, 1 AS completed
+                  FROM tasks
+                  WHERE is_completed = 1
+                  UNION ALL
+                  SELECT day, project_id, completed_count FROM task_aggregates)
+            WHERE project_id IN (SELECT id FROM subtree)
+              AND (?2 IS NULL OR day >= ?2)
+              AND (?3 IS NULL OR day <= ?3)
+            GROUP BY week_start
+        ),
+        weeks AS (
+            SELECT week_start FROM work
+            UNION
+            SELECT week_start FROM done
+        )
+        SELECT w.week_start,
+               COALESCE(work.pomodoros, 0),
+               COALESCE(work.minutes, 0),
+               COALESCE(done.tasks, 0)
+        FROM weeks w
+        LEFT JOIN work ON work.week_start = w.week_start
+        LEFT JOIN done ON done.week_start = w.week_start
+        WHERE w.week_start IS NOT NULL
+        ORDER BY w.week_start",
+        completed_work()
+    ))?;
+
+    let weeks = stmt.query_map(params![project_id, start_date, end_date], |row| {
+        Ok(WeeklyVelocity {
+            week_start: row.get(0)?,
+            completed_pomodoros_count: row.get(1)?,
+            total_focus_minutes: row.get(2)?,
+            completed_tasks_count: row.get(3)?,
+        })
+    })?;
+
+    weeks.collect()
+}
+
+/// Calcule l'activité quotidienne d'une plage de dates pour une heatmap
+///
+/// Une seule requête agrège les sessions de travail complétées et non
+/// interrompues par jour local, agrégats de l'historique purgé compris. Les niveaux d'intensité découpent les jours
+/// actifs en quartiles de minutes de focus.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+/// * `project_id` - Filtre optionnel sur un projet (sous-projets inclus)
+/// * `task_id` - Filtre optionnel sur une tâche
+pub fn get_activity_heatmap(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    project_id: Option<i64>,
+    task_id: Option<i64>,
+) -> Result<ActivityHeatmap> {
+    let start = parse_input_date(start_date)?;
+    let end = parse_input_date(end_date)?;
+    if start > end {
+        return Err(constraint_violation(format!(
+            "Invalid date range: {} is after {}",
+            start_date, end_date
+        )));
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT ?3
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )
+        SELECT w.day, SUM(w.pomodoros), SUM(w.focus_minutes)
+        FROM {} w
+        LEFT JOIN tasks t ON t.id = w.task_id
+        WHERE w.day BETWEEN ?1 AND ?2
+          AND (?3 IS NULL OR t.project_id IN (SELECT id FROM subtree))
+          AND (?4 IS NULL OR w.task_id = ?4)
+        GROUP BY w.day",
+        completed_work()
+    ))?;
+    let activity: HashMap<String, (i32, i32)> = stmt
+        .query_map(params![start_date, end_date, project_id, task_id], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+        })?
+        .collect::<Result<_>>()?;
+
+    let mut active_minutes: Vec<i32> = activity.values().map(|(_, minutes)| *minutes).collect();
+    active_minutes.sort_unstable();
+    let thresholds: Vec<i32> = if active_minutes.is_empty() {
+        Vec::new()
+    } else {
+        [0.25, 0.5, 0.75]
+            .iter()
+            .map(|q| active_minutes[((active_minutes.len() - 1) as f64 * q) as usize])
+            .collect()
+    };
+
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let key = date.format("%Y-%m-%d").to_string();
+        let (completed_pomodoros_count, total_focus_minutes) =
+            activity.get(&key).copied().unwrap_or((0, 0));
+        let intensity = if completed_pomodoros_count == 0 {
+            0
+        } else {
+            1 + thresholds.iter().filter(|t| total_focus_minutes > **t).count() as u8
+        };
+
+        days.push(HeatmapDay {
+            date: key,
+            total_focus_minutes,
+            completed_pomodoros_count,
+            intensity,
+        });
+        date += Duration::days(1);
+    }
+
+    Ok(ActivityHeatmap {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        total_focus_minutes: days.iter().map(|d| d.total_focus_minutes).sum(),
+        completed_pomodoros_count: days.iter().map(|d| d.completed_pomodoros_count).sum(),
+        active_days_count: activity.len() as i32,
+        days,
+        thresholds,
+    })
+}
+
+/// Calcule la répartition de la productivité par heure et par jour de la semaine
+///
+/// L'heure est l'heure locale réelle de démarrage de la session, le jour de la
+/// semaine celui du jour local (début de journée pris en compte). Le taux
+/// d'interruption porte sur les sessions de travail terminées, la note moyenne
+/// sur les sessions notées uniquement.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+/// * `project_id` - Filtre optionnel sur un projet (sous-projets inclus)
+pub fn get_productivity_distribution(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    project_id: Option<i64>,
+) -> Result<ProductivityDistribution> {
+    let start = parse_input_date(start_date)?;
+    let end = parse_input_date(end_date)?;
+    if start > end {
+        return Err(constraint_violation(format!(
+            "Invalid date range: {} is after {}",
+            start_date, end_date
+        )));
+    }
+
+    let session_day = local_day("s.started_at", "s.utc_offset_minutes");
+    let hour_modifier = utc_offset_modifier("s.utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT ?3
+            UNION
+            SELECT p.id FROM projects p JOIN subtree st ON p.parent_project_id = st.id
+        )
+        SELECT
+            CAST(strftime('%H', s.started_at, {hour_modifier}) AS INTEGER) AS hour,
+            CAST(strftime('%w', {session_day}) AS INTEGER) AS weekday,
+            COALESCE(SUM(s.interrupted = 0), 0),
+            COALESCE(SUM(CASE WHEN s.interrupted = 0 THEN s.duration_minutes ELSE 0 END), 0),
+            COALESCE(SUM(s.interrupted = 1), 0),
+            COALESCE(SUM(s.focus_rating), 0),
+            COUNT(s.focus_rating)
+        FROM pomodoro_sessions s
+        LEFT JOIN tasks t ON t.id = s.task_id
+        WHERE {session_day} BETWEEN ?1 AND ?2
+          AND s.completed_at IS NOT NULL
+          AND s.session_type = 'work'
+          AND (?3 IS NULL OR t.project_id IN (SELECT id FROM subtree))
+        GROUP BY hour, weekday"
+    ))?;
+
+    let mut by_hour: Vec<BucketTotals> = (0..24).map(BucketTotals::new).collect();
+    let mut by_weekday: Vec<BucketTotals> = (1..=7).map(BucketTotals::new).collect();
+    let rows = stmt.query_map(params![start_date, end_date, project_id], |row| {
+        Ok((
+            row.get::<_, u32>(0)?,
+            row.get::<_, u32>(1)?,
+            BucketTotals {
+                key: 0,
+                completed: row.get(2)?,
+                focus_minutes: row.get(3)?,
+                interrupted: row.get(4)?,
+                rating_sum: row.get(5)?,
+                rated: row.get(6)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (hour, weekday, totals) = row?;
+        by_hour[hour as usize].add(&totals);
+        // strftime('%w') compte dimanche = 0, on passe en numérotation ISO
+        let iso_weekday = if weekday == 0 { 7 } else { weekday };
+        by_weekday[iso_weekday as usize - 1].add(&totals);
+    }
+
+    Ok(ProductivityDistribution {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        by_hour: by_hour.into_iter().map(BucketTotals::into_bucket).collect(),
+        by_weekday: by_weekday.into_iter().map(BucketTotals::into_bucket).collect(),
+    })
+}
+
+/// Compteurs bruts d'une tranche de répartition, avant calcul des moyennes
+struct BucketTotals {
+    key: u32,
+    completed: i32,
+    focus_minutes: i32,
+    interrupted: i32,
+    rating_sum: i32,
+    rated: i32,
+}
+
+impl BucketTotals {
+    fn new(key: u32) -> Self {
+        Self {
+            key,
+            completed: 0,
+            focus_minutes: 0,
+            interrupted: 0,
+            rating_sum: 0,
+            rated: 0,
+        }
+    }
+
+    fn add(&mut self, other: &BucketTotals) {
+        self.completed += other.completed;
+        self.focus_minutes += other.focus_minutes;
+        self.interrupted += other.interrupted;
+        self.rating_sum += other.rating_sum;
+        self.rated += other.rated;
+    }
+
+    fn into_bucket(self) -> DistributionBucket {
+        DistributionBucket {
+            key: self.key,
+            completed_pomodoros_count: self.completed,
+            total_focus_minutes: self.focus_minutes,
+            interrupted_count: self.interrupted,
+            interruption_rate: ratio(self.interrupted, self.completed + self.interrupted),
+            average_focus_rating: (self.rated > 0).then(|| ratio(self.rating_sum, self.rated)),
+        }
+    }
+}
+
+/// Marge tolérée avant de considérer qu'une pause a dépassé sa durée prévue
+const BREAK_OVERRUN_TOLERANCE_MINUTES: f64 = 1.0;
+
+/// Session telle qu'utilisée par l'analyse des pauses
+struct BreakAnalysisSession {
+    day: String,
+    session_type: String,
+    duration_minutes: i32,
+    completed: bool,
+    interrupted: bool,
+    /// Minutes écoulées entre le démarrage et la fin (None si la session est en cours)
+    elapsed_minutes: Option<f64>,
+    /// Minutes entre le démarrage de cette session et celui de la suivante du même jour
+    minutes_to_next: Option<f64>,
+}
+
+/// Analyse le respect des pauses sur une plage de jours locaux
+///
+/// Chaque session de travail complétée est comparée à la session qui la suit
+/// le même jour : une pause compte comme prise, une session de travail comme
+/// pause sautée. La dernière session de la journée n'est pas prise en compte.
+/// Une pause dépasse sa durée prévue si le travail reprend plus tard que prévu.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+pub fn get_break_compliance(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<BreakCompliance> {
+    let start = parse_input_date(start_date)?;
+    let end = parse_input_date(end_date)?;
+    if start > end {
+        return Err(constraint_violation(format!(
+            "Invalid date range: {} is after {}",
+            start_date, end_date
+        )));
+    }
+
+    let pomodoros_until_long_break: i32 = conn.query_row(
+        "SELECT pomodoros_until_long_break FROM settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?;
+
+    let session_day = local_day("started_at", "utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {session_day} AS day, session_type, duration_minutes, completed_at IS NOT NULL,
+                interrupted, (julianday(completed_at) - julianday(started_at)) * 1440,
+                (julianday(LEAD(started_at) OVER (PARTITION BY {session_day} ORDER BY started_at, id))
+                    - julianday(started_at)) * 1440
+         FROM pomodoro_sessions
+         WHERE {session_day} BETWEEN ?1 AND ?2
+         ORDER BY started_at, id"
+    ))?;
+    let sessions: Vec<BreakAnalysisSession> = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok(BreakAnalysisSession {
+                day: row.get(0)?,
+                session_type: row.get(1)?,
+                duration_minutes: row.get(2)?,
+                completed: row.get(3)?,
+                interrupted: row.get(4)?,
+                elapsed_minutes: row.get(5)?,
+                minutes_to_next: row.get(6)?,
+            })
+        })?
+        .collect::<Result<_>>()?;
+
+    let mut work_sessions_count = 0;
+    let mut breaks_taken_count = 0;
+    let mut breaks_skipped_count = 0;
+    let mut overruns = Vec::new();
+    let mut total_break_minutes = 0.0;
+    let mut total_focus_minutes = 0;
+    let mut long_breaks_taken_count = 0;
+    let mut completed_work_by_day: HashMap<&str, i32> = HashMap::new();
+
+    for (index, session) in sessions.iter().enumerate() {
+        let next = sessions
+            .get(index + 1)
+            .filter(|next| next.day == session.day);
+
+        if session.session_type == "work" {
+            if !session.completed || session.interrupted {
+                continue;
+            }
+            total_focus_minutes += session.duration_minutes;
+            *completed_work_by_day.entry(session.day.as_str()).or_insert(0) += 1;
+
+            if let Some(next) = next {
+                work_sessions_count += 1;
+                if next.session_type == "work" {
+                    breaks_skipped_count += 1;
+                } else {
+                    breaks_taken_count += 1;
+                }
+            }
+            continue;
+        }
+
+        if session.session_type == "long_break" {
+            long_breaks_taken_count += 1;
+        }
+        total_break_minutes += session.elapsed_minutes.unwrap_or(0.0);
+
+        // Durée réelle de la pause : jusqu'à la reprise du travail le même jour
+        if let (Some(next), Some(minutes_to_next)) = (next, session.minutes_to_next) {
+            let overrun = minutes_to_next - f64::from(session.duration_minutes);
+            if next.session_type == "work" && overrun > BREAK_OVERRUN_TOLERANCE_MINUTES {
+                overruns.push(overrun);
+            }
+        }
+    }
+
+    let long_breaks_expected_count = completed_work_by_day
+        .values()
+        .map(|count| count / pomodoros_until_long_break.max(1))
+        .sum();
+
+    Ok(BreakCompliance {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        work_sessions_count,
+        breaks_taken_count,
+        breaks_skipped_count,
+        break_compliance_rate: ratio(breaks_taken_count, work_sessions_count),
+        break_overrun_count: overruns.len() as i32,
+        average_overrun_minutes: if overruns.is_empty() {
+            0.0
+        } else {
+            overruns.iter().sum::<f64>() / overruns.len() as f64
+        },
+        total_break_minutes,
+        total_focus_minutes,
+        break_to_work_ratio: if total_focus_minutes == 0 {
+            0.0
+        } else {
+            total_break_minutes / f64::from(total_focus_minutes)
+        },
+        long_breaks_taken_count,
+        long_breaks_expected_count,
+    })
+}
+
+/// Divise deux compteurs en retournant 0 si le dénominateur est nul
+fn ratio(numerator: i32, denominator: i32) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        f64::from(numerator) / f64::from(denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_session(conn: &Connection, task_id: i64, started_at: &str, interrupted: bool) {
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type, interrupted)
+             VALUES (?1, ?2, ?2, 25, 'work', ?3)",
+            params![task_id, started_at, interrupted],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute(
+            "INSERT INTO projects (name, parent_project_id) VALUES ('Phase 1', 1)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id, is_completed, completed_at)
+             VALUES ('Done', 1, 1, '2025-11-11 17:00:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Open', 2)", []).unwrap();
+
+        add_session(&conn, 1, "2025-11-10 09:00:00", false);
+        add_session(&conn, 1, "2025-11-11 09:00:00", false);
+        add_session(&conn, 2, "2025-11-17 09:00:00", false);
+        add_session(&conn, 2, "2025-11-18 09:00:00", true);
+
+        let stats = get_project_stats(&conn, 1, None, None).unwrap();
+        assert_eq!(stats.total_focus_minutes, 75);
+        assert_eq!(stats.completed_tasks_count, 1);
+        assert_eq!(stats.open_tasks_count, 1);
+        assert_eq!(stats.completion_rate, 0.5);
+        assert_eq!(stats.average_pomodoros_per_task, 1.5);
+        assert_eq!(stats.interruption_rate, 0.25);
+        assert_eq!(stats.first_activity_at.as_deref(), Some("2025-11-10 09:00:00"));
+        assert_eq!(stats.last_activity_at.as_deref(), Some("2025-11-18 09:00:00"));
+        assert_eq!(
+            stats.weekly_velocity,
+            vec![
+                WeeklyVelocity {
+                    week_start: "2025-11-10".to_string(),
+                    completed_pomodoros_count: 2,
+                    total_focus_minutes: 50,
+                    completed_tasks_count: 1,
+                },
+                WeeklyVelocity {
+                    week_start: "2025-11-17".to_string(),
+                    completed_pomodoros_count: 1,
+                    total_focus_minutes: 25,
+                    completed_tasks_count: 0,
+                },
+            ]
+        );
+
+        let ranged = get_project_stats(&conn, 1, Some("2025-11-17"), Some("2025-11-23")).unwrap();
+        assert_eq!(ranged.completed_pomodoros_count, 1);
+        assert_eq!(ranged.completed_tasks_count, 0);
+        assert_eq!(ranged.weekly_velocity.len(), 1);
+
+        // Seules les tâches en cours sur la période comptent dans le taux
+        conn.execute("UPDATE tasks SET created_at = '2025-11-01 08:00:00'", []).unwrap();
+        let ranged = get_project_stats(&conn, 1, Some("2025-11-17"), Some("2025-11-23")).unwrap();
+        assert_eq!(ranged.completion_rate, 0.0);
+        let ranged = get_project_stats(&conn, 1, Some("2025-11-10"), Some("2025-11-16")).unwrap();
+        assert_eq!(ranged.completion_rate, 0.5);
+        let ranged = get_project_stats(&conn, 1, Some("2025-10-01"), Some("2025-10-31")).unwrap();
+        assert_eq!(ranged.completion_rate, 0.0);
+
+        assert!(get_project_stats(&conn, 99, None, None).is_err());
+    }
+
+    #[test]
+    fn test_activity_heatmap() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('A', 1)", []).unwrap();
+        conn.execute("INSERT INTO tasks (title) VALUES ('B')", []).unwrap();
+
+        add_session(&conn, 1, "2025-11-01 09:00:00", false);
+        for _ in 0..4 {
+            add_session(&conn, 1, "2025-11-02 09:00:00", false);
+        }
+        add_session(&conn, 2, "2025-11-03 09:00:00", false);
+        add_session(&conn, 2, "2025-11-03 10:00:00", false);
+        add_session(&conn, 2, "2025-11-04 09:00:00", true);
+
+        let heatmap = get_activity_heatmap(&conn, "2025-11-01", "2025-11-05", None, None).unwrap();
+        assert_eq!(heatmap.days.len(), 5);
+        assert_eq!(heatmap.active_days_count, 3);
+        assert_eq!(heatmap.total_focus_minutes, 175);
+        let intensities: Vec<u8> = heatmap.days.iter().map(|d| d.intensity).collect();
+        assert_eq!(intensities, vec![1, 4, 2, 0, 0]);
+
+        let filtered = get_activity_heatmap(&conn, "2025-11-01", "2025-11-05", Some(1), None).unwrap();
+        assert_eq!(filtered.completed_pomodoros_count, 5);
+        let by_task = get_activity_heatmap(&conn, "2025-11-01", "2025-11-05", None, Some(2)).unwrap();
+        assert_eq!(by_task.completed_pomodoros_count, 2);
+    }
+
+    #[test]
+    fn test_productivity_distribution() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('A', 1)", []).unwrap();
+        conn.execute("INSERT INTO tasks (title) VALUES ('B')", []).unwrap();
+
+        // Lundi 10 novembre 2025, 8h UTC = 9h locale (UTC+1)
+        add_session(&conn, 1, "2025-11-10 08:00:00", false);
+        add_session(&conn, 1, "2025-11-10 08:30:00", true);
+        add_session(&conn, 2, "2025-11-16 14:00:00", false);
+        conn.execute(
+            "UPDATE pomodoro_sessions SET utc_offset_minutes = 60, focus_rating = id + 2
+             WHERE task_id = 1",
+            [],
+        )
+        .unwrap();
+
+        let distribution =
+            get_productivity_distribution(&conn, "2025-11-10", "2025-11-16", None).unwrap();
+        assert_eq!(distribution.by_hour.len(), 24);
+        assert_eq!(distribution.by_weekday.len(), 7);
+
+        let nine = &distribution.by_hour[9];
+        assert_eq!(nine.completed_pomodoros_count, 1);
+        assert_eq!(nine.interrupted_count, 1);
+        assert_eq!(nine.interruption_rate, 0.5);
+        assert_eq!(nine.average_focus_rating, Some(3.5));
+        assert_eq!(distribution.by_hour[14].average_focus_rating, None);
+
+        assert_eq!(distribution.by_weekday[0].key, 1);
+        assert_eq!(distribution.by_weekday[0].total_focus_minutes, 25);
+        assert_eq!(distribution.by_weekday[6].completed_pomodoros_count, 1);
+
+        let filtered =
+            get_productivity_distribution(&conn, "2025-11-10", "2025-11-16", Some(1)).unwrap();
+        assert_eq!(filtered.by_weekday[6].completed_pomodoros_count, 0);
+    }
+
+    #[test]
+    fn test_break_compliance() {
+        let conn = setup_test_db();
+        conn.execute("UPDATE settings SET pomodoros_until_long_break = 2 WHERE id = 1", [])
+            .unwrap();
+        for (started_at, completed_at, duration, session_type) in [
+            ("2025-11-10 09:00:00", "2025-11-10 09:25:00", 25, "work"),
+            ("2025-11-10 09:25:00", "2025-11-10 09:30:00", 5, "short_break"),
+            // Reprise 10 minutes après le début d'une pause de 5 minutes
+            ("2025-11-10 09:35:00", "2025-11-10 10:00:00", 25, "work"),
+            ("2025-11-10 10:00:00", "2025-11-10 10:25:00", 25, "work"),
+            ("2025-11-10 10:25:00", "2025-11-10 10:40:00", 15, "long_break"),
+        ] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![started_at, completed_at, duration, session_type],
+            )
+            .unwrap();
+        }
+
+        let compliance = get_break_compliance(&conn, "2025-11-10", "2025-11-10").unwrap();
+        assert_eq!(compliance.work_sessions_count, 3);
+        assert_eq!(compliance.breaks_taken_count, 2);
+        assert_eq!(compliance.breaks_skipped_count, 1);
+        assert_eq!(compliance.break_overrun_count, 1);
+        assert!((compliance.average_overrun_minutes - 5.0).abs() < 0.01);
+        assert_eq!(compliance.total_focus_minutes, 75);
+        assert!((compliance.break_to_work_ratio - 20.0 / 75.0).abs() < 0.001);
+        assert_eq!(compliance.long_breaks_taken_count, 1);
+        assert_eq!(compliance.long_breaks_expected_count, 1);
+    }
+}
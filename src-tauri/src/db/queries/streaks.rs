@@ -0,0 +1,242 @@
+// Requêtes SQL pour l'objectif quotidien, les jours de repos et les séries
+use crate::db::models::{GoalMetric, RestDay, StreakDay, StreakSummary};
+use crate::db::queries::{completed_work, get_settings, parse_input_date};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{Connection, Result, params};
+use std::collections::{HashMap, HashSet};
+
+/// Récupère les jours de repos ponctuels
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn get_rest_days(conn: &Connection) -> Result<Vec<RestDay>> {
+    let mut stmt = conn.prepare("SELECT date, note FROM rest_days ORDER BY date")?;
+
+    let days = stmt.query_map([], |row| {
+        Ok(RestDay {
+            date: row.get(0)?,
+            note: row.get(1)?,
+        })
+    })?;
+
+    days.collect()
+}
+
+/// Ajoute (ou remplace) un jour de repos ponctuel
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `date` - Jour local (format ISO: YYYY-MM-DD)
+/// * `note` - Commentaire optionnel (ex: "Congés")
+pub fn add_rest_day(conn: &Connection, date: &str, note: Option<&str>) -> Result<RestDay> {
+    parse_input_date(date)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO rest_days (date, note) VALUES (?1, ?2)",
+        params![date, note],
+    )?;
+
+    Ok(RestDay {
+        date: date.to_string(),
+        note: note.map(str::to_string),
+    })
+}
+
+/// Supprime un jour de repos ponctuel
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `date` - Jour local (format ISO: YYYY-MM-DD)
+pub fn remove_rest_day(conn: &Connection, date: &str) -> Result<()> {
+    conn.execute("DELETE FROM rest_days WHERE date = ?1", [date])?;
+    Ok(())
+}
+
+/// Calcule la série en cours, la plus longue série et l'historique des jours
+///
+/// Un jour atteint l'objectif selon les mêmes règles que
+/// `count_completed_sessions_by_date` (sessions de travail complétées et non
+/// interrompues, par jour local). Un jour de repos (hebdomadaire ou ponctuel)
+/// qui n'atteint pas l'objectif ne casse pas la série, pas plus que le jour en
+/// cours tant qu'il n'est pas terminé.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Début de l'historique (première activité par défaut)
+/// * `end_date` - Fin de l'historique (aujourd'hui par défaut, bornée à aujourd'hui)
+/// * `today` - Jour local en cours
+pub fn get_streaks(
+    conn: &Connection,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    today: NaiveDate,
+) -> Result<StreakSummary> {
+    let settings = get_settings(conn)?;
+    let history_start = start_date.map(parse_input_date).transpose()?;
+    let history_end = end_date.map(parse_input_date).transpose()?.unwrap_or(today).min(today);
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT day, SUM(pomodoros), SUM(focus_minutes)
+         FROM {}
+         GROUP BY day",
+        completed_work()
+    ))?;
+    let mut values: HashMap<NaiveDate, i32> = HashMap::new();
+    for row in stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?, row.get::<_, i32>(2)?))
+    })? {
+        let (day, pomodoros, minutes) = row?;
+        let day = NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+            .map_err(|_| rusqlite::Error::InvalidQuery)?;
+        let value = match settings.daily_goal_metric {
+            GoalMetric::Pomodoros => pomodoros,
+            GoalMetric::Minutes => minutes,
+        };
+        values.insert(day, value);
+    }
+
+    let rest_dates: HashSet<NaiveDate> = get_rest_days(conn)?
+        .iter()
+        .filter_map(|day| NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").ok())
+        .collect();
+    let is_rest_day = |day: NaiveDate| {
+        rest_dates.contains(&day)
+            || settings.rest_weekdays.contains(&day.weekday().number_from_monday())
+    };
+
+    // Parcourt tout l'historique pour connaître la série en cours au début de la période
+    let first_activity = values.keys().min().copied();
+    let first_day = match (first_activity, history_start) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    let mut summary = StreakSummary {
+        daily_goal_metric: settings.daily_goal_metric.clone(),
+        daily_goal_target: settings.daily_goal_target,
+        current_streak: 0,
+        current_streak_start: None,
+        longest_streak: 0,
+        longest_streak_start: None,
+        longest_streak_end: None,
+        history: Vec::new(),
+    };
+    let Some(first_day) = first_day else {
+        return Ok(summary);
+    };
+
+    let mut running = 0;
+    let mut running_start: Option<NaiveDate> = None;
+    let mut day = first_day;
+    while day <= today {
+        let value = values.get(&day).copied().unwrap_or(0);
+        let goal_met = value >= settings.daily_goal_target;
+        let rest = is_rest_day(day);
+
+        if goal_met {
+            if running == 0 {
+                running_start = Some(day);
+            }
+            running += 1;
+        } else if !rest && day != today {
+            running = 0;
+            running_start = None;
+        }
+
+        if running > summary.longest_streak {
+            summary.longest_streak = running;
+            summary.longest_streak_start = running_start.map(|d| d.format("%Y-%m-%d").to_string());
+            summary.longest_streak_end = Some(day.format("%Y-%m-%d").to_string());
+        }
+
+        if day >= history_start.unwrap_or(first_day) && day <= history_end {
+            summary.history.push(StreakDay {
+                date: day.format("%Y-%m-%d").to_string(),
+                value,
+                goal_met,
+                is_rest_day: rest,
+                streak_length: running,
+            });
+        }
+
+        day += Duration::days(1);
+    }
+
+    summary.current_streak = running;
+    summary.current_streak_start = running_start.map(|d| d.format("%Y-%m-%d").to_string());
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute("UPDATE settings SET daily_goal_target = 2 WHERE id = 1", []).unwrap();
+        conn
+    }
+
+    fn add_pomodoros(conn: &Connection, date: &str, count: usize) {
+        for _ in 0..count {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type)
+                 VALUES (?1 || ' 10:00:00', ?1 || ' 10:25:00', 25, 'work')",
+                [date],
+            )
+            .unwrap();
+        }
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_streaks_with_rest_days() {
+        let conn = setup_test_db();
+        // Lundi 10 au vendredi 14 novembre 2025, puis lundi 17
+        add_pomodoros(&conn, "2025-11-10", 2);
+        add_pomodoros(&conn, "2025-11-11", 3);
+        add_pomodoros(&conn, "2025-11-12", 1);
+        add_pomodoros(&conn, "2025-11-13", 2);
+        add_pomodoros(&conn, "2025-11-14", 2);
+        add_pomodoros(&conn, "2025-11-17", 2);
+
+        // Sans jours de repos, le week-end casse la série
+        let streaks = get_streaks(&conn, None, None, day("2025-11-17")).unwrap();
+        assert_eq!(streaks.current_streak, 1);
+        assert_eq!(streaks.longest_streak, 2);
+        assert_eq!(streaks.longest_streak_start.as_deref(), Some("2025-11-10"));
+
+        // Samedi et dimanche en repos
+        conn.execute("UPDATE settings SET rest_weekdays = '6,7' WHERE id = 1", []).unwrap();
+        let streaks = get_streaks(&conn, None, None, day("2025-11-17")).unwrap();
+        assert_eq!(streaks.current_streak, 3);
+        assert_eq!(streaks.current_streak_start.as_deref(), Some("2025-11-13"));
+
+        // Un jour de repos ponctuel le mercredi 12 relie les deux séries
+        add_rest_day(&conn, "2025-11-12", Some("Off")).unwrap();
+        let streaks = get_streaks(&conn, None, None, day("2025-11-18")).unwrap();
+        assert_eq!(streaks.current_streak, 5);
+        assert_eq!(streaks.longest_streak, 5);
+    }
+
+    #[test]
+    fn test_streak_history_for_period() {
+        let conn = setup_test_db();
+        add_pomodoros(&conn, "2025-11-10", 2);
+        add_pomodoros(&conn, "2025-11-11", 2);
+
+        let streaks =
+            get_streaks(&conn, Some("2025-11-11"), Some("2025-11-12"), day("2025-11-12")).unwrap();
+        assert_eq!(streaks.history.len(), 2);
+        assert_eq!(streaks.history[0].streak_length, 2);
+        assert!(!streaks.history[1].goal_met);
+        // Le jour en cours n'est pas terminé : la série continue
+        assert_eq!(streaks.current_streak, 2);
+    }
+}
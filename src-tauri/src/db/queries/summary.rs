@@ -0,0 +1,547 @@
+// Requêtes SQL pour les résumés de productivité par période
+use crate::db::models::{
+    MetricDelta, PeriodComparison, ProjectBreakdown, RangeSummary, SummaryAverages,
+    SummaryBreakdown, SummaryBucket, SummaryGranularity, TaskBreakdown,
+};
+use crate::db::queries::{completed_work, constraint_violation, local_day, parse_input_date};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{Connection, Result, params};
+use std::collections::HashMap;
+
+/// Calcule le résumé d'une plage de dates, découpée par jour, semaine ISO, mois ou année
+///
+/// Les agrégats sont calculés par deux requêtes groupées (sessions et tâches)
+/// quelle que soit la longueur de la plage, en incluant les agrégats
+/// journaliers de l'historique purgé. Les périodes sans activité sont
+/// incluses avec des totaux à zéro. Les dates sont des jours locaux.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+/// * `granularity` - Taille des périodes
+pub fn get_range_summary(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    granularity: SummaryGranularity,
+) -> Result<RangeSummary> {
+    let start = parse_input_date(start_date)?;
+    let end = parse_input_date(end_date)?;
+    if start > end {
+        return Err(constraint_violation(format!(
+            "Invalid date range: {} is after {}",
+            start_date, end_date
+        )));
+    }
+
+    let session_bucket = bucket_expr(granularity, "day");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {session_bucket} AS bucket, SUM(pomodoros), SUM(focus_minutes)
+         FROM {}
+         WHERE day BETWEEN ?1 AND ?2
+         GROUP BY bucket",
+        completed_work()
+    ))?;
+    let sessions: HashMap<String, (i32, i32)> = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+        })?
+        .collect::<Result<_>>()?;
+
+    let task_bucket = bucket_expr(granularity, "day");
+    let task_day = local_day("completed_at", "completed_utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {task_bucket} AS bucket, SUM(completed)
+         FROM (SELECT {task_day} AS day, 1 AS completed FROM tasks
+               UNION ALL
+               SELECT day, completed_count FROM task_aggregates)
+         WHERE day BETWEEN ?1 AND ?2
+         GROUP BY bucket"
+    ))?;
+    let tasks: HashMap<String, i32> = stmt
+        .query_map(params![start_date, end_date], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_>>()?;
+
+    let mut buckets = Vec::new();
+    let mut bucket_start = period_start(start, granularity);
+    while bucket_start <= end {
+        let next_start = next_period_start(bucket_start, granularity);
+        let key = bucket_start.format("%Y-%m-%d").to_string();
+        let (completed_pomodoros_count, total_focus_minutes) =
+            sessions.get(&key).copied().unwrap_or((0, 0));
+
+        buckets.push(SummaryBucket {
+            period_start: bucket_start.max(start).format("%Y-%m-%d").to_string(),
+            period_end: (next_start - Duration::days(1))
+                .min(end)
+                .format("%Y-%m-%d")
+                .to_string(),
+            completed_tasks_count: tasks.get(&key).copied().unwrap_or(0),
+            completed_pomodoros_count,
+            total_focus_minutes,
+        });
+        bucket_start = next_start;
+    }
+
+    let totals = SummaryBucket {
+        period_start: start.format("%Y-%m-%d").to_string(),
+        period_end: end.format("%Y-%m-%d").to_string(),
+        completed_tasks_count: buckets.iter().map(|b| b.completed_tasks_count).sum(),
+        completed_pomodoros_count: buckets.iter().map(|b| b.completed_pomodoros_count).sum(),
+        total_focus_minutes: buckets.iter().map(|b| b.total_focus_minutes).sum(),
+    };
+
+    // Il y a toujours au moins une période puisque start <= end
+    let count = buckets.len() as f64;
+    let averages = SummaryAverages {
+        completed_tasks_count: f64::from(totals.completed_tasks_count) / count,
+        completed_pomodoros_count: f64::from(totals.completed_pomodoros_count) / count,
+        total_focus_minutes: f64::from(totals.total_focus_minutes) / count,
+    };
+
+    Ok(RangeSummary {
+        start_date: totals.period_start.clone(),
+        end_date: totals.period_end.clone(),
+        granularity,
+        buckets,
+        totals,
+        averages,
+    })
+}
+
+/// Compare la période contenant `date` à la période équivalente précédente
+///
+/// Si la période est en cours (elle contient `today`), seuls les jours écoulés
+/// sont comparés au même nombre de jours au début de la période précédente,
+/// borné à sa fin (un 31 mars est comparé au 28 février).
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `period` - Taille des périodes (jour, semaine ISO, mois ou année)
+/// * `date` - Jour local quelconque de la période
+/// * `today` - Jour local courant
+pub fn get_period_comparison(
+    conn: &Connection,
+    period: SummaryGranularity,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> Result<PeriodComparison> {
+    let start = period_start(date, period);
+    let end = next_period_start(start, period) - Duration::days(1);
+    let previous_start = period_start(start - Duration::days(1), period);
+    let previous_end = start - Duration::days(1);
+
+    let partial = start <= today && today < end;
+    let (current_end, previous_end) = if partial {
+        (today, (previous_start + (today - start)).min(previous_end))
+    } else {
+        (end, previous_end)
+    };
+
+    let current_start = start.format("%Y-%m-%d").to_string();
+    let current_end = current_end.format("%Y-%m-%d").to_string();
+    let previous_start = previous_start.format("%Y-%m-%d").to_string();
+    let previous_end = previous_end.format("%Y-%m-%d").to_string();
+
+    let current = get_range_summary(conn, &current_start, &current_end, period)?.totals;
+    let previous = get_range_summary(conn, &previous_start, &previous_end, period)?.totals;
+    let interrupted = count_interrupted_sessions(conn, &current_start, &current_end)?;
+    let previous_interrupted = count_interrupted_sessions(conn, &previous_start, &previous_end)?;
+
+    let interruption_rate = |interrupted: i32, completed: i32| {
+        if interrupted + completed == 0 {
+            0.0
+        } else {
+            f64::from(interrupted) / f64::from(interrupted + completed)
+        }
+    };
+
+    Ok(PeriodComparison {
+        period,
+        partial,
+        total_focus_minutes: metric_delta(
+            f64::from(current.total_focus_minutes),
+            f64::from(previous.total_focus_minutes),
+        ),
+        completed_pomodoros_count: metric_delta(
+            f64::from(current.completed_pomodoros_count),
+            f64::from(previous.completed_pomodoros_count),
+        ),
+        completed_tasks_count: metric_delta(
+            f64::from(current.completed_tasks_count),
+            f64::from(previous.completed_tasks_count),
+        ),
+        interrupted_count: metric_delta(f64::from(interrupted), f64::from(previous_interrupted)),
+        interruption_rate: metric_delta(
+            interruption_rate(interrupted, current.completed_pomodoros_count),
+            interruption_rate(previous_interrupted, previous.completed_pomodoros_count),
+        ),
+        current_start,
+        current_end,
+        previous_start,
+        previous_end,
+    })
+}
+
+/// Calcule l'évolution d'un indicateur entre deux périodes
+fn metric_delta(current: f64, previous: f64) -> MetricDelta {
+    let change = current - previous;
+    MetricDelta {
+        current,
+        previous,
+        change,
+        percent_change: (previous != 0.0).then(|| change * 100.0 / previous),
+    }
+}
+
+/// Compte les sessions de travail interrompues d'une plage de jours locaux
+///
+/// Les sessions regroupées par `purge_history` sont comptées via leurs agrégats.
+pub(crate) fn count_interrupted_sessions(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<i32> {
+    conn.query_row(
+        &format!(
+            "SELECT (SELECT COUNT(*)
+                     FROM pomodoro_sessions
+                     WHERE {} BETWEEN ?1 AND ?2
+                       AND interrupted = 1
+                       AND session_type = 'work')
+                    + (SELECT COALESCE(SUM(interrupted_count), 0)
+                       FROM session_aggregates
+                       WHERE day BETWEEN ?1 AND ?2)",
+            local_day("started_at", "utc_offset_minutes")
+        ),
+        params![start_date, end_date],
+        |row| row.get(0),
+    )
+}
+
+/// Calcule la répartition du temps de focus par projet et par tâche sur une plage
+///
+/// Seules les sessions de travail complétées et non interrompues sont comptées.
+/// Les sessions sans tâche sont regroupées à part.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+pub fn get_summary_breakdown(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<SummaryBreakdown> {
+    let mut breakdown = SummaryBreakdown::default();
+    for (_, row) in get_breakdown_rows(conn, start_date, end_date)? {
+        add_breakdown_row(&mut breakdown, row);
+    }
+    sort_breakdown(&mut breakdown);
+
+    Ok(breakdown)
+}
+
+/// Calcule la répartition par projet et par tâche de chaque jour local d'une plage
+///
+/// Une seule requête groupée est exécutée ; seuls les jours ayant une activité
+/// sont présents dans le résultat.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Date de début incluse (format ISO: YYYY-MM-DD)
+/// * `end_date` - Date de fin incluse (format ISO: YYYY-MM-DD)
+pub fn get_daily_breakdowns(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<HashMap<String, SummaryBreakdown>> {
+    let mut breakdowns: HashMap<String, SummaryBreakdown> = HashMap::new();
+    for (day, row) in get_breakdown_rows(conn, start_date, end_date)? {
+        add_breakdown_row(breakdowns.entry(day).or_default(), row);
+    }
+    breakdowns.values_mut().for_each(sort_breakdown);
+
+    Ok(breakdowns)
+}
+
+/// Agrégats d'une tâche (ou des sessions sans tâche) pour un jour local
+struct BreakdownRow {
+    task_id: Option<i64>,
+    title: Option<String>,
+    project_id: Option<i64>,
+    project_name: Option<String>,
+    completed: i32,
+    focus_minutes: i32,
+}
+
+/// Agrège les sessions de travail par jour local et par tâche
+fn get_breakdown_rows(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<(String, BreakdownRow)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT w.day, t.id, t.title, p.id, p.name,
+                SUM(w.pomodoros), SUM(w.focus_minutes)
+         FROM {} w
+         LEFT JOIN tasks t ON t.id = w.task_id
+         LEFT JOIN projects p ON p.id = w.project_id
+         WHERE w.day BETWEEN ?1 AND ?2
+         GROUP BY w.day, t.id, p.id",
+        completed_work()
+    ))?;
+    let rows = stmt.query_map(params![start_date, end_date], |row| {
+        Ok((
+            row.get(0)?,
+            BreakdownRow {
+                task_id: row.get(1)?,
+                title: row.get(2)?,
+                project_id: row.get(3)?,
+                project_name: row.get(4)?,
+                completed: row.get(5)?,
+                focus_minutes: row.get(6)?,
+            },
+        ))
+    })?;
+
+    rows.collect()
+}
+
+/// Ajoute les agrégats d'une tâche à une répartition
+///
+/// Les sessions d'une tâche purgée restent comptées dans son projet.
+fn add_breakdown_row(breakdown: &mut SummaryBreakdown, row: BreakdownRow) {
+    if row.task_id.is_none() && row.project_id.is_none() {
+        breakdown.unassigned_pomodoros_count += row.completed;
+        breakdown.unassigned_focus_minutes += row.focus_minutes;
+        return;
+    }
+
+    let index = match breakdown
+        .projects
+        .iter()
+        .position(|p| p.project_id == row.project_id)
+    {
+        Some(index) => index,
+        None => {
+            breakdown.projects.push(ProjectBreakdown {
+                project_id: row.project_id,
+                project_name: row.project_name,
+                completed_pomodoros_count: 0,
+                total_focus_minutes: 0,
+                tasks: Vec::new(),
+            });
+            breakdown.projects.len() - 1
+        }
+    };
+    let project = &mut breakdown.projects[index];
+    project.completed_pomodoros_count += row.completed;
+    project.total_focus_minutes += row.focus_minutes;
+
+    let Some(task_id) = row.task_id else {
+        return;
+    };
+    match project.tasks.iter_mut().find(|t| t.task_id == task_id) {
+        Some(task) => {
+            task.completed_pomodoros_count += row.completed;
+            task.total_focus_minutes += row.focus_minutes;
+        }
+        None => project.tasks.push(TaskBreakdown {
+            task_id,
+            title: row.title.unwrap_or_default(),
+            completed_pomodoros_count: row.completed,
+            total_focus_minutes: row.focus_minutes,
+        }),
+    }
+}
+
+/// Trie projets et tâches par temps de focus décroissant
+fn sort_breakdown(breakdown: &mut SummaryBreakdown) {
+    breakdown
+        .projects
+        .sort_by_key(|p| std::cmp::Reverse(p.total_focus_minutes));
+    for project in &mut breakdown.projects {
+        project
+            .tasks
+            .sort_by_key(|t| std::cmp::Reverse(t.total_focus_minutes));
+    }
+}
+
+/// Expression SQL donnant le premier jour de la période contenant le jour local `day`
+fn bucket_expr(granularity: SummaryGranularity, day: &str) -> String {
+    match granularity {
+        SummaryGranularity::Day => day.to_string(),
+        // 'weekday 0' avance au dimanche suivant (ou reste sur le dimanche)
+        SummaryGranularity::Week => format!("DATE({day}, 'weekday 0', '-6 days')"),
+        SummaryGranularity::Month => format!("DATE({day}, 'start of month')"),
+        SummaryGranularity::Year => format!("DATE({day}, 'start of year')"),
+    }
+}
+
+/// Premier jour de la période contenant `date`
+pub(crate) fn period_start(date: NaiveDate, granularity: SummaryGranularity) -> NaiveDate {
+    match granularity {
+        SummaryGranularity::Day => date,
+        SummaryGranularity::Week => {
+            date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+        }
+        SummaryGranularity::Month => date.with_day(1).unwrap_or(date),
+        SummaryGranularity::Year => date.with_ordinal(1).unwrap_or(date),
+    }
+}
+
+/// Premier jour de la période suivant celle commençant à `start`
+pub(crate) fn next_period_start(start: NaiveDate, granularity: SummaryGranularity) -> NaiveDate {
+    match granularity {
+        SummaryGranularity::Day => start + Duration::days(1),
+        SummaryGranularity::Week => start + Duration::days(7),
+        SummaryGranularity::Month => start
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap_or(NaiveDate::MAX),
+        SummaryGranularity::Year => start
+            .checked_add_months(chrono::Months::new(12))
+            .unwrap_or(NaiveDate::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        for started_at in ["2025-11-09 10:00:00", "2025-11-10 10:00:00", "2025-12-01 10:00:00"] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type)
+                 VALUES (?1, ?1, 25, 'work')",
+                [started_at],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO tasks (title, is_completed, completed_at)
+             VALUES ('Done', 1, '2025-11-11 18:00:00')",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_range_summary_by_week() {
+        let conn = setup_test_db();
+        let summary =
+            get_range_summary(&conn, "2025-11-05", "2025-11-16", SummaryGranularity::Week).unwrap();
+
+        assert_eq!(summary.buckets.len(), 2);
+        assert_eq!(summary.buckets[0].period_start, "2025-11-05");
+        assert_eq!(summary.buckets[0].period_end, "2025-11-09");
+        assert_eq!(summary.buckets[0].completed_pomodoros_count, 1);
+        assert_eq!(summary.buckets[1].period_start, "2025-11-10");
+        assert_eq!(summary.buckets[1].completed_tasks_count, 1);
+        assert_eq!(summary.totals.total_focus_minutes, 50);
+        assert_eq!(summary.averages.completed_pomodoros_count, 1.0);
+    }
+
+    #[test]
+    fn test_range_summary_by_day_and_month() {
+        let conn = setup_test_db();
+        let daily =
+            get_range_summary(&conn, "2025-11-09", "2025-11-11", SummaryGranularity::Day).unwrap();
+        let minutes: Vec<i32> = daily.buckets.iter().map(|b| b.total_focus_minutes).collect();
+        assert_eq!(minutes, vec![25, 25, 0]);
+
+        let monthly =
+            get_range_summary(&conn, "2025-01-01", "2025-12-31", SummaryGranularity::Month).unwrap();
+        assert_eq!(monthly.buckets.len(), 12);
+        assert_eq!(monthly.buckets[10].completed_pomodoros_count, 2);
+        assert_eq!(monthly.buckets[11].period_end, "2025-12-31");
+
+        assert!(get_range_summary(&conn, "2025-12-31", "2025-01-01", SummaryGranularity::Year).is_err());
+    }
+
+    #[test]
+    fn test_summary_breakdown() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute("INSERT INTO tasks (title, project_id) VALUES ('Spec', 1)", []).unwrap();
+        conn.execute("INSERT INTO tasks (title) VALUES ('Inbox')", []).unwrap();
+        for (task_id, started_at) in [
+            (2, "2025-11-10 11:00:00"),
+            (2, "2025-11-10 12:00:00"),
+            (3, "2025-11-11 09:00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (?1, ?2, ?2, 25, 'work')",
+                params![task_id, started_at],
+            )
+            .unwrap();
+        }
+
+        let breakdown = get_summary_breakdown(&conn, "2025-11-10", "2025-11-11").unwrap();
+        assert_eq!(breakdown.unassigned_pomodoros_count, 1);
+        assert_eq!(breakdown.unassigned_focus_minutes, 25);
+        assert_eq!(breakdown.projects.len(), 2);
+        assert_eq!(breakdown.projects[0].project_name.as_deref(), Some("Client X"));
+        assert_eq!(breakdown.projects[0].total_focus_minutes, 50);
+        assert_eq!(breakdown.projects[0].tasks[0].title, "Spec");
+        assert_eq!(breakdown.projects[1].project_id, None);
+        assert_eq!(breakdown.projects[1].completed_pomodoros_count, 1);
+
+        let daily = get_daily_breakdowns(&conn, "2025-11-10", "2025-11-11").unwrap();
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily["2025-11-11"].unassigned_pomodoros_count, 0);
+        assert_eq!(daily["2025-11-11"].projects[0].tasks[0].title, "Inbox");
+    }
+
+    #[test]
+    fn test_period_comparison() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type, interrupted)
+             VALUES ('2025-11-12 10:00:00', '2025-11-12 10:10:00', 25, 'work', 1)",
+            [],
+        )
+        .unwrap();
+
+        // Semaine en cours au mercredi 12 : lundi-mercredi contre lundi-mercredi précédents
+        let wednesday = NaiveDate::from_ymd_opt(2025, 11, 12).unwrap();
+        let weekly =
+            get_period_comparison(&conn, SummaryGranularity::Week, wednesday, wednesday).unwrap();
+        assert!(weekly.partial);
+        assert_eq!(weekly.current_end, "2025-11-12");
+        assert_eq!(weekly.previous_start, "2025-11-03");
+        assert_eq!(weekly.previous_end, "2025-11-05");
+        assert_eq!(weekly.completed_pomodoros_count.current, 1.0);
+        assert_eq!(weekly.completed_pomodoros_count.previous, 0.0);
+        assert_eq!(weekly.completed_pomodoros_count.percent_change, None);
+        assert_eq!(weekly.interruption_rate.current, 0.5);
+
+        // Mois complet : novembre contre octobre
+        let later = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let monthly =
+            get_period_comparison(&conn, SummaryGranularity::Month, wednesday, later).unwrap();
+        assert!(!monthly.partial);
+        assert_eq!(monthly.current_end, "2025-11-30");
+        assert_eq!(monthly.previous_end, "2025-10-31");
+
+        // Fin mars en cours : comparée au mois de février entier
+        let march_end = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        let clamped =
+            get_period_comparison(&conn, SummaryGranularity::Month, march_end, march_end).unwrap();
+        assert_eq!(clamped.previous_end, "2025-02-28");
+
+        let december = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
+        let against_november =
+            get_period_comparison(&conn, SummaryGranularity::Month, december, later).unwrap();
+        assert_eq!(against_november.total_focus_minutes.change, -25.0);
+        assert_eq!(against_november.total_focus_minutes.percent_change, Some(-50.0));
+    }
+}
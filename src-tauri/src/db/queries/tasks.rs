@@ -1,9 +1,12 @@
 // Requêtes SQL pour la gestion des tâches
-use crate::db::models::{CreateTaskInput, Task, UpdateTaskInput};
-use rusqlite::{Connection, Result, params};
+use crate::db::models::{CreateTaskInput, ExternalTaskInput, Task, UpdateTaskInput};
+use crate::db::queries::current_utc_offset_minutes;
+use rusqlite::{Connection, OptionalExtension, Result, params};
 
 /// Récupère toutes les tâches
 ///
+/// Les tâches appartenant à un projet archivé sont exclues
+///
 /// # Arguments
 /// * `conn` - Connexion à la base de données
 ///
@@ -11,10 +14,13 @@ use rusqlite::{Connection, Result, params};
 /// Un vecteur de toutes les tâches triées par date de création (plus récent en premier)
 pub fn get_all_tasks(conn: &Connection) -> Result<Vec<Task>> {
     let mut stmt = conn.prepare(
-        "SELECT id, title, description, project_id, estimated_pomodoros,
-                completed_pomodoros, is_completed, created_at, updated_at, completed_at
-         FROM tasks
-         ORDER BY created_at DESC",
+        "SELECT t.id, t.title, t.description, t.project_id, t.estimated_pomodoros,
+                t.completed_pomodoros, t.is_completed, t.created_at, t.updated_at, t.completed_at,
+                t.due_date, t.priority
+         FROM tasks t
+         LEFT JOIN projects p ON p.id = t.project_id
+         WHERE p.status IS NULL OR p.status != 'archived'
+         ORDER BY t.created_at DESC",
     )?;
 
     let tasks = stmt.query_map([], |row| {
@@ -29,6 +35,8 @@ pub fn get_all_tasks(conn: &Connection) -> Result<Vec<Task>> {
             created_at: row.get(7)?,
             updated_at: row.get(8)?,
             completed_at: row.get(9)?,
+            due_date: row.get(10)?,
+            priority: row.get(11)?,
         })
     })?;
 
@@ -43,7 +51,8 @@ pub fn get_all_tasks(conn: &Connection) -> Result<Vec<Task>> {
 pub fn get_task_by_id(conn: &Connection, task_id: i64) -> Result<Task> {
     conn.query_row(
         "SELECT id, title, description, project_id, estimated_pomodoros,
-                completed_pomodoros, is_completed, created_at, updated_at, completed_at
+                completed_pomodoros, is_completed, created_at, updated_at, completed_at,
+                due_date, priority
          FROM tasks
          WHERE id = ?1",
         [task_id],
@@ -59,6 +68,8 @@ pub fn get_task_by_id(conn: &Connection, task_id: i64) -> Result<Task> {
                 created_at: row.get(7)?,
                 updated_at: row.get(8)?,
                 completed_at: row.get(9)?,
+                due_date: row.get(10)?,
+                priority: row.get(11)?,
             })
         },
     )
@@ -138,14 +149,94 @@ pub fn toggle_task_completion(conn: &Connection, task_id: i64) -> Result<Task> {
         "UPDATE tasks
          SET is_completed = ?1,
              completed_at = CASE WHEN ?1 = 1 THEN datetime('now') ELSE NULL END,
+             completed_utc_offset_minutes = CASE WHEN ?1 = 1 THEN ?3 ELSE NULL END,
              updated_at = datetime('now')
          WHERE id = ?2",
-        params![if new_status { 1 } else { 0 }, task_id],
+        params![
+            if new_status { 1 } else { 0 },
+            task_id,
+            current_utc_offset_minutes(),
+        ],
     )?;
 
     get_task_by_id(conn, task_id)
 }
 
+/// Crée ou met à jour une tâche provenant d'un outil externe, selon son UID
+///
+/// Une tâche existante garde son projet et n'est jamais rouverte : elle est
+/// seulement marquée complétée si l'outil externe l'a terminée.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `input` - Tâche externe
+///
+/// # Retourne
+/// La tâche et `true` si elle vient d'être créée
+pub fn upsert_task_by_external_uid(
+    conn: &Connection,
+    input: &ExternalTaskInput,
+) -> Result<(Task, bool)> {
+    let existing_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM tasks WHERE external_uid = ?1",
+            [&input.external_uid],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let (task_id, created) = match existing_id {
+        Some(task_id) => {
+            conn.execute(
+                "UPDATE tasks
+                 SET title = ?1,
+                     description = ?2,
+                     due_date = ?3,
+                     priority = ?4,
+                     is_completed = CASE WHEN ?5 IS NOT NULL THEN 1 ELSE is_completed END,
+                     completed_at = CASE WHEN ?5 IS NOT NULL AND is_completed = 0
+                                         THEN ?5 ELSE completed_at END,
+                     completed_utc_offset_minutes = CASE WHEN ?5 IS NOT NULL AND is_completed = 0
+                                         THEN ?6 ELSE completed_utc_offset_minutes END,
+                     updated_at = datetime('now')
+                 WHERE id = ?7",
+                params![
+                    input.title,
+                    input.description,
+                    input.due_date,
+                    input.priority,
+                    input.completed_at,
+                    current_utc_offset_minutes(),
+                    task_id,
+                ],
+            )?;
+            (task_id, false)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO tasks (title, description, project_id, due_date, priority,
+                                    external_uid, is_completed, completed_at,
+                                    completed_utc_offset_minutes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7 IS NOT NULL, ?7,
+                         CASE WHEN ?7 IS NOT NULL THEN ?8 END)",
+                params![
+                    input.title,
+                    input.description,
+                    input.project_id,
+                    input.due_date,
+                    input.priority,
+                    input.external_uid,
+                    input.completed_at,
+                    current_utc_offset_minutes(),
+                ],
+            )?;
+            (conn.last_insert_rowid(), true)
+        }
+    };
+
+    Ok((get_task_by_id(conn, task_id)?, created))
+}
+
 /// Incrémente le compteur de Pomodoros complétés pour une tâche
 ///
 /// # Arguments
@@ -170,7 +261,8 @@ pub fn increment_completed_pomodoros(conn: &Connection, task_id: i64) -> Result<
 pub fn get_tasks_by_project(conn: &Connection, project_id: i64) -> Result<Vec<Task>> {
     let mut stmt = conn.prepare(
         "SELECT id, title, description, project_id, estimated_pomodoros,
-                completed_pomodoros, is_completed, created_at, updated_at, completed_at
+                completed_pomodoros, is_completed, created_at, updated_at, completed_at,
+                due_date, priority
          FROM tasks
          WHERE project_id = ?1
          ORDER BY created_at DESC",
@@ -188,6 +280,44 @@ pub fn get_tasks_by_project(conn: &Connection, project_id: i64) -> Result<Vec<Ta
             created_at: row.get(7)?,
             updated_at: row.get(8)?,
             completed_at: row.get(9)?,
+            due_date: row.get(10)?,
+            priority: row.get(11)?,
+        })
+    })?;
+
+    tasks.collect()
+}
+
+/// Récupère les tâches liées à un jalon
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `milestone_id` - ID du jalon
+pub fn get_tasks_by_milestone(conn: &Connection, milestone_id: i64) -> Result<Vec<Task>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.title, t.description, t.project_id, t.estimated_pomodoros,
+                t.completed_pomodoros, t.is_completed, t.created_at, t.updated_at, t.completed_at,
+                t.due_date, t.priority
+         FROM tasks t
+         JOIN milestone_tasks mt ON mt.task_id = t.id
+         WHERE mt.milestone_id = ?1
+         ORDER BY t.created_at DESC",
+    )?;
+
+    let tasks = stmt.query_map([milestone_id], |row| {
+        Ok(Task {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            project_id: row.get(3)?,
+            estimated_pomodoros: row.get(4)?,
+            completed_pomodoros: row.get(5)?,
+            is_completed: row.get::<_, i32>(6)? != 0,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+            completed_at: row.get(9)?,
+            due_date: row.get(10)?,
+            priority: row.get(11)?,
         })
     })?;
 
@@ -241,4 +371,31 @@ mod tests {
         assert!(toggled.is_completed);
         assert!(toggled.completed_at.is_some());
     }
+
+    #[test]
+    fn test_tasks_of_archived_project_hidden() {
+        use crate::db::models::{CreateProjectInput, ProjectStatus};
+        use crate::db::queries::{create_project, set_project_status};
+
+        let conn = setup_test_db();
+        let project = create_project(&conn, &CreateProjectInput {
+            name: "Archived".to_string(),
+            color: None,
+            parent_project_id: None,
+        })
+        .unwrap();
+
+        let input = CreateTaskInput {
+            title: "Hidden".to_string(),
+            description: None,
+            project_id: Some(project.id),
+            estimated_pomodoros: 1,
+        };
+        create_task(&conn, &input).unwrap();
+        assert_eq!(get_all_tasks(&conn).unwrap().len(), 1);
+
+        set_project_status(&conn, project.id, &ProjectStatus::Archived).unwrap();
+        assert!(get_all_tasks(&conn).unwrap().is_empty());
+        assert_eq!(get_tasks_by_project(&conn, project.id).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,632 @@
+// Export et import JSON de l'ensemble des données
+use crate::db::migrations::get_schema_version;
+use crate::db::models::{
+    DataExport, ExportedGoal, ExportedMilestone, ExportedProject, ExportedReportTemplate,
+    ExportedSession, ExportedSessionAggregate, ExportedTask, ExportedTaskAggregate, GoalMetric,
+    GoalPeriod, ImportMode, ImportSummary, ProjectStatus, ReportFormat, SessionType,
+};
+use crate::db::queries::{constraint_violation, get_rest_days, get_settings, update_settings};
+use rusqlite::{Connection, Result, params};
+use std::collections::{HashMap, HashSet};
+
+/// Exporte toutes les données de la base
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn export_data(conn: &Connection) -> Result<DataExport> {
+    Ok(DataExport {
+        schema_version: get_schema_version(conn)?,
+        exported_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        settings: get_settings(conn)?,
+        projects: export_projects(conn)?,
+        tasks: export_tasks(conn)?,
+        sessions: export_sessions(conn)?,
+        goals: export_goals(conn)?,
+        milestones: export_milestones(conn)?,
+        rest_days: get_rest_days(conn)?,
+        report_templates: export_report_templates(conn)?,
+        session_aggregates: export_session_aggregates(conn)?,
+        task_aggregates: export_task_aggregates(conn)?,
+    })
+}
+
+fn export_projects(conn: &Connection) -> Result<Vec<ExportedProject>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, color, parent_project_id, status, status_changed_at, completed_at,
+                archived_at, created_at, updated_at
+         FROM projects
+         ORDER BY id",
+    )?;
+    let projects = stmt.query_map([], |row| {
+        let status: String = row.get(4)?;
+        Ok(ExportedProject {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            parent_project_id: row.get(3)?,
+            status: status.parse::<ProjectStatus>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+            status_changed_at: row.get(5)?,
+            completed_at: row.get(6)?,
+            archived_at: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+        })
+    })?;
+
+    projects.collect()
+}
+
+fn export_tasks(conn: &Connection) -> Result<Vec<ExportedTask>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, description, project_id, estimated_pomodoros, completed_pomodoros,
+                is_completed, completed_at, completed_utc_offset_minutes, due_date, priority,
+                external_uid, extra_tags, created_at, updated_at
+         FROM tasks
+         ORDER BY id",
+    )?;
+    let tasks = stmt.query_map([], |row| {
+        Ok(ExportedTask {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            project_id: row.get(3)?,
+            estimated_pomodoros: row.get(4)?,
+            completed_pomodoros: row.get(5)?,
+            is_completed: row.get::<_, i32>(6)? != 0,
+            completed_at: row.get(7)?,
+            completed_utc_offset_minutes: row.get(8)?,
+            due_date: row.get(9)?,
+            priority: row.get(10)?,
+            external_uid: row.get(11)?,
+            extra_tags: row.get(12)?,
+            created_at: row.get(13)?,
+            updated_at: row.get(14)?,
+        })
+    })?;
+
+    tasks.collect()
+}
+
+fn export_sessions(conn: &Connection) -> Result<Vec<ExportedSession>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at, completed_at, duration_minutes, session_type,
+                interrupted, utc_offset_minutes, focus_rating
+         FROM pomodoro_sessions
+         ORDER BY id",
+    )?;
+    let sessions = stmt.query_map([], |row| {
+        let session_type: String = row.get(5)?;
+        Ok(ExportedSession {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            started_at: row.get(2)?,
+            completed_at: row.get(3)?,
+            duration_minutes: row.get(4)?,
+            session_type: SessionType::from_str(&session_type)
+                .map_err(|_| rusqlite::Error::InvalidQuery)?,
+            interrupted: row.get::<_, i32>(6)? != 0,
+            utc_offset_minutes: row.get(7)?,
+            focus_rating: row.get(8)?,
+        })
+    })?;
+
+    sessions.collect()
+}
+
+fn export_goals(conn: &Connection) -> Result<Vec<ExportedGoal>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, metric, target, period, created_at, updated_at
+         FROM project_goals
+         ORDER BY id",
+    )?;
+    let goals = stmt.query_map([], |row| {
+        let metric: String = row.get(2)?;
+        let period: String = row.get(4)?;
+        Ok(ExportedGoal {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            metric: metric.parse::<GoalMetric>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+            target: row.get(3)?,
+            period: period.parse::<GoalPeriod>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    })?;
+
+    goals.collect()
+}
+
+fn export_milestones(conn: &Connection) -> Result<Vec<ExportedMilestone>> {
+    let mut links_stmt = conn.prepare(
+        "SELECT milestone_id, task_id FROM milestone_tasks ORDER BY milestone_id, task_id",
+    )?;
+    let mut task_ids: HashMap<i64, Vec<i64>> = HashMap::new();
+    for link in links_stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (milestone_id, task_id) = link?;
+        task_ids.entry(milestone_id).or_default().push(task_id);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, name, description, start_date, due_date, created_at, updated_at
+         FROM milestones
+         ORDER BY id",
+    )?;
+    let milestones = stmt.query_map([], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(ExportedMilestone {
+            id,
+            project_id: row.get(1)?,
+            name: row.get(2)?,
+            description: row.get(3)?,
+            start_date: row.get(4)?,
+            due_date: row.get(5)?,
+            task_ids: task_ids.remove(&id).unwrap_or_default(),
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    })?;
+
+    milestones.collect()
+}
+
+fn export_report_templates(conn: &Connection) -> Result<Vec<ExportedReportTemplate>> {
+    let mut stmt = conn.prepare("SELECT format, content FROM report_templates ORDER BY format")?;
+    let templates = stmt.query_map([], |row| {
+        let format: String = row.get(0)?;
+        Ok(ExportedReportTemplate {
+            format: format.parse::<ReportFormat>().map_err(|_| rusqlite::Error::InvalidQuery)?,
+            content: row.get(1)?,
+        })
+    })?;
+
+    templates.collect()
+}
+
+fn export_session_aggregates(conn: &Connection) -> Result<Vec<ExportedSessionAggregate>> {
+    let mut stmt = conn.prepare(
+        "SELECT day, task_id, project_id, completed_count, focus_minutes, interrupted_count
+         FROM session_aggregates
+         ORDER BY id",
+    )?;
+    let aggregates = stmt.query_map([], |row| {
+        Ok(ExportedSessionAggregate {
+            day: row.get(0)?,
+            task_id: row.get(1)?,
+            project_id: row.get(2)?,
+            completed_count: row.get(3)?,
+            focus_minutes: row.get(4)?,
+            interrupted_count: row.get(5)?,
+        })
+    })?;
+
+    aggregates.collect()
+}
+
+fn export_task_aggregates(conn: &Connection) -> Result<Vec<ExportedTaskAggregate>> {
+    let mut stmt = conn.prepare(
+        "SELECT day, project_id, completed_count FROM task_aggregates ORDER BY id",
+    )?;
+    let aggregates = stmt.query_map([], |row| {
+        Ok(ExportedTaskAggregate {
+            day: row.get(0)?,
+            project_id: row.get(1)?,
+            completed_count: row.get(2)?,
+        })
+    })?;
+
+    aggregates.collect()
+}
+
+/// Importe un export JSON dans une seule transaction
+///
+/// L'export est entièrement validé avant toute écriture (version du schéma,
+/// références entre éléments, cycles de projets). Les IDs sont réattribués
+/// par la base et les références remappées.
+///
+/// En mode `Replace`, toutes les données et les paramètres sont remplacés.
+/// En mode `Merge`, les éléments sont ajoutés aux données existantes ; les
+/// paramètres locaux sont conservés, ainsi que les jours de repos et modèles
+/// de rapport déjà présents.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `data` - Export à importer
+/// * `mode` - Remplacement complet ou fusion
+pub fn import_data(conn: &Connection, data: &DataExport, mode: ImportMode) -> Result<ImportSummary> {
+    let current_version = get_schema_version(conn)?;
+    if data.schema_version > current_version {
+        return Err(constraint_violation(format!(
+            "Export was created by a newer version of the application (schema {} > {})",
+            data.schema_version, current_version
+        )));
+    }
+    let project_order = validate_export(data)?;
+
+    let tx = conn.unchecked_transaction()?;
+
+    if mode == ImportMode::Replace {
+        for table in [
+            "milestone_tasks",
+            "milestones",
+            "project_goals",
+            "pomodoro_sessions",
+            "tasks",
+            "projects",
+            "rest_days",
+            "report_templates",
+            "session_aggregates",
+            "task_aggregates",
+        ] {
+            tx.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+        update_settings(&tx, &data.settings)?;
+    }
+
+    let mut summary = ImportSummary::default();
+
+    let mut project_ids: HashMap<i64, i64> = HashMap::new();
+    for index in project_order {
+        let project = &data.projects[index];
+        let parent_id = project.parent_project_id.map(|id| project_ids[&id]);
+        tx.execute(
+            "INSERT INTO projects (name, color, parent_project_id, status, status_changed_at,
+                                   completed_at, archived_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                project.name,
+                project.color,
+                parent_id,
+                project.status.as_str(),
+                // Absente d'un export ancien ou écrit à la main
+                project.status_changed_at.as_ref().unwrap_or(&project.created_at),
+                project.completed_at,
+                project.archived_at,
+                project.created_at,
+                project.updated_at,
+            ],
+        )?;
+        project_ids.insert(project.id, tx.last_insert_rowid());
+        summary.projects_count += 1;
+    }
+
+    let mut task_ids: HashMap<i64, i64> = HashMap::new();
+    for task in &data.tasks {
+        tx.execute(
+            "INSERT INTO tasks (title, description, project_id, estimated_pomodoros,
+                                completed_pomodoros, is_completed, completed_at,
+                                completed_utc_offset_minutes, due_date, priority,
+                                external_uid, extra_tags, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                     -- Un UID déjà présent localement (fusion) n'est pas dupliqué
+                     (SELECT ?11 WHERE NOT EXISTS (SELECT 1 FROM tasks WHERE external_uid = ?11)),
+                     ?12, ?13, ?14)",
+            params![
+                task.title,
+                task.description,
+                task.project_id.map(|id| project_ids[&id]),
+                task.estimated_pomodoros,
+                task.completed_pomodoros,
+                task.is_completed,
+                task.completed_at,
+                task.completed_utc_offset_minutes,
+                task.due_date,
+                task.priority,
+                task.external_uid,
+                task.extra_tags,
+                task.created_at,
+                task.updated_at,
+            ],
+        )?;
+        task_ids.insert(task.id, tx.last_insert_rowid());
+        summary.tasks_count += 1;
+    }
+
+    for session in &data.sessions {
+        tx.execute(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes,
+                                            session_type, interrupted, utc_offset_minutes,
+                                            focus_rating)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                session.task_id.map(|id| task_ids[&id]),
+                session.started_at,
+                session.completed_at,
+                session.duration_minutes,
+                session.session_type.as_str(),
+                session.interrupted,
+                session.utc_offset_minutes,
+                session.focus_rating,
+            ],
+        )?;
+        summary.sessions_count += 1;
+    }
+
+    for goal in &data.goals {
+        tx.execute(
+            "INSERT INTO project_goals (project_id, metric, target, period, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                project_ids[&goal.project_id],
+                goal.metric.as_str(),
+                goal.target,
+                goal.period.as_str(),
+                goal.created_at,
+                goal.updated_at,
+            ],
+        )?;
+        summary.goals_count += 1;
+    }
+
+    for milestone in &data.milestones {
+        tx.execute(
+            "INSERT INTO milestones (project_id, name, description, start_date, due_date,
+                                     created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                project_ids[&milestone.project_id],
+                milestone.name,
+                milestone.description,
+                milestone.start_date,
+                milestone.due_date,
+                milestone.created_at,
+                milestone.updated_at,
+            ],
+        )?;
+        let milestone_id = tx.last_insert_rowid();
+        for task_id in &milestone.task_ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO milestone_tasks (milestone_id, task_id) VALUES (?1, ?2)",
+                params![milestone_id, task_ids[task_id]],
+            )?;
+        }
+        summary.milestones_count += 1;
+    }
+
+    for day in &data.rest_days {
+        summary.rest_days_count += tx.execute(
+            "INSERT OR IGNORE INTO rest_days (date, note) VALUES (?1, ?2)",
+            params![day.date, day.note],
+        )? as i32;
+    }
+
+    for template in &data.report_templates {
+        summary.report_templates_count += tx.execute(
+            "INSERT OR IGNORE INTO report_templates (format, content) VALUES (?1, ?2)",
+            params![template.format.as_str(), template.content],
+        )? as i32;
+    }
+
+    for aggregate in &data.session_aggregates {
+        tx.execute(
+            "INSERT INTO session_aggregates (day, task_id, project_id, completed_count,
+                                             focus_minutes, interrupted_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                aggregate.day,
+                aggregate.task_id.map(|id| task_ids[&id]),
+                aggregate.project_id.map(|id| project_ids[&id]),
+                aggregate.completed_count,
+                aggregate.focus_minutes,
+                aggregate.interrupted_count,
+            ],
+        )?;
+        summary.aggregates_count += 1;
+    }
+
+    for aggregate in &data.task_aggregates {
+        tx.execute(
+            "INSERT INTO task_aggregates (day, project_id, completed_count) VALUES (?1, ?2, ?3)",
+            params![
+                aggregate.day,
+                aggregate.project_id.map(|id| project_ids[&id]),
+                aggregate.completed_count,
+            ],
+        )?;
+        summary.aggregates_count += 1;
+    }
+
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Vérifie la cohérence d'un export avant écriture
+///
+/// Retourne l'ordre d'insertion des projets (parents avant enfants).
+fn validate_export(data: &DataExport) -> Result<Vec<usize>> {
+    let mut project_ids = HashSet::new();
+    for project in &data.projects {
+        if !project_ids.insert(project.id) {
+            return Err(constraint_violation(format!("Duplicate project ID {}", project.id)));
+        }
+    }
+    let mut task_ids = HashSet::new();
+    for task in &data.tasks {
+        if !task_ids.insert(task.id) {
+            return Err(constraint_violation(format!("Duplicate task ID {}", task.id)));
+        }
+    }
+
+    let check_project = |id: i64, owner: &str| {
+        if project_ids.contains(&id) {
+            Ok(())
+        } else {
+            Err(constraint_violation(format!(
+                "{} references unknown project {}",
+                owner, id
+            )))
+        }
+    };
+    let check_task = |id: i64, owner: &str| {
+        if task_ids.contains(&id) {
+            Ok(())
+        } else {
+            Err(constraint_violation(format!("{} references unknown task {}", owner, id)))
+        }
+    };
+
+    for project in &data.projects {
+        if let Some(parent_id) = project.parent_project_id {
+            check_project(parent_id, &format!("Project {}", project.id))?;
+        }
+    }
+    for task in &data.tasks {
+        if let Some(project_id) = task.project_id {
+            check_project(project_id, &format!("Task {}", task.id))?;
+        }
+    }
+    for session in &data.sessions {
+        if let Some(task_id) = session.task_id {
+            check_task(task_id, &format!("Session {}", session.id))?;
+        }
+    }
+    for goal in &data.goals {
+        check_project(goal.project_id, &format!("Goal {}", goal.id))?;
+    }
+    for aggregate in &data.session_aggregates {
+        if let Some(task_id) = aggregate.task_id {
+            check_task(task_id, &format!("Session aggregate of {}", aggregate.day))?;
+        }
+        if let Some(project_id) = aggregate.project_id {
+            check_project(project_id, &format!("Session aggregate of {}", aggregate.day))?;
+        }
+    }
+    for aggregate in &data.task_aggregates {
+        if let Some(project_id) = aggregate.project_id {
+            check_project(project_id, &format!("Task aggregate of {}", aggregate.day))?;
+        }
+    }
+    for milestone in &data.milestones {
+        let owner = format!("Milestone {}", milestone.id);
+        check_project(milestone.project_id, &owner)?;
+        for task_id in &milestone.task_ids {
+            check_task(*task_id, &owner)?;
+        }
+    }
+
+    // Tri topologique : un projet est inséré une fois son parent inséré
+    let mut order = Vec::with_capacity(data.projects.len());
+    let mut inserted = HashSet::new();
+    while order.len() < data.projects.len() {
+        let ready: Vec<usize> = data
+            .projects
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| {
+                !inserted.contains(&p.id)
+                    && p.parent_project_id.map_or(true, |id| inserted.contains(&id))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if ready.is_empty() {
+            return Err(constraint_violation("Project hierarchy contains a cycle"));
+        }
+        for index in ready {
+            inserted.insert(data.projects[index].id);
+            order.push(index);
+        }
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn seed(conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO projects (name) VALUES ('Client X');
+             INSERT INTO projects (name, parent_project_id) VALUES ('Phase 1', 1);
+             INSERT INTO tasks (title, project_id) VALUES ('Spec', 2);
+             INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, '2025-11-10 09:00:00', '2025-11-10 09:25:00', 25, 'work');
+             INSERT INTO project_goals (project_id, metric, target, period) VALUES (1, 'pomodoros', 10, 'week');
+             INSERT INTO milestones (project_id, name, start_date, due_date)
+                 VALUES (2, 'Beta', '2025-11-01', '2025-11-30');
+             INSERT INTO milestone_tasks (milestone_id, task_id) VALUES (1, 1);
+             INSERT INTO rest_days (date) VALUES ('2025-12-25');
+             INSERT INTO session_aggregates (day, project_id, completed_count, focus_minutes)
+                 VALUES ('2023-03-01', 2, 2, 50);
+             UPDATE settings SET work_duration = 50 WHERE id = 1;",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_export_and_replace_import_round_trip() {
+        let source = setup_test_db();
+        seed(&source);
+        let export = export_data(&source).unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+
+        let target = setup_test_db();
+        target.execute("INSERT INTO projects (name) VALUES ('Old')", []).unwrap();
+        let parsed: DataExport = serde_json::from_str(&json).unwrap();
+        let summary = import_data(&target, &parsed, ImportMode::Replace).unwrap();
+
+        assert_eq!(summary.projects_count, 2);
+        assert_eq!(summary.milestones_count, 1);
+        let reexported = export_data(&target).unwrap();
+        assert_eq!(reexported.settings.work_duration, 50);
+        assert_eq!(reexported.projects.len(), 2);
+        assert!(reexported.projects.iter().all(|p| p.name != "Old"));
+        let phase = reexported.projects.iter().find(|p| p.name == "Phase 1").unwrap();
+        let client = reexported.projects.iter().find(|p| p.name == "Client X").unwrap();
+        assert_eq!(phase.parent_project_id, Some(client.id));
+        assert_eq!(reexported.tasks[0].project_id, Some(phase.id));
+        assert_eq!(reexported.sessions[0].task_id, Some(reexported.tasks[0].id));
+        assert_eq!(reexported.milestones[0].task_ids, vec![reexported.tasks[0].id]);
+        assert_eq!(reexported.session_aggregates[0].project_id, Some(phase.id));
+    }
+
+    #[test]
+    fn test_merge_import_remaps_ids_and_rejects_invalid_exports() {
+        let conn = setup_test_db();
+        seed(&conn);
+        let mut export = export_data(&conn).unwrap();
+
+        let summary = import_data(&conn, &export, ImportMode::Merge).unwrap();
+        assert_eq!(summary.sessions_count, 1);
+        assert_eq!(summary.rest_days_count, 0);
+        let merged = export_data(&conn).unwrap();
+        assert_eq!(merged.projects.len(), 4);
+        assert_eq!(merged.sessions.len(), 2);
+        assert_eq!(merged.sessions[1].task_id, Some(merged.tasks[1].id));
+
+        export.sessions[0].task_id = Some(999);
+        assert!(import_data(&conn, &export, ImportMode::Replace).is_err());
+        export.sessions[0].task_id = None;
+        export.schema_version += 1;
+        assert!(import_data(&conn, &export, ImportMode::Replace).is_err());
+
+        // Aucune écriture après un import rejeté
+        assert_eq!(export_data(&conn).unwrap().projects.len(), 4);
+    }
+
+    #[test]
+    fn test_import_project_without_status_date() {
+        let source = setup_test_db();
+        seed(&source);
+        let mut json = serde_json::to_value(export_data(&source).unwrap()).unwrap();
+        for project in json["projects"].as_array_mut().unwrap() {
+            project.as_object_mut().unwrap().remove("statusChangedAt");
+        }
+        let export: DataExport = serde_json::from_value(json).unwrap();
+
+        let conn = setup_test_db();
+        import_data(&conn, &export, ImportMode::Replace).unwrap();
+        let projects = crate::db::queries::get_all_projects(&conn).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert!(projects.iter().all(|p| p.status_changed_at == p.created_at));
+    }
+}
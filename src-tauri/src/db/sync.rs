@@ -0,0 +1,1447 @@
+// Synchronisation entre appareils via un dossier partagé (Syncthing, etc.)
+//
+// Chaque projet, tâche et session porte un UUID stable. Des triggers notent
+// les champs modifiés localement dans `sync_pending` ; à la synchronisation,
+// ils deviennent des changements versionnés (`sync_changes`) ajoutés au
+// fichier `<dossier>/<device_id>.jsonl` de l'appareil. Les fichiers des
+// autres appareils sont relus à partir du dernier changement importé.
+//
+// Chaque champ est résolu indépendamment : la version la plus récente (date
+// de modification, puis identifiant d'appareil) l'emporte sur tous les
+// appareils. Deux modifications concurrentes d'un même champ (aucune ne
+// connaissait l'autre) sont signalées comme conflit. Une suppression
+// l'emporte sur toute modification de la ligne.
+//
+// Seuls les projets, les tâches et les sessions sont synchronisés. Les
+// objectifs, jalons (et leurs tâches), jours de repos et paramètres restent
+// propres à chaque appareil et ne sont pas fusionnés.
+//
+// Les fichiers de changements sont en clair : une base chiffrée n'est donc
+// pas synchronisée (`sync_folder` et `set_sync_folder` la refusent), et le
+// fichier de l'appareil est retiré au chiffrement (`remove_change_file`).
+use crate::db::encryption::is_encrypted;
+use crate::db::models::{SyncConflict, SyncReport, SyncStatus};
+use crate::db::queries::completed_work;
+use chrono::{Duration, NaiveDateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Tables synchronisées, dans l'ordre d'application (parents avant enfants)
+///
+/// Les autres tables ne sont pas échangées : `project_goals`, `milestones`
+/// et `milestone_tasks` exigent un projet (ou un jalon) déjà connu, qu'une
+/// ligne reçue avant son parent ne peut pas laisser vide comme le fait
+/// `resolve_references` ; `rest_days` est indexée par date et non par ligne,
+/// et `settings` décrit l'appareil lui-même.
+pub const SYNC_TABLE_NAMES: [&str; 3] = ["projects", "tasks", "pomodoro_sessions"];
+
+/// Expression SQL générant un UUID v4
+pub const UUID_EXPRESSION: &str = "lower(hex(randomblob(4)) || '-' || hex(randomblob(2))
+    || '-4' || substr(hex(randomblob(2)), 2)
+    || '-' || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2)
+    || '-' || hex(randomblob(6)))";
+
+/// Horodatage SQL des changements, à la milliseconde
+const NOW_EXPRESSION: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+/// Formats Rust équivalents à `NOW_EXPRESSION`
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+const TIMESTAMP_PARSE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.fZ";
+
+/// Pseudo-colonne d'une ligne insérée (tous ses champs sont à envoyer)
+const INSERTED: &str = "*";
+
+/// Pseudo-colonne d'une ligne supprimée
+const DELETED: &str = "_deleted";
+
+/// Profondeur maximale parcourue dans l'historique d'un champ
+const MAX_HISTORY_DEPTH: usize = 10_000;
+
+struct SyncTable {
+    name: &'static str,
+    columns: &'static [SyncColumn],
+}
+
+struct SyncColumn {
+    name: &'static str,
+    /// Table référencée : la valeur échangée est l'UUID de la ligne parente
+    references: Option<&'static str>,
+}
+
+const fn column(name: &'static str) -> SyncColumn {
+    SyncColumn {
+        name,
+        references: None,
+    }
+}
+
+const fn reference(name: &'static str, table: &'static str) -> SyncColumn {
+    SyncColumn {
+        name,
+        references: Some(table),
+    }
+}
+
+/// Colonnes synchronisées (`updated_at` et `external_uid` restent locaux)
+///
+/// Le compteur `completed_pomodoros` n'est pas échangé : deux appareils
+/// l'incrémentant en même temps perdraient une session. Il est recalculé à
+/// partir des sessions reçues (voir `update_pomodoro_counters`).
+const SYNC_TABLES: [SyncTable; 3] = [
+    SyncTable {
+        name: SYNC_TABLE_NAMES[0],
+        columns: &[
+            column("name"),
+            column("color"),
+            column("created_at"),
+            column("status"),
+            column("status_changed_at"),
+            column("completed_at"),
+            column("archived_at"),
+            reference("parent_project_id", "projects"),
+        ],
+    },
+    SyncTable {
+        name: SYNC_TABLE_NAMES[1],
+        columns: &[
+            column("title"),
+            column("description"),
+            reference("project_id", "projects"),
+            column("estimated_pomodoros"),
+            column("is_completed"),
+            column("created_at"),
+            column("completed_at"),
+            column("completed_utc_offset_minutes"),
+            column("due_date"),
+            column("priority"),
+            column("extra_tags"),
+        ],
+    },
+    SyncTable {
+        name: SYNC_TABLE_NAMES[2],
+        columns: &[
+            reference("task_id", "tasks"),
+            column("started_at"),
+            column("completed_at"),
+            column("duration_minutes"),
+            column("session_type"),
+            column("interrupted"),
+            column("utc_offset_minutes"),
+            column("focus_rating"),
+        ],
+    },
+];
+
+/// Erreur de synchronisation
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Dossier non configuré ou invalide
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Version d'un champ : les versions sont ordonnées par date puis par appareil
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    changed_at: String,
+    device_id: String,
+}
+
+/// Changement d'un champ, local ou reçu d'un autre appareil
+struct Change {
+    device_seq: i64,
+    table: String,
+    row_uuid: String,
+    column: String,
+    value: Value,
+    version: Version,
+    /// Version remplacée par ce changement sur son appareil d'origine
+    base: Option<Version>,
+}
+
+/// Ligne d'un fichier de changements
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileChange {
+    seq: i64,
+    table: String,
+    row_uuid: String,
+    column: String,
+    value: serde_json::Value,
+    changed_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_changed_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_device_id: Option<String>,
+}
+
+/// (Ré)installe les triggers qui notent les modifications locales
+///
+/// Les modifications faites pendant l'import (`sync_state.applying = 1`) ne
+/// sont pas notées.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub(crate) fn install_triggers(conn: &Connection) -> rusqlite::Result<()> {
+    for table in &SYNC_TABLES {
+        let name = table.name;
+        let updated_columns: String = table
+            .columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "INSERT OR REPLACE INTO sync_pending (table_name, row_uuid, column_name, changed_at)
+                     SELECT '{name}', NEW.uuid, '{column}', {NOW_EXPRESSION}
+                     WHERE OLD.{column} IS NOT NEW.{column};",
+                    column = column.name
+                )
+            })
+            .collect();
+
+        conn.execute_batch(&format!(
+            "DROP TRIGGER IF EXISTS sync_{name}_insert;
+             DROP TRIGGER IF EXISTS sync_{name}_update;
+             DROP TRIGGER IF EXISTS sync_{name}_delete;
+
+             CREATE TRIGGER sync_{name}_insert AFTER INSERT ON {name}
+             BEGIN
+                 UPDATE {name} SET uuid = {UUID_EXPRESSION}
+                 WHERE rowid = NEW.rowid AND uuid IS NULL;
+                 INSERT OR REPLACE INTO sync_pending (table_name, row_uuid, column_name, changed_at)
+                 SELECT '{name}', uuid, '{INSERTED}', {NOW_EXPRESSION} FROM {name}
+                 WHERE rowid = NEW.rowid AND (SELECT applying FROM sync_state) = 0;
+             END;
+
+             CREATE TRIGGER sync_{name}_update AFTER UPDATE ON {name}
+             WHEN (SELECT applying FROM sync_state) = 0
+             BEGIN
+                 {updated_columns}
+             END;
+
+             CREATE TRIGGER sync_{name}_delete AFTER DELETE ON {name}
+             WHEN (SELECT applying FROM sync_state) = 0 AND OLD.uuid IS NOT NULL
+             BEGIN
+                 INSERT OR REPLACE INTO sync_pending (table_name, row_uuid, column_name, changed_at)
+                 VALUES ('{name}', OLD.uuid, '{DELETED}', {NOW_EXPRESSION});
+             END;"
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Suspend (ou rétablit) l'enregistrement des modifications locales
+///
+/// Utilisé pendant l'import des changements des autres appareils, ainsi que
+/// par `purge_history` dont les suppressions restent propres à cet appareil.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `suspended` - Vrai pour ne plus noter les modifications
+pub(crate) fn set_tracking_suspended(conn: &Connection, suspended: bool) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE sync_state SET applying = ?1 WHERE id = 1",
+        params![suspended],
+    )?;
+
+    Ok(())
+}
+
+/// Récupère l'état de la synchronisation
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn get_sync_status(conn: &Connection) -> Result<SyncStatus, SyncError> {
+    let status = conn.query_row(
+        "SELECT device_id, folder, (SELECT COUNT(*) FROM sync_pending), last_synced_at
+         FROM sync_state WHERE id = 1",
+        [],
+        |row| {
+            Ok(SyncStatus {
+                device_id: row.get(0)?,
+                folder: row.get(1)?,
+                pending_count: row.get(2)?,
+                last_synced_at: row.get(3)?,
+            })
+        },
+    )?;
+
+    Ok(status)
+}
+
+/// Définit le dossier partagé entre les appareils
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `folder` - Chemin absolu du dossier, ou vide pour désactiver la synchronisation
+pub fn set_sync_folder(conn: &Connection, folder: Option<&str>) -> Result<SyncStatus, SyncError> {
+    let folder = folder.map(str::trim).filter(|folder| !folder.is_empty());
+    if let Some(folder) = folder {
+        if !Path::new(folder).is_absolute() {
+            return Err(SyncError::Invalid(format!(
+                "Sync folder must be an absolute path: {}",
+                folder
+            )));
+        }
+        ensure_not_encrypted(conn)?;
+    }
+
+    conn.execute(
+        "UPDATE sync_state SET folder = ?1 WHERE id = 1",
+        params![folder],
+    )?;
+
+    get_sync_status(conn)
+}
+
+/// Retire le fichier de changements de cet appareil et désactive la synchronisation
+///
+/// Appelé avant le chiffrement de la base : les changements déjà exportés
+/// resteraient sinon en clair dans le dossier partagé. Les fichiers des
+/// autres appareils ne sont pas touchés. Tout l'historique local sera
+/// réexporté si la synchronisation est réactivée.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+///
+/// # Retourne
+/// Vrai si un fichier a été supprimé
+pub(crate) fn remove_change_file(conn: &Connection) -> Result<bool, SyncError> {
+    let status = get_sync_status(conn)?;
+    let removed = match status.folder {
+        Some(folder) => {
+            let path = Path::new(&folder).join(format!("{}.jsonl", status.device_id));
+            match std::fs::remove_file(path) {
+                Ok(()) => true,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        None => false,
+    };
+
+    conn.execute(
+        "UPDATE sync_state SET folder = NULL, last_exported_seq = 0 WHERE id = 1",
+        [],
+    )?;
+
+    Ok(removed)
+}
+
+/// Synchronise avec le dossier configuré
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub fn sync_now(conn: &Connection) -> Result<SyncReport, SyncError> {
+    let folder = get_sync_status(conn)?
+        .folder
+        .ok_or_else(|| SyncError::Invalid("No sync folder configured".to_string()))?;
+
+    sync_folder(conn, Path::new(&folder))
+}
+
+/// Synchronise la base avec un dossier partagé
+///
+/// Les modifications locales sont enregistrées, les changements des autres
+/// appareils sont appliqués, puis les changements locaux sont ajoutés au
+/// fichier de cet appareil.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `folder` - Dossier partagé entre les appareils
+pub fn sync_folder(conn: &Connection, folder: &Path) -> Result<SyncReport, SyncError> {
+    ensure_not_encrypted(conn)?;
+    let device_id: String =
+        conn.query_row("SELECT device_id FROM sync_state WHERE id = 1", [], |row| {
+            row.get(0)
+        })?;
+    let mut report = SyncReport {
+        exported_count: 0,
+        imported_count: 0,
+        conflicts: Vec::new(),
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let device_id = if is_device_id_reused(&tx, &device_id, folder)? {
+        renew_device_id(&tx)?
+    } else {
+        device_id
+    };
+    record_local_changes(&tx, &device_id)?;
+    set_tracking_suspended(&tx, true)?;
+    let counts_before = completed_session_counts(&tx)?;
+    import_peer_changes(&tx, &device_id, folder, &mut report)?;
+    resolve_references(&tx)?;
+    update_pomodoro_counters(&tx, &counts_before)?;
+    set_tracking_suspended(&tx, false)?;
+    tx.commit()?;
+
+    report.exported_count = export_local_changes(conn, &device_id, folder)? as i32;
+    conn.execute(
+        &format!("UPDATE sync_state SET last_synced_at = {NOW_EXPRESSION} WHERE id = 1"),
+        [],
+    )?;
+
+    Ok(report)
+}
+
+/// Transforme les champs notés par les triggers en changements versionnés
+fn record_local_changes(conn: &Connection, device_id: &str) -> Result<usize, SyncError> {
+    let mut rows: BTreeMap<(String, String), HashMap<String, String>> = BTreeMap::new();
+    {
+        let mut stmt =
+            conn.prepare("SELECT table_name, row_uuid, column_name, changed_at FROM sync_pending")?;
+        let pending = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        for entry in pending {
+            let (table, row_uuid, column, changed_at): (String, String, String, String) = entry?;
+            rows.entry((table, row_uuid))
+                .or_default()
+                .insert(column, changed_at);
+        }
+    }
+
+    let mut count = 0;
+    for ((table_name, row_uuid), columns) in rows {
+        let Some(table) = find_table(&table_name) else {
+            continue;
+        };
+        if let Some(changed_at) = columns.get(DELETED) {
+            if record_local_change(
+                conn,
+                device_id,
+                table,
+                &row_uuid,
+                DELETED,
+                Value::Integer(1),
+                changed_at,
+            )? {
+                count += 1;
+            }
+            continue;
+        }
+
+        let inserted_at = columns.get(INSERTED);
+        for column in table.columns {
+            let changed_at = match (inserted_at, columns.get(column.name)) {
+                (Some(inserted_at), Some(changed_at)) => inserted_at.max(changed_at),
+                (Some(changed_at), None) | (None, Some(changed_at)) => changed_at,
+                (None, None) => continue,
+            };
+            // Ligne supprimée depuis sans passer par le trigger : rien à envoyer
+            let Some(value) = read_value(conn, table, column, &row_uuid)? else {
+                break;
+            };
+            if record_local_change(
+                conn,
+                device_id,
+                table,
+                &row_uuid,
+                column.name,
+                value,
+                changed_at,
+            )? {
+                count += 1;
+            }
+        }
+    }
+    conn.execute("DELETE FROM sync_pending", [])?;
+
+    Ok(count)
+}
+
+/// Enregistre un changement local, par-dessus la version actuelle du champ
+///
+/// Le changement est ignoré si la valeur n'a pas changé. Sa date est au
+/// moins postérieure à celle de la version remplacée, même si l'horloge de
+/// l'appareil qui l'a produite était en avance.
+fn record_local_change(
+    conn: &Connection,
+    device_id: &str,
+    table: &SyncTable,
+    row_uuid: &str,
+    column: &str,
+    value: Value,
+    changed_at: &str,
+) -> Result<bool, SyncError> {
+    let winner = current_winner(conn, table.name, row_uuid, column)?;
+    let mut changed_at = changed_at.to_string();
+    if let Some((version, winner_value)) = &winner {
+        if *winner_value == value {
+            return Ok(false);
+        }
+        if changed_at <= version.changed_at {
+            changed_at = next_timestamp(&version.changed_at);
+        }
+    }
+
+    let device_seq: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(device_seq), 0) + 1 FROM sync_changes WHERE device_id = ?1",
+        params![device_id],
+        |row| row.get(0),
+    )?;
+    insert_change(
+        conn,
+        &Change {
+            device_seq,
+            table: table.name.to_string(),
+            row_uuid: row_uuid.to_string(),
+            column: column.to_string(),
+            value,
+            version: Version {
+                changed_at,
+                device_id: device_id.to_string(),
+            },
+            base: winner.map(|(version, _)| version),
+        },
+    )?;
+
+    Ok(true)
+}
+
+/// Valeur actuelle d'un champ (l'UUID du parent pour une référence)
+fn read_value(
+    conn: &Connection,
+    table: &SyncTable,
+    column: &SyncColumn,
+    row_uuid: &str,
+) -> rusqlite::Result<Option<Value>> {
+    let expression = match column.references {
+        Some(parent) => format!(
+            "(SELECT p.uuid FROM {} p WHERE p.id = t.{})",
+            parent, column.name
+        ),
+        None => format!("t.{}", column.name),
+    };
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM {} t WHERE t.uuid = ?1",
+            expression, table.name
+        ),
+        params![row_uuid],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Refuse de synchroniser une base chiffrée, dont les changements seraient écrits en clair
+fn ensure_not_encrypted(conn: &Connection) -> Result<(), SyncError> {
+    if conn.path().is_some_and(|path| is_encrypted(Path::new(path))) {
+        return Err(SyncError::Invalid(
+            "An encrypted database cannot be synced: change files are not encrypted".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Indique si le fichier de l'appareil contient des changements que la base ignore
+///
+/// C'est le cas d'une base restaurée ou copiée depuis un autre appareil : ses
+/// prochains numéros de changement seraient déjà pris.
+fn is_device_id_reused(
+    conn: &Connection,
+    device_id: &str,
+    folder: &Path,
+) -> Result<bool, SyncError> {
+    let path = folder.join(format!("{}.jsonl", device_id));
+    if !path.is_file() {
+        return Ok(false);
+    }
+    let last_exported: i64 = conn.query_row(
+        "SELECT last_exported_seq FROM sync_state WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(!read_change_file(&path, device_id, last_exported)?.is_empty())
+}
+
+/// Attribue un nouvel identifiant à cet appareil
+///
+/// Les changements pas encore exportés passent sous le nouvel identifiant.
+/// Le fichier de l'ancien identifiant est ensuite lu comme celui d'un autre
+/// appareil : les changements que la base ignore y sont récupérés.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+pub(crate) fn renew_device_id(conn: &Connection) -> rusqlite::Result<String> {
+    let (old_id, last_exported): (String, i64) = conn.query_row(
+        "SELECT device_id, last_exported_seq FROM sync_state WHERE id = 1",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let new_id: String =
+        conn.query_row(&format!("SELECT {UUID_EXPRESSION}"), [], |row| row.get(0))?;
+
+    conn.execute(
+        "UPDATE sync_changes SET device_id = ?1 WHERE device_id = ?2 AND device_seq > ?3",
+        params![new_id, old_id, last_exported],
+    )?;
+    conn.execute(
+        "UPDATE sync_changes SET base_device_id = ?1
+         WHERE base_device_id = ?2
+           AND EXISTS (SELECT 1 FROM sync_changes c
+                       WHERE c.device_id = ?1 AND c.changed_at = sync_changes.base_changed_at
+                         AND c.table_name = sync_changes.table_name
+                         AND c.row_uuid = sync_changes.row_uuid
+                         AND c.column_name = sync_changes.column_name)",
+        params![new_id, old_id],
+    )?;
+    conn.execute(
+        "UPDATE sync_state SET device_id = ?1, last_exported_seq = 0 WHERE id = 1",
+        params![new_id],
+    )?;
+
+    Ok(new_id)
+}
+
+/// Lit les fichiers des autres appareils et applique leurs nouveaux changements
+fn import_peer_changes(
+    conn: &Connection,
+    device_id: &str,
+    folder: &Path,
+    report: &mut SyncReport,
+) -> Result<(), SyncError> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+
+    let mut peers: Vec<(String, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        if let Some(peer) = path.file_stem().and_then(|s| s.to_str()) {
+            if peer != device_id {
+                peers.push((peer.to_string(), path.clone()));
+            }
+        }
+    }
+    peers.sort();
+
+    for (peer, path) in peers {
+        let last_seq: i64 = conn
+            .query_row(
+                "SELECT last_seq FROM sync_peers WHERE device_id = ?1",
+                params![peer],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+
+        let mut changes = read_change_file(&path, &peer, last_seq)?;
+        let Some(max_seq) = changes.iter().map(|change| change.device_seq).max() else {
+            continue;
+        };
+        changes.sort_by_key(|change| (table_rank(&change.table), change.device_seq));
+        apply_changes(conn, &changes, report)?;
+
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO sync_peers (device_id, last_seq, last_synced_at)
+                 VALUES (?1, ?2, {NOW_EXPRESSION})"
+            ),
+            params![peer, max_seq],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Lit les changements d'un appareil postérieurs à `after_seq`
+///
+/// Une dernière ligne sans fin de ligne est en cours d'écriture (ou de
+/// transfert) : elle sera lue à la prochaine synchronisation. Les lignes
+/// illisibles sont ignorées.
+fn read_change_file(path: &Path, peer: &str, after_seq: i64) -> Result<Vec<Change>, SyncError> {
+    let content = std::fs::read_to_string(path)?;
+    let complete = match content.rfind('\n') {
+        Some(end) => &content[..=end],
+        None => "",
+    };
+
+    let mut changes = Vec::new();
+    for line in complete.lines() {
+        let Ok(change) = serde_json::from_str::<FileChange>(line) else {
+            continue;
+        };
+        if change.seq <= after_seq {
+            continue;
+        }
+        changes.push(Change {
+            device_seq: change.seq,
+            table: change.table,
+            row_uuid: change.row_uuid,
+            column: change.column,
+            value: from_json(&change.value),
+            version: Version {
+                changed_at: change.changed_at,
+                device_id: peer.to_string(),
+            },
+            base: change.base_changed_at.zip(change.base_device_id).map(
+                |(changed_at, device_id)| Version {
+                    changed_at,
+                    device_id,
+                },
+            ),
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Applique les changements d'un appareil (triés, parents avant enfants)
+///
+/// Une référence vers une ligne pas encore reçue est laissée vide, puis
+/// complétée par `resolve_references` une fois la ligne reçue.
+fn apply_changes(
+    conn: &Connection,
+    changes: &[Change],
+    report: &mut SyncReport,
+) -> Result<(), SyncError> {
+    for change in changes {
+        let Some(table) = find_table(&change.table) else {
+            continue;
+        };
+        let known: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sync_changes WHERE device_id = ?1 AND device_seq = ?2)",
+            params![change.version.device_id, change.device_seq],
+            |row| row.get(0),
+        )?;
+        if known {
+            continue;
+        }
+
+        let winner = current_winner(conn, table.name, &change.row_uuid, &change.column)?;
+        insert_change(conn, change)?;
+        report.imported_count += 1;
+
+        if change.column == DELETED {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE uuid = ?1", table.name),
+                params![change.row_uuid],
+            )?;
+            continue;
+        }
+        let Some(column) = table.columns.iter().find(|c| c.name == change.column) else {
+            continue;
+        };
+        if current_winner(conn, table.name, &change.row_uuid, DELETED)?.is_some() {
+            continue;
+        }
+
+        if let Some((version, value)) = winner {
+            let local_wins = version > change.version;
+            if value != change.value && is_concurrent(conn, change, &version)? {
+                let (kept, discarded) = if local_wins {
+                    (
+                        (version, value),
+                        (change.version.clone(), change.value.clone()),
+                    )
+                } else {
+                    (
+                        (change.version.clone(), change.value.clone()),
+                        (version, value),
+                    )
+                };
+                report.conflicts.push(SyncConflict {
+                    table: table.name.to_string(),
+                    row_uuid: change.row_uuid.clone(),
+                    column: column.name.to_string(),
+                    kept_value: to_json(kept.1),
+                    kept_device_id: kept.0.device_id,
+                    kept_changed_at: kept.0.changed_at,
+                    discarded_value: to_json(discarded.1),
+                    discarded_device_id: discarded.0.device_id,
+                    discarded_changed_at: discarded.0.changed_at,
+                });
+            }
+            if local_wins {
+                continue;
+            }
+        }
+
+        if !row_exists(conn, table, &change.row_uuid)?
+            && !create_row(conn, table, &change.row_uuid, changes)?
+        {
+            continue;
+        }
+        apply_value(conn, table, column, &change.row_uuid, &change.value)?;
+    }
+
+    Ok(())
+}
+
+/// Complète les références restées vides faute de connaître la ligne parente
+///
+/// La ligne parente peut venir d'un autre appareil dont le fichier est lu
+/// plus tard, ou lors d'une synchronisation suivante : chaque synchronisation
+/// relit donc la dernière version connue des références vides.
+fn resolve_references(conn: &Connection) -> rusqlite::Result<()> {
+    for table in &SYNC_TABLES {
+        for column in table.columns {
+            let Some(parent) = column.references else {
+                continue;
+            };
+            let winner = format!(
+                "(SELECT c.value FROM sync_changes c
+                  WHERE c.table_name = '{table}' AND c.row_uuid = {table}.uuid
+                    AND c.column_name = '{column}'
+                  ORDER BY c.changed_at DESC, c.device_id DESC
+                  LIMIT 1)",
+                table = table.name,
+                column = column.name
+            );
+            conn.execute(
+                &format!(
+                    "UPDATE {table} SET {column} = (SELECT p.id FROM {parent} p WHERE p.uuid = {winner})
+                     WHERE {column} IS NULL
+                       AND EXISTS (SELECT 1 FROM {parent} p WHERE p.uuid = {winner})",
+                    table = table.name,
+                    column = column.name
+                ),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Nombre de sessions de travail complétées par tâche
+fn completed_session_counts(conn: &Connection) -> rusqlite::Result<HashMap<i64, i64>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT task_id, SUM(pomodoros) FROM {} WHERE task_id IS NOT NULL GROUP BY task_id",
+        completed_work()
+    ))?;
+    let counts = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    counts.collect()
+}
+
+/// Reporte sur `completed_pomodoros` les sessions reçues ou supprimées
+///
+/// Seul l'écart est appliqué : les Pomodoros comptés sans session (import
+/// CSV, par exemple) sont conservés.
+fn update_pomodoro_counters(
+    conn: &Connection,
+    counts_before: &HashMap<i64, i64>,
+) -> rusqlite::Result<()> {
+    let counts_after = completed_session_counts(conn)?;
+    let task_ids: BTreeSet<i64> = counts_before
+        .keys()
+        .chain(counts_after.keys())
+        .copied()
+        .collect();
+
+    for task_id in task_ids {
+        let delta = counts_after.get(&task_id).unwrap_or(&0)
+            - counts_before.get(&task_id).unwrap_or(&0);
+        if delta != 0 {
+            conn.execute(
+                "UPDATE tasks SET completed_pomodoros = MAX(0, COALESCE(completed_pomodoros, 0) + ?1)
+                 WHERE id = ?2",
+                params![delta, task_id],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Indique si deux versions d'un champ ont été produites sans se connaître
+fn is_concurrent(conn: &Connection, change: &Change, winner: &Version) -> rusqlite::Result<bool> {
+    if is_ancestor(conn, change, winner, change.base.clone())? {
+        return Ok(false);
+    }
+    let winner_base = base_of(conn, change, winner)?;
+
+    Ok(!is_ancestor(conn, change, &change.version, winner_base)?)
+}
+
+/// Indique si `ancestor` figure dans l'historique commençant à `start`
+fn is_ancestor(
+    conn: &Connection,
+    field: &Change,
+    ancestor: &Version,
+    start: Option<Version>,
+) -> rusqlite::Result<bool> {
+    let mut current = start;
+    for _ in 0..MAX_HISTORY_DEPTH {
+        match current {
+            Some(version) if version == *ancestor => return Ok(true),
+            // Une version est toujours plus récente que celle qu'elle remplace
+            Some(version) if version > *ancestor => current = base_of(conn, field, &version)?,
+            _ => return Ok(false),
+        }
+    }
+
+    Ok(false)
+}
+
+/// Version remplacée par une version donnée du champ de `field`
+fn base_of(
+    conn: &Connection,
+    field: &Change,
+    version: &Version,
+) -> rusqlite::Result<Option<Version>> {
+    let base: Option<(Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT base_changed_at, base_device_id FROM sync_changes
+             WHERE table_name = ?1 AND row_uuid = ?2 AND column_name = ?3
+               AND changed_at = ?4 AND device_id = ?5",
+            params![
+                field.table,
+                field.row_uuid,
+                field.column,
+                version.changed_at,
+                version.device_id
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    Ok(base.and_then(|(changed_at, device_id)| {
+        Some(Version {
+            changed_at: changed_at?,
+            device_id: device_id?,
+        })
+    }))
+}
+
+/// Version la plus récente connue d'un champ, avec sa valeur
+fn current_winner(
+    conn: &Connection,
+    table: &str,
+    row_uuid: &str,
+    column: &str,
+) -> rusqlite::Result<Option<(Version, Value)>> {
+    conn.query_row(
+        "SELECT changed_at, device_id, value FROM sync_changes
+         WHERE table_name = ?1 AND row_uuid = ?2 AND column_name = ?3
+         ORDER BY changed_at DESC, device_id DESC
+         LIMIT 1",
+        params![table, row_uuid, column],
+        |row| {
+            Ok((
+                Version {
+                    changed_at: row.get(0)?,
+                    device_id: row.get(1)?,
+                },
+                row.get(2)?,
+            ))
+        },
+    )
+    .optional()
+}
+
+fn insert_change(conn: &Connection, change: &Change) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO sync_changes (device_id, device_seq, table_name, row_uuid, column_name,
+                                   value, changed_at, base_changed_at, base_device_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            change.version.device_id,
+            change.device_seq,
+            change.table,
+            change.row_uuid,
+            change.column,
+            change.value,
+            change.version.changed_at,
+            change.base.as_ref().map(|base| &base.changed_at),
+            change.base.as_ref().map(|base| &base.device_id),
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn row_exists(conn: &Connection, table: &SyncTable, row_uuid: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        &format!(
+            "SELECT EXISTS (SELECT 1 FROM {} WHERE uuid = ?1)",
+            table.name
+        ),
+        params![row_uuid],
+        |row| row.get(0),
+    )
+}
+
+/// Crée une ligne reçue avec les valeurs les plus récentes du lot
+///
+/// Retourne `false` si le lot ne permet pas de la créer (par exemple si la
+/// création a été reçue lors d'une synchronisation précédente puis supprimée).
+fn create_row(
+    conn: &Connection,
+    table: &SyncTable,
+    row_uuid: &str,
+    changes: &[Change],
+) -> Result<bool, SyncError> {
+    let mut names = vec!["uuid"];
+    let mut values = vec![Value::Text(row_uuid.to_string())];
+    for column in table.columns {
+        let latest = changes
+            .iter()
+            .filter(|c| c.table == table.name && c.row_uuid == row_uuid && c.column == column.name)
+            .max_by(|a, b| a.version.cmp(&b.version));
+        if let Some(change) = latest {
+            names.push(column.name);
+            // Une référence pas encore reçue est complétée par `resolve_references`
+            values.push(resolve_value(conn, column, &change.value)?.unwrap_or(Value::Null));
+        }
+    }
+
+    let placeholders: Vec<String> = (1..=values.len()).map(|i| format!("?{}", i)).collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table.name,
+        names.join(", "),
+        placeholders.join(", ")
+    );
+    match conn.execute(&sql, params_from_iter(values)) {
+        Ok(_) => Ok(true),
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation => {
+            Ok(false)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Écrit une valeur reçue dans la ligne
+///
+/// Une référence vers une ligne encore inconnue est laissée vide.
+fn apply_value(
+    conn: &Connection,
+    table: &SyncTable,
+    column: &SyncColumn,
+    row_uuid: &str,
+    value: &Value,
+) -> rusqlite::Result<()> {
+    let resolved = resolve_value(conn, column, value)?;
+    conn.execute(
+        &format!(
+            "UPDATE {} SET {} = ?1 WHERE uuid = ?2",
+            table.name, column.name
+        ),
+        params![resolved.unwrap_or(Value::Null), row_uuid],
+    )?;
+
+    Ok(())
+}
+
+/// Convertit l'UUID d'une référence en identifiant local (`None` si inconnu)
+fn resolve_value(
+    conn: &Connection,
+    column: &SyncColumn,
+    value: &Value,
+) -> rusqlite::Result<Option<Value>> {
+    match (column.references, value) {
+        (Some(parent), Value::Text(parent_uuid)) => conn
+            .query_row(
+                &format!("SELECT id FROM {} WHERE uuid = ?1", parent),
+                params![parent_uuid],
+                |row| row.get(0),
+            )
+            .optional(),
+        _ => Ok(Some(value.clone())),
+    }
+}
+
+/// Ajoute les changements locaux pas encore exportés au fichier de l'appareil
+fn export_local_changes(
+    conn: &Connection,
+    device_id: &str,
+    folder: &Path,
+) -> Result<usize, SyncError> {
+    let last_exported: i64 = conn.query_row(
+        "SELECT last_exported_seq FROM sync_state WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT device_seq, table_name, row_uuid, column_name, value, changed_at,
+                base_changed_at, base_device_id
+         FROM sync_changes
+         WHERE device_id = ?1 AND device_seq > ?2
+         ORDER BY device_seq",
+    )?;
+    let changes = stmt.query_map(params![device_id, last_exported], |row| {
+        Ok(FileChange {
+            seq: row.get(0)?,
+            table: row.get(1)?,
+            row_uuid: row.get(2)?,
+            column: row.get(3)?,
+            value: to_json(row.get(4)?),
+            changed_at: row.get(5)?,
+            base_changed_at: row.get(6)?,
+            base_device_id: row.get(7)?,
+        })
+    })?;
+
+    let mut content = String::new();
+    let mut count = 0;
+    let mut last_seq = last_exported;
+    for change in changes {
+        let change = change?;
+        content.push_str(&serde_json::to_string(&change)?);
+        content.push('\n');
+        last_seq = change.seq;
+        count += 1;
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+
+    std::fs::create_dir_all(folder)?;
+    let path = folder.join(format!("{}.jsonl", device_id));
+    // Termine une ligne laissée incomplète par une écriture interrompue
+    if std::fs::read(&path).is_ok_and(|bytes| !bytes.is_empty() && !bytes.ends_with(b"\n")) {
+        content.insert(0, '\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+
+    conn.execute(
+        "UPDATE sync_state SET last_exported_seq = ?1 WHERE id = 1",
+        params![last_seq],
+    )?;
+
+    Ok(count)
+}
+
+fn find_table(name: &str) -> Option<&'static SyncTable> {
+    SYNC_TABLES.iter().find(|table| table.name == name)
+}
+
+fn table_rank(name: &str) -> usize {
+    SYNC_TABLE_NAMES
+        .iter()
+        .position(|table| *table == name)
+        .unwrap_or(SYNC_TABLE_NAMES.len())
+}
+
+/// Horodatage suivant (une milliseconde plus tard)
+fn next_timestamp(timestamp: &str) -> String {
+    match NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_PARSE_FORMAT) {
+        Ok(timestamp) => (timestamp + Duration::milliseconds(1))
+            .format(TIMESTAMP_FORMAT)
+            .to_string(),
+        Err(_) => Utc::now().format(TIMESTAMP_FORMAT).to_string(),
+    }
+}
+
+fn to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(i) => i.into(),
+        Value::Real(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::Text(s) => serde_json::Value::String(s),
+        // Aucune colonne synchronisée n'est binaire
+        Value::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+fn from_json(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_f64().map(Value::Real))
+            .unwrap_or(Value::Null),
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tomatotask-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Simule le transfert par Syncthing du fichier de changements d'un appareil
+    fn copy_changes(conn: &Connection, from: &Path, to: &Path) {
+        let file_name = format!("{}.jsonl", get_sync_status(conn).unwrap().device_id);
+        std::fs::copy(from.join(&file_name), to.join(&file_name)).unwrap();
+    }
+
+    fn task_fields(conn: &Connection) -> (String, Option<i32>) {
+        conn.query_row("SELECT title, priority FROM tasks", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sync_between_two_devices() {
+        let laptop = setup_test_db();
+        let desktop = setup_test_db();
+        let laptop_dir = test_dir("sync-laptop");
+        let desktop_dir = test_dir("sync-desktop");
+
+        laptop
+            .execute_batch(
+                "INSERT INTO projects (name) VALUES ('Thesis');
+                 INSERT INTO tasks (title, project_id, completed_pomodoros)
+                     VALUES ('Write intro', 1, 1);
+                 INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                     VALUES (1, '2025-11-10 09:00:00', '2025-11-10 09:25:00', 25, 'work');",
+            )
+            .unwrap();
+        let exported = sync_folder(&laptop, &laptop_dir).unwrap().exported_count;
+        assert!(exported > 0);
+
+        copy_changes(&laptop, &laptop_dir, &desktop_dir);
+        let report = sync_folder(&desktop, &desktop_dir).unwrap();
+        assert_eq!(report.imported_count, exported);
+        assert_eq!(report.exported_count, 0); // Les changements reçus ne sont pas renvoyés
+        let (project, pomodoros, sessions): (String, i32, i32) = desktop
+            .query_row(
+                "SELECT p.name, t.completed_pomodoros, COUNT(s.id)
+                 FROM tasks t
+                 JOIN projects p ON p.id = t.project_id
+                 JOIN pomodoro_sessions s ON s.task_id = t.id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((project.as_str(), pomodoros, sessions), ("Thesis", 1, 1));
+
+        // Modifications concurrentes du titre ; la priorité n'est modifiée que d'un côté
+        laptop
+            .execute("UPDATE tasks SET title = 'Write introduction'", [])
+            .unwrap();
+        desktop
+            .execute("UPDATE tasks SET title = 'Draft intro', priority = 2", [])
+            .unwrap();
+        sync_folder(&laptop, &laptop_dir).unwrap();
+        copy_changes(&laptop, &laptop_dir, &desktop_dir);
+        let desktop_report = sync_folder(&desktop, &desktop_dir).unwrap();
+        copy_changes(&desktop, &desktop_dir, &laptop_dir);
+        let laptop_report = sync_folder(&laptop, &laptop_dir).unwrap();
+
+        assert_eq!(desktop_report.conflicts.len(), 1);
+        assert_eq!(laptop_report.conflicts.len(), 1);
+        let conflict = &laptop_report.conflicts[0];
+        assert_eq!(
+            (conflict.table.as_str(), conflict.column.as_str()),
+            ("tasks", "title")
+        );
+        assert_eq!(conflict.kept_value, desktop_report.conflicts[0].kept_value);
+        let (title, priority) = task_fields(&laptop);
+        assert_eq!(task_fields(&desktop), (title.clone(), priority));
+        assert_eq!(serde_json::Value::String(title), conflict.kept_value);
+        assert_eq!(priority, Some(2));
+
+        // Une suppression se propage
+        desktop
+            .execute("DELETE FROM pomodoro_sessions", [])
+            .unwrap();
+        sync_folder(&desktop, &desktop_dir).unwrap();
+        copy_changes(&desktop, &desktop_dir, &laptop_dir);
+        let report = sync_folder(&laptop, &laptop_dir).unwrap();
+        assert!(report.conflicts.is_empty());
+        let sessions: i64 = laptop
+            .query_row("SELECT COUNT(*) FROM pomodoro_sessions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(sessions, 0);
+        assert_eq!(get_sync_status(&laptop).unwrap().pending_count, 0);
+
+        std::fs::remove_dir_all(&laptop_dir).unwrap();
+        std::fs::remove_dir_all(&desktop_dir).unwrap();
+    }
+
+    #[test]
+    fn test_pomodoro_counter_follows_synced_sessions() {
+        let laptop = setup_test_db();
+        let desktop = setup_test_db();
+        let dir = test_dir("sync-counter");
+        let complete_pomodoro = |conn: &Connection, started_at: &str| {
+            conn.execute(
+                "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, ?1, ?1, 25, 'work')",
+                params![started_at],
+            )
+            .unwrap();
+            crate::db::queries::increment_completed_pomodoros(conn, 1).unwrap();
+        };
+        let counter = |conn: &Connection| -> i32 {
+            conn.query_row("SELECT completed_pomodoros FROM tasks", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        laptop.execute("INSERT INTO tasks (title) VALUES ('Write intro')", []).unwrap();
+        sync_folder(&laptop, &dir).unwrap();
+        sync_folder(&desktop, &dir).unwrap();
+
+        // Un Pomodoro terminé sur chaque appareil avant de synchroniser
+        complete_pomodoro(&laptop, "2025-11-10 09:00:00");
+        complete_pomodoro(&desktop, "2025-11-10 10:00:00");
+        sync_folder(&laptop, &dir).unwrap();
+        sync_folder(&desktop, &dir).unwrap();
+        sync_folder(&laptop, &dir).unwrap();
+        assert_eq!((counter(&laptop), counter(&desktop)), (2, 2));
+
+        // Une session supprimée sur un appareil n'est plus comptée sur l'autre
+        desktop.execute("DELETE FROM pomodoro_sessions WHERE started_at LIKE '%09:00%'", []).unwrap();
+        sync_folder(&desktop, &dir).unwrap();
+        sync_folder(&laptop, &dir).unwrap();
+        assert_eq!(counter(&laptop), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reference_to_row_from_another_device() {
+        let devices: Vec<(Connection, PathBuf)> = ["aaaa", "bbbb", "cccc"]
+            .iter()
+            .map(|device_id| {
+                let conn = setup_test_db();
+                conn.execute(
+                    "UPDATE sync_state SET device_id = ?1 WHERE id = 1",
+                    params![device_id],
+                )
+                .unwrap();
+                (conn, test_dir(&format!("sync-{}", device_id)))
+            })
+            .collect();
+        let (aaaa, aaaa_dir) = &devices[0];
+        let (bbbb, bbbb_dir) = &devices[1];
+        let (cccc, cccc_dir) = &devices[2];
+
+        // bbbb crée le projet, aaaa y ajoute une tâche
+        bbbb.execute("INSERT INTO projects (name) VALUES ('Thesis')", [])
+            .unwrap();
+        sync_folder(bbbb, bbbb_dir).unwrap();
+        copy_changes(bbbb, bbbb_dir, aaaa_dir);
+        sync_folder(aaaa, aaaa_dir).unwrap();
+        aaaa.execute("INSERT INTO tasks (title, project_id) VALUES ('Write intro', 1)", [])
+            .unwrap();
+        sync_folder(aaaa, aaaa_dir).unwrap();
+
+        // cccc lit le fichier de aaaa avant celui de bbbb
+        copy_changes(aaaa, aaaa_dir, cccc_dir);
+        sync_folder(cccc, cccc_dir).unwrap();
+        let project: Option<String> = cccc
+            .query_row(
+                "SELECT p.name FROM tasks t LEFT JOIN projects p ON p.id = t.project_id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(project, None);
+
+        copy_changes(bbbb, bbbb_dir, cccc_dir);
+        sync_folder(cccc, cccc_dir).unwrap();
+        let project: Option<String> = cccc
+            .query_row(
+                "SELECT p.name FROM tasks t LEFT JOIN projects p ON p.id = t.project_id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(project.as_deref(), Some("Thesis"));
+        assert_eq!(get_sync_status(cccc).unwrap().pending_count, 0);
+
+        for (_, dir) in &devices {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_copied_database_gets_new_device_id() {
+        let laptop = setup_test_db();
+        let dir = test_dir("sync-copy");
+        laptop.execute("INSERT INTO tasks (title) VALUES ('Write intro')", []).unwrap();
+        sync_folder(&laptop, &dir).unwrap();
+        let laptop_id = get_sync_status(&laptop).unwrap().device_id;
+
+        // Copie de la base avant la synchronisation de la tâche
+        let copy = setup_test_db();
+        copy.execute(
+            "UPDATE sync_state SET device_id = ?1 WHERE id = 1",
+            params![laptop_id],
+        )
+        .unwrap();
+        copy.execute("INSERT INTO tasks (title) VALUES ('Read papers')", []).unwrap();
+        let report = sync_folder(&copy, &dir).unwrap();
+        let copy_id = get_sync_status(&copy).unwrap().device_id;
+        assert_ne!(copy_id, laptop_id);
+        assert!(report.imported_count > 0);
+        assert!(report.exported_count > 0);
+
+        sync_folder(&laptop, &dir).unwrap();
+        assert_eq!(get_sync_status(&laptop).unwrap().device_id, laptop_id);
+        let titles: Vec<String> = laptop
+            .prepare("SELECT title FROM tasks ORDER BY title")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(titles, ["Read papers", "Write intro"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_change_file() {
+        let conn = setup_test_db();
+        let dir = test_dir("sync-remove");
+        conn.execute("INSERT INTO tasks (title) VALUES ('Draft')", [])
+            .unwrap();
+        set_sync_folder(&conn, dir.to_str()).unwrap();
+        let exported = sync_now(&conn).unwrap().exported_count;
+        let file = dir.join(format!("{}.jsonl", get_sync_status(&conn).unwrap().device_id));
+        let peer = dir.join("peer.jsonl");
+        std::fs::write(&peer, "").unwrap();
+        assert!(file.exists());
+
+        assert!(remove_change_file(&conn).unwrap());
+        assert!(!file.exists());
+        assert!(peer.exists());
+        assert_eq!(get_sync_status(&conn).unwrap().folder, None);
+        assert!(!remove_change_file(&conn).unwrap());
+
+        // Réactivée, la synchronisation réécrit tout l'historique local
+        set_sync_folder(&conn, dir.to_str()).unwrap();
+        assert_eq!(sync_now(&conn).unwrap().exported_count, exported);
+        assert!(file.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,431 @@
+// Espaces de travail : une base SQLite distincte par espace
+//
+// La liste des espaces et l'espace actif sont enregistrés dans
+// `workspaces.json`, à côté des bases. L'espace par défaut utilise le
+// fichier historique `tomatotask.db`.
+use crate::db::backup::DefaultBackupDir;
+use crate::db::migrations::run_migrations;
+use crate::db::models::{Workspace, WorkspaceRegistry};
+use crate::db::DbConnection;
+use rusqlite::Connection;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Fichier listant les espaces de travail
+const REGISTRY_FILE: &str = "workspaces.json";
+
+/// Identifiant de l'espace par défaut
+const DEFAULT_WORKSPACE_ID: &str = "default";
+
+/// Base de l'espace par défaut
+const DEFAULT_DATABASE_FILE: &str = "tomatotask.db";
+
+/// Erreur de gestion des espaces de travail
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid workspace registry: {0}")]
+    Registry(#[from] serde_json::Error),
+    /// Nom invalide, espace inconnu ou opération interdite
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Gestionnaire des espaces de travail, partagé comme état de l'application
+pub struct WorkspaceManager {
+    app_dir: PathBuf,
+    registry: Mutex<WorkspaceRegistry>,
+}
+
+impl WorkspaceManager {
+    /// Charge la liste des espaces, ou la crée avec l'espace par défaut
+    ///
+    /// Une liste vide est réparée en y remettant l'espace par défaut, et un
+    /// espace actif inconnu est remplacé par le premier espace. Un espace dont
+    /// la base ou les sauvegardes sortiraient du répertoire de l'application
+    /// (chemin absolu, séparateur ou `..`) est refusé.
+    ///
+    /// # Arguments
+    /// * `app_dir` - Répertoire de données de l'application
+    pub fn load(app_dir: PathBuf) -> Result<Self, WorkspaceError> {
+        let registry_path = app_dir.join(REGISTRY_FILE);
+        let mut registry = if registry_path.is_file() {
+            serde_json::from_str(&std::fs::read_to_string(&registry_path)?)?
+        } else {
+            WorkspaceRegistry {
+                active_id: DEFAULT_WORKSPACE_ID.to_string(),
+                workspaces: Vec::new(),
+            }
+        };
+
+        for workspace in &registry.workspaces {
+            validate_file_name(&workspace.id)?;
+            validate_file_name(&workspace.file_name)?;
+        }
+
+        let mut repaired = !registry_path.is_file();
+        if registry.workspaces.is_empty() {
+            registry.workspaces.push(default_workspace());
+            repaired = true;
+        }
+        if !registry.workspaces.iter().any(|w| w.id == registry.active_id) {
+            registry.active_id = registry.workspaces[0].id.clone();
+            repaired = true;
+        }
+        if repaired {
+            save_registry(&app_dir, &registry)?;
+        }
+
+        Ok(Self {
+            app_dir,
+            registry: Mutex::new(registry),
+        })
+    }
+
+    /// Retourne la liste des espaces et l'espace actif
+    pub fn registry(&self) -> WorkspaceRegistry {
+        self.lock().clone()
+    }
+
+    /// Chemin de la base de l'espace actif
+    pub fn active_database_path(&self) -> Result<PathBuf, WorkspaceError> {
+        let registry = self.lock();
+        Ok(self.app_dir.join(&active_workspace(&registry)?.file_name))
+    }
+
+    /// Répertoire de sauvegarde par défaut de l'espace actif
+    pub fn active_backup_dir(&self) -> Result<PathBuf, WorkspaceError> {
+        let registry = self.lock();
+        Ok(self.backup_dir(&active_workspace(&registry)?.id))
+    }
+
+    /// Crée un espace de travail avec une base vide (non activé)
+    ///
+    /// # Arguments
+    /// * `name` - Nom de l'espace (unique)
+    pub fn create(&self, name: &str) -> Result<Workspace, WorkspaceError> {
+        let mut registry = self.lock();
+        let name = validate_name(&registry, name, None)?;
+
+        let id = self.unique_id(&registry, &name);
+        let workspace = Workspace {
+            file_name: format!("workspace-{}.db", id),
+            id,
+            name,
+            created_at: now(),
+        };
+
+        // Initialise la base pour qu'elle soit utilisable immédiatement
+        let conn = Connection::open(self.app_dir.join(&workspace.file_name))?;
+        run_migrations(&conn)?;
+        drop(conn);
+
+        registry.workspaces.push(workspace.clone());
+        save_registry(&self.app_dir, &registry)?;
+
+        Ok(workspace)
+    }
+
+    /// Renomme un espace de travail (son identifiant et sa base ne changent pas)
+    ///
+    /// # Arguments
+    /// * `id` - Identifiant de l'espace
+    /// * `name` - Nouveau nom (unique)
+    pub fn rename(&self, id: &str, name: &str) -> Result<Workspace, WorkspaceError> {
+        let mut registry = self.lock();
+        let name = validate_name(&registry, name, Some(id))?;
+
+        let workspace = find_workspace_mut(&mut registry, id)?;
+        workspace.name = name;
+        let workspace = workspace.clone();
+        save_registry(&self.app_dir, &registry)?;
+
+        Ok(workspace)
+    }
+
+    /// Active un espace : sa base est migrée puis remplace la base ouverte
+    ///
+    /// # Arguments
+    /// * `id` - Identifiant de l'espace
+    /// * `db` - Connexion partagée à la base de données
+    /// * `default_backup_dir` - Répertoire de sauvegarde par défaut, mis à jour
+    pub fn switch(
+        &self,
+        id: &str,
+        db: &DbConnection,
+        default_backup_dir: &DefaultBackupDir,
+    ) -> Result<Workspace, WorkspaceError> {
+        let mut registry = self.lock();
+        let workspace = find_workspace_mut(&mut registry, id)?.clone();
+
+        db.switch_to(self.app_dir.join(&workspace.file_name))?;
+        default_backup_dir.set(self.backup_dir(&workspace.id));
+
+        registry.active_id = workspace.id.clone();
+        save_registry(&self.app_dir, &registry)?;
+
+        Ok(workspace)
+    }
+
+    /// Supprime un espace inactif et sa base (ses sauvegardes sont conservées)
+    ///
+    /// # Arguments
+    /// * `id` - Identifiant de l'espace
+    pub fn delete(&self, id: &str) -> Result<(), WorkspaceError> {
+        let mut registry = self.lock();
+        if registry.active_id == id {
+            return Err(WorkspaceError::Invalid(
+                "The active workspace cannot be deleted".to_string(),
+            ));
+        }
+        let file_name = find_workspace_mut(&mut registry, id)?.file_name.clone();
+
+        registry.workspaces.retain(|workspace| workspace.id != id);
+        save_registry(&self.app_dir, &registry)?;
+
+        // Supprime aussi les fichiers annexes de SQLite s'ils existent
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let path = self.app_dir.join(format!("{}{}", file_name, suffix));
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WorkspaceRegistry> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn backup_dir(&self, id: &str) -> PathBuf {
+        let backups = self.app_dir.join("backups");
+        if id == DEFAULT_WORKSPACE_ID {
+            backups
+        } else {
+            backups.join(id)
+        }
+    }
+
+    /// Identifiant dérivé du nom, sans collision avec un espace, une base ou
+    /// des sauvegardes existants (y compris ceux d'un espace supprimé)
+    fn unique_id(&self, registry: &WorkspaceRegistry, name: &str) -> String {
+        let mut slug = String::new();
+        for c in name.chars().flat_map(char::to_lowercase) {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = match slug.trim_end_matches('-') {
+            "" => "workspace".to_string(),
+            slug => slug.to_string(),
+        };
+
+        let is_free = |id: &str| {
+            registry.workspaces.iter().all(|workspace| workspace.id != id)
+                && !self.app_dir.join(format!("workspace-{}.db", id)).exists()
+                && !self.backup_dir(id).exists()
+        };
+        let mut id = slug.clone();
+        let mut suffix = 2;
+        while !is_free(&id) {
+            id = format!("{}-{}", slug, suffix);
+            suffix += 1;
+        }
+        id
+    }
+}
+
+fn active_workspace(registry: &WorkspaceRegistry) -> Result<&Workspace, WorkspaceError> {
+    registry
+        .workspaces
+        .iter()
+        .find(|workspace| workspace.id == registry.active_id)
+        .ok_or_else(|| {
+            WorkspaceError::Invalid(format!("Active workspace not found: {}", registry.active_id))
+        })
+}
+
+/// Espace par défaut, sur la base historique
+fn default_workspace() -> Workspace {
+    Workspace {
+        id: DEFAULT_WORKSPACE_ID.to_string(),
+        name: "Default".to_string(),
+        file_name: DEFAULT_DATABASE_FILE.to_string(),
+        created_at: now(),
+    }
+}
+
+fn find_workspace_mut<'a>(
+    registry: &'a mut WorkspaceRegistry,
+    id: &str,
+) -> Result<&'a mut Workspace, WorkspaceError> {
+    registry
+        .workspaces
+        .iter_mut()
+        .find(|workspace| workspace.id == id)
+        .ok_or_else(|| WorkspaceError::Invalid(format!("Workspace not found: {}", id)))
+}
+
+/// Vérifie qu'un nom est non vide et pas déjà utilisé par un autre espace
+fn validate_name(
+    registry: &WorkspaceRegistry,
+    name: &str,
+    excluded_id: Option<&str>,
+) -> Result<String, WorkspaceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(WorkspaceError::Invalid(
+            "Workspace name cannot be empty".to_string(),
+        ));
+    }
+    let taken = registry.workspaces.iter().any(|workspace| {
+        Some(workspace.id.as_str()) != excluded_id && workspace.name.eq_ignore_ascii_case(name)
+    });
+    if taken {
+        return Err(WorkspaceError::Invalid(format!(
+            "A workspace named {} already exists",
+            name
+        )));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Vérifie qu'un nom de fichier lu dans la liste des espaces désigne une
+/// entrée directe du répertoire de l'application
+fn validate_file_name(file_name: &str) -> Result<(), WorkspaceError> {
+    let mut components = Path::new(file_name).components();
+    let is_plain = !file_name.contains(['/', '\\'])
+        && matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none();
+    if !is_plain {
+        return Err(WorkspaceError::Invalid(format!(
+            "Invalid workspace file name: {}",
+            file_name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Enregistre la liste des espaces (fichier temporaire puis renommage)
+fn save_registry(app_dir: &Path, registry: &WorkspaceRegistry) -> Result<(), WorkspaceError> {
+    let path = app_dir.join(REGISTRY_FILE);
+    let partial = path.with_extension("json.partial");
+    std::fs::write(&partial, serde_json::to_string_pretty(registry)?)?;
+    std::fs::rename(&partial, &path)?;
+
+    Ok(())
+}
+
+fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tomatotask-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn count_tasks(db: &DbConnection) -> i64 {
+        let conn = db.get_connection();
+        let conn = conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_workspace_lifecycle() {
+        let dir = test_dir("workspaces");
+        let manager = WorkspaceManager::load(dir.clone()).unwrap();
+        assert_eq!(manager.active_database_path().unwrap(), dir.join("tomatotask.db"));
+
+        let personal = manager.create("  Personal projects ").unwrap();
+        assert_eq!(personal.id, "personal-projects");
+        assert_eq!(personal.name, "Personal projects");
+        assert!(manager.create("personal PROJECTS").is_err());
+        assert!(manager.create("").is_err());
+        let renamed = manager.rename(&personal.id, "Personal").unwrap();
+        assert_eq!(renamed.id, personal.id);
+
+        let db = DbConnection::new(manager.active_database_path().unwrap()).unwrap();
+        run_migrations(&db.get_connection().lock().unwrap()).unwrap();
+        db.get_connection()
+            .lock()
+            .unwrap()
+            .execute("INSERT INTO tasks (title) VALUES ('Work task')", [])
+            .unwrap();
+
+        let backup_dir = DefaultBackupDir::new(manager.active_backup_dir().unwrap());
+        manager.switch(&personal.id, &db, &backup_dir).unwrap();
+        assert_eq!(count_tasks(&db), 0);
+        assert_eq!(backup_dir.path(), dir.join("backups").join("personal-projects"));
+        assert!(manager.delete(&personal.id).is_err());
+
+        // L'espace actif est conservé d'un lancement à l'autre
+        let reloaded = WorkspaceManager::load(dir.clone()).unwrap();
+        assert_eq!(reloaded.registry(), manager.registry());
+        assert_eq!(reloaded.registry().active_id, "personal-projects");
+
+        manager.switch("default", &db, &backup_dir).unwrap();
+        assert_eq!(count_tasks(&db), 1);
+        manager.delete(&personal.id).unwrap();
+        assert!(!dir.join(&personal.file_name).exists());
+        assert_eq!(manager.registry().workspaces.len(), 1);
+        assert!(manager.switch(&personal.id, &db, &backup_dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_registry_is_repaired() {
+        let dir = test_dir("workspaces-empty");
+        std::fs::write(dir.join(REGISTRY_FILE), r#"{"activeId":"gone","workspaces":[]}"#)
+            .unwrap();
+
+        let manager = WorkspaceManager::load(dir.clone()).unwrap();
+        assert_eq!(manager.registry().active_id, "default");
+        assert_eq!(manager.active_database_path().unwrap(), dir.join("tomatotask.db"));
+        let saved = std::fs::read_to_string(dir.join(REGISTRY_FILE)).unwrap();
+        assert!(saved.contains("tomatotask.db"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_registry_paths_outside_app_dir_are_rejected() {
+        let dir = test_dir("workspaces-paths");
+        for file_name in ["../outside.db", "/tmp/outside.db", "sub\\outside.db", ".."] {
+            let registry = format!(
+                r#"{{"activeId":"default","workspaces":[{{"id":"default","name":"Default","fileName":{},"createdAt":"2024-01-01 00:00:00"}}]}}"#,
+                serde_json::to_string(file_name).unwrap()
+            );
+            std::fs::write(dir.join(REGISTRY_FILE), registry).unwrap();
+            assert!(matches!(
+                WorkspaceManager::load(dir.clone()),
+                Err(WorkspaceError::Invalid(_))
+            ));
+        }
+
+        std::fs::write(
+            dir.join(REGISTRY_FILE),
+            r#"{"activeId":"x","workspaces":[{"id":"../x","name":"X","fileName":"x.db","createdAt":"2024-01-01 00:00:00"}]}"#,
+        )
+        .unwrap();
+        assert!(WorkspaceManager::load(dir.clone()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,310 @@
+// Export CSV des sessions et des tâches
+//
+// Les lignes sont écrites au fil de la lecture de la requête : l'historique
+// complet n'est jamais chargé en mémoire.
+use super::FormatError;
+use crate::db::queries::{local_day, parse_input_date};
+use crate::db::CsvExportOptions;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection};
+use std::io::Write;
+
+/// Colonne exportable : nom dans l'en-tête et expression SQL
+struct CsvColumn {
+    name: &'static str,
+    expression: String,
+}
+
+/// Colonnes disponibles pour l'export des sessions
+fn session_columns() -> Vec<CsvColumn> {
+    let column = |name, expression: &str| CsvColumn {
+        name,
+        expression: expression.to_string(),
+    };
+    vec![
+        column("id", "s.id"),
+        column("started_at", "s.started_at"),
+        column("completed_at", "s.completed_at"),
+        column("local_date", &local_day("s.started_at", "s.utc_offset_minutes")),
+        column("session_type", "s.session_type"),
+        column("duration_minutes", "s.duration_minutes"),
+        column("duration_hours", "s.duration_minutes / 60.0"),
+        column("interrupted", "s.interrupted"),
+        column("focus_rating", "s.focus_rating"),
+        column("utc_offset_minutes", "s.utc_offset_minutes"),
+        column("task_id", "s.task_id"),
+        column("task_title", "t.title"),
+        column("project_id", "t.project_id"),
+        column("project_name", "p.name"),
+    ]
+}
+
+/// Colonnes disponibles pour l'export des tâches
+fn task_columns() -> Vec<CsvColumn> {
+    let column = |name, expression: &str| CsvColumn {
+        name,
+        expression: expression.to_string(),
+    };
+    vec![
+        column("id", "t.id"),
+        column("title", "t.title"),
+        column("description", "t.description"),
+        column("project_id", "t.project_id"),
+        column("project_name", "p.name"),
+        column("estimated_pomodoros", "t.estimated_pomodoros"),
+        column("completed_pomodoros", "t.completed_pomodoros"),
+        column(
+            "progress",
+            "CASE WHEN t.estimated_pomodoros > 0
+                  THEN CAST(t.completed_pomodoros AS REAL) / t.estimated_pomodoros END",
+        ),
+        column("is_completed", "t.is_completed"),
+        column("due_date", "t.due_date"),
+        column("priority", "t.priority"),
+        column("created_at", "t.created_at"),
+        column("completed_at", "t.completed_at"),
+        column(
+            "completed_date",
+            &local_day("t.completed_at", "t.completed_utc_offset_minutes"),
+        ),
+    ]
+}
+
+/// Écrit les sessions d'une plage de jours locaux au format CSV
+///
+/// Les sessions sont jointes au titre de leur tâche et au nom de leur projet.
+/// Retourne le nombre de lignes écrites (hors en-tête).
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `options` - Plage de dates, colonnes et séparateurs
+/// * `writer` - Destination du CSV
+pub fn write_sessions_csv(
+    conn: &Connection,
+    options: &CsvExportOptions,
+    writer: &mut impl Write,
+) -> Result<usize, FormatError> {
+    let session_day = local_day("s.started_at", "s.utc_offset_minutes");
+    write_csv(
+        conn,
+        &session_columns(),
+        &format!(
+            "FROM pomodoro_sessions s
+             LEFT JOIN tasks t ON t.id = s.task_id
+             LEFT JOIN projects p ON p.id = t.project_id
+             WHERE (?1 IS NULL OR {session_day} >= ?1)
+               AND (?2 IS NULL OR {session_day} <= ?2)
+             ORDER BY s.started_at, s.id"
+        ),
+        options,
+        writer,
+    )
+}
+
+/// Écrit les tâches au format CSV
+///
+/// Avec une plage de dates, seules les tâches ouvertes pendant la plage sont
+/// exportées : créées avant sa fin et non complétées avant son début.
+/// Retourne le nombre de lignes écrites (hors en-tête).
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `options` - Plage de dates, colonnes et séparateurs
+/// * `writer` - Destination du CSV
+pub fn write_tasks_csv(
+    conn: &Connection,
+    options: &CsvExportOptions,
+    writer: &mut impl Write,
+) -> Result<usize, FormatError> {
+    let completed_day = local_day("t.completed_at", "t.completed_utc_offset_minutes");
+    write_csv(
+        conn,
+        &task_columns(),
+        &format!(
+            "FROM tasks t
+             LEFT JOIN projects p ON p.id = t.project_id
+             WHERE (?2 IS NULL OR DATE(t.created_at) <= ?2)
+               AND (?1 IS NULL OR t.completed_at IS NULL OR {completed_day} >= ?1)
+             ORDER BY t.id"
+        ),
+        options,
+        writer,
+    )
+}
+
+/// Exécute la requête et écrit l'en-tête puis chaque ligne au fil de l'eau
+///
+/// `from_clause` reçoit la date de début en `?1` et la date de fin en `?2`.
+fn write_csv(
+    conn: &Connection,
+    available: &[CsvColumn],
+    from_clause: &str,
+    options: &CsvExportOptions,
+    writer: &mut impl Write,
+) -> Result<usize, FormatError> {
+    validate_options(options)?;
+    let columns = select_columns(available, options.columns.as_deref())?;
+
+    let expressions: Vec<&str> = columns.iter().map(|c| c.expression.as_str()).collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} {}",
+        expressions.join(", "),
+        from_clause
+    ))?;
+
+    let delimiter = options.delimiter.to_string();
+    let header: Vec<String> = columns
+        .iter()
+        .map(|c| escape_field(c.name, options.delimiter))
+        .collect();
+    writeln!(writer, "{}", header.join(&delimiter))?;
+
+    let mut rows = stmt.query(params![options.start_date, options.end_date])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        let mut fields = Vec::with_capacity(columns.len());
+        for index in 0..columns.len() {
+            let field = match row.get_ref(index)? {
+                ValueRef::Null => String::new(),
+                ValueRef::Integer(value) => value.to_string(),
+                ValueRef::Real(value) => format!("{:.2}", value)
+                    .replace('.', &options.decimal_separator.to_string()),
+                ValueRef::Text(text) | ValueRef::Blob(text) => {
+                    String::from_utf8_lossy(text).into_owned()
+                }
+            };
+            fields.push(escape_field(&field, options.delimiter));
+        }
+        writeln!(writer, "{}", fields.join(&delimiter))?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Vérifie la plage de dates et la cohérence des séparateurs
+fn validate_options(options: &CsvExportOptions) -> Result<(), FormatError> {
+    for date in [&options.start_date, &options.end_date].into_iter().flatten() {
+        parse_input_date(date)?;
+    }
+    if matches!(options.delimiter, '"' | '\n' | '\r') {
+        return Err(FormatError::Invalid(format!(
+            "Invalid CSV delimiter: {:?}",
+            options.delimiter
+        )));
+    }
+    if options.delimiter == options.decimal_separator {
+        return Err(FormatError::Invalid(
+            "CSV delimiter and decimal separator must differ".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Retourne les colonnes demandées dans l'ordre demandé (toutes par défaut)
+fn select_columns<'a>(
+    available: &'a [CsvColumn],
+    requested: Option<&[String]>,
+) -> Result<Vec<&'a CsvColumn>, FormatError> {
+    let Some(requested) = requested else {
+        return Ok(available.iter().collect());
+    };
+    if requested.is_empty() {
+        return Err(FormatError::Invalid("No CSV column selected".to_string()));
+    }
+
+    requested
+        .iter()
+        .map(|name| {
+            available.iter().find(|c| c.name == name).ok_or_else(|| {
+                let names: Vec<&str> = available.iter().map(|c| c.name).collect();
+                FormatError::Invalid(format!(
+                    "Unknown CSV column: {} (expected one of: {})",
+                    name,
+                    names.join(", ")
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Entoure un champ de guillemets s'il contient le séparateur, un guillemet ou un saut de ligne
+fn escape_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (name) VALUES ('Client; \"X\"');
+             INSERT INTO tasks (title, project_id, estimated_pomodoros, completed_pomodoros)
+                 VALUES ('Spec', 1, 4, 1);
+             INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, '2025-11-10 09:00:00', '2025-11-10 09:50:00', 50, 'work');
+             INSERT INTO pomodoro_sessions (started_at, completed_at, duration_minutes, session_type)
+                 VALUES ('2025-11-12 09:00:00', '2025-11-12 09:05:00', 5, 'short_break');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_sessions_csv_with_french_locale() {
+        let conn = setup_test_db();
+        let options = CsvExportOptions {
+            start_date: Some("2025-11-10".to_string()),
+            end_date: Some("2025-11-11".to_string()),
+            columns: Some(vec![
+                "local_date".to_string(),
+                "duration_hours".to_string(),
+                "task_title".to_string(),
+                "project_name".to_string(),
+            ]),
+            delimiter: ';',
+            decimal_separator: ',',
+        };
+
+        let mut output = Vec::new();
+        let count = write_sessions_csv(&conn, &options, &mut output).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "local_date;duration_hours;task_title;project_name\n\
+             2025-11-10;0,83;Spec;\"Client; \"\"X\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_tasks_csv_and_invalid_options() {
+        let conn = setup_test_db();
+        let mut output = Vec::new();
+        let count = write_tasks_csv(&conn, &CsvExportOptions::default(), &mut output).unwrap();
+        assert_eq!(count, 1);
+        let csv = String::from_utf8(output).unwrap();
+        assert!(csv.starts_with("id,title,description,project_id,project_name,"));
+        assert!(csv.contains(",0.25,"));
+
+        let unknown = CsvExportOptions {
+            columns: Some(vec!["password".to_string()]),
+            ..CsvExportOptions::default()
+        };
+        assert!(write_tasks_csv(&conn, &unknown, &mut Vec::new()).is_err());
+        let same_separators = CsvExportOptions {
+            decimal_separator: ',',
+            ..CsvExportOptions::default()
+        };
+        assert!(write_tasks_csv(&conn, &same_separators, &mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,451 @@
+// Export iCalendar (RFC 5545) des sessions et import des tâches VTODO
+use super::FormatError;
+use crate::db::queries::{local_day, parse_input_date, upsert_task_by_external_uid};
+use crate::db::{ExternalTaskInput, TaskImportSummary};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::{params, Connection};
+use std::io::Write;
+
+/// Longueur maximale d'une ligne iCalendar avant repli, en octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Format des horodatages UTC stockés en base
+const DB_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Format des horodatages UTC iCalendar
+const ICS_UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Écrit les sessions de travail complétées sous forme d'événements VEVENT
+///
+/// Le résumé reprend le titre de la tâche et la catégorie le nom du projet.
+/// Chaque événement a un UID stable dérivé de l'ID de la session, ce qui
+/// permet aux agendas de remplacer les événements lors d'un nouvel export.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `start_date` - Jour local de début inclus (format ISO: YYYY-MM-DD)
+/// * `end_date` - Jour local de fin inclus (format ISO: YYYY-MM-DD)
+/// * `writer` - Destination du calendrier
+pub fn write_sessions_ics(
+    conn: &Connection,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    writer: &mut impl Write,
+) -> Result<usize, FormatError> {
+    for date in [start_date, end_date].into_iter().flatten() {
+        parse_input_date(date)?;
+    }
+
+    let session_day = local_day("s.started_at", "s.utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT s.id, s.started_at, s.completed_at, t.title, p.name
+         FROM pomodoro_sessions s
+         LEFT JOIN tasks t ON t.id = s.task_id
+         LEFT JOIN projects p ON p.id = t.project_id
+         WHERE s.session_type = 'work'
+           AND s.completed_at IS NOT NULL
+           AND s.interrupted = 0
+           AND (?1 IS NULL OR {session_day} >= ?1)
+           AND (?2 IS NULL OR {session_day} <= ?2)
+         ORDER BY s.started_at, s.id"
+    ))?;
+
+    let stamp = Utc::now().format(ICS_UTC_FORMAT).to_string();
+    write_line(writer, "BEGIN:VCALENDAR")?;
+    write_line(writer, "VERSION:2.0")?;
+    write_line(writer, "PRODID:-//TomatoTask//Pomodoro sessions//EN")?;
+    write_line(writer, "CALSCALE:GREGORIAN")?;
+
+    let mut rows = stmt.query(params![start_date, end_date])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let started_at: String = row.get(1)?;
+        let completed_at: String = row.get(2)?;
+        let title: Option<String> = row.get(3)?;
+        let project_name: Option<String> = row.get(4)?;
+
+        write_line(writer, "BEGIN:VEVENT")?;
+        write_line(writer, &format!("UID:session-{}@tomatotask", id))?;
+        write_line(writer, &format!("DTSTAMP:{}", stamp))?;
+        write_line(writer, &format!("DTSTART:{}", to_ics_utc(&started_at)?))?;
+        write_line(writer, &format!("DTEND:{}", to_ics_utc(&completed_at)?))?;
+        write_line(
+            writer,
+            &format!(
+                "SUMMARY:{}",
+                escape_text(title.as_deref().unwrap_or("Focus session"))
+            ),
+        )?;
+        if let Some(project_name) = project_name {
+            write_line(writer, &format!("CATEGORIES:{}", escape_text(&project_name)))?;
+        }
+        write_line(writer, "END:VEVENT")?;
+        count += 1;
+    }
+
+    write_line(writer, "END:VCALENDAR")?;
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Importe les VTODO d'un calendrier comme tâches, dans une seule transaction
+///
+/// SUMMARY, DESCRIPTION, DUE et PRIORITY sont repris ; l'UID est conservé
+/// pour qu'un nouvel import mette à jour les tâches au lieu de les dupliquer.
+/// Les VTODO sans UID ou sans résumé sont ignorés, ainsi que ceux dont une
+/// date est invalide (la raison est alors ajoutée au bilan).
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `content` - Contenu du fichier .ics
+/// * `project_id` - Projet des tâches créées (les tâches existantes gardent le leur)
+pub fn import_ics_todos(
+    conn: &Connection,
+    content: &str,
+    project_id: Option<i64>,
+) -> Result<TaskImportSummary, FormatError> {
+    if let Some(project_id) = project_id {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM projects WHERE id = ?1)",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(FormatError::Invalid(format!("Unknown project {}", project_id)));
+        }
+    }
+    let todos = parse_vtodos(content);
+
+    let tx = conn.unchecked_transaction()?;
+    let mut summary = TaskImportSummary::default();
+    for todo in todos {
+        if let Some(error) = todo.error {
+            summary.skipped_count += 1;
+            summary.errors.push(match &todo.uid {
+                Some(uid) => format!("VTODO {}: {}", uid, error),
+                None => error,
+            });
+            continue;
+        }
+        let (Some(external_uid), Some(title)) = (todo.uid, todo.summary) else {
+            summary.skipped_count += 1;
+            continue;
+        };
+
+        let (_, created) = upsert_task_by_external_uid(
+            &tx,
+            &ExternalTaskInput {
+                external_uid,
+                title,
+                description: todo.description,
+                project_id,
+                due_date: todo.due_date,
+                priority: todo.priority,
+                completed_at: todo.completed_at,
+            },
+        )?;
+        if created {
+            summary.created_count += 1;
+        } else {
+            summary.updated_count += 1;
+        }
+    }
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Propriétés utiles d'un VTODO
+#[derive(Debug, Default)]
+struct ParsedTodo {
+    uid: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    due_date: Option<String>,
+    priority: Option<i32>,
+    completed_at: Option<String>,
+    /// Première propriété invalide : le VTODO n'est pas importé
+    error: Option<String>,
+}
+
+/// Extrait les VTODO d'un calendrier (les composants imbriqués comme VALARM sont ignorés)
+fn parse_vtodos(content: &str) -> Vec<ParsedTodo> {
+    let mut todos = Vec::new();
+    let mut current: Option<ParsedTodo> = None;
+    // Profondeur des composants ouverts à l'intérieur du VTODO courant
+    let mut nested_depth = 0;
+
+    for line in unfold_lines(content) {
+        let Some((name, parameters, value)) = split_content_line(&line) else {
+            continue;
+        };
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VTODO") if current.is_none() => current = Some(ParsedTodo::default()),
+            ("BEGIN", _) if current.is_some() => nested_depth += 1,
+            ("END", "VTODO") if nested_depth == 0 => {
+                if let Some(todo) = current.take() {
+                    todos.push(todo);
+                }
+            }
+            ("END", _) if nested_depth > 0 => nested_depth -= 1,
+            _ => {
+                let Some(todo) = current.as_mut().filter(|_| nested_depth == 0) else {
+                    continue;
+                };
+                match name.as_str() {
+                    "UID" => todo.uid = Some(value.to_string()),
+                    "SUMMARY" => todo.summary = Some(unescape_text(value)),
+                    "DESCRIPTION" => todo.description = Some(unescape_text(value)),
+                    "DUE" => match parse_ics_date(value, &parameters) {
+                        Ok(date) => todo.due_date = Some(date),
+                        Err(e) => {
+                            todo.error.get_or_insert(e.to_string());
+                        }
+                    },
+                    "PRIORITY" => {
+                        todo.priority = value.parse().ok().filter(|p| (1..=9).contains(p))
+                    }
+                    "COMPLETED" => match parse_ics_utc(value) {
+                        Ok(completed_at) => todo.completed_at = Some(completed_at),
+                        Err(e) => {
+                            todo.error.get_or_insert(e.to_string());
+                        }
+                    },
+                    "STATUS" if value == "COMPLETED" && todo.completed_at.is_none() => {
+                        todo.completed_at = Some(Utc::now().format(DB_DATETIME_FORMAT).to_string())
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    todos
+}
+
+/// Recolle les lignes repliées (une ligne commençant par un espace prolonge la précédente)
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Découpe une ligne `NOM;PARAM=VALEUR:valeur` en nom (majuscules), paramètres et valeur
+fn split_content_line(line: &str) -> Option<(String, String, &str)> {
+    // Le premier ':' hors guillemets sépare le nom et ses paramètres de la valeur
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, parameters) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_ascii_uppercase(), parameters.to_ascii_uppercase(), value))
+}
+
+/// Convertit une échéance DATE ou DATE-TIME en jour local (YYYY-MM-DD)
+///
+/// Les horaires UTC (suffixe Z) sont convertis dans le fuseau de la machine ;
+/// les horaires flottants ou avec TZID gardent leur date.
+fn parse_ics_date(value: &str, parameters: &str) -> Result<String, FormatError> {
+    let invalid = || FormatError::Invalid(format!("Invalid iCalendar date: {}", value));
+
+    if value.ends_with('Z') {
+        let utc = NaiveDateTime::parse_from_str(value, ICS_UTC_FORMAT).map_err(|_| invalid())?;
+        let local = Local.from_utc_datetime(&utc);
+        return Ok(local.format("%Y-%m-%d").to_string());
+    }
+    if !parameters.contains("VALUE=DATE") && value.len() != 8 {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+    }
+
+    let date = value.get(..8).ok_or_else(invalid)?;
+    let date = NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| invalid())?;
+    Ok(date.format("%Y-%m-%d").to_string())
+}
+
+/// Convertit un horodatage iCalendar UTC en horodatage de la base
+fn parse_ics_utc(value: &str) -> Result<String, FormatError> {
+    let utc = NaiveDateTime::parse_from_str(value, ICS_UTC_FORMAT)
+        .map_err(|_| FormatError::Invalid(format!("Invalid iCalendar timestamp: {}", value)))?;
+    Ok(utc.format(DB_DATETIME_FORMAT).to_string())
+}
+
+/// Convertit un horodatage UTC de la base au format iCalendar
+fn to_ics_utc(value: &str) -> Result<String, FormatError> {
+    let utc = NaiveDateTime::parse_from_str(value, DB_DATETIME_FORMAT)
+        .map_err(|_| FormatError::Invalid(format!("Invalid timestamp in database: {}", value)))?;
+    Ok(utc.format(ICS_UTC_FORMAT).to_string())
+}
+
+/// Échappe une valeur TEXT iCalendar
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Décode une valeur TEXT iCalendar
+fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Écrit une ligne terminée par CRLF, repliée tous les 75 octets
+fn write_line(writer: &mut impl Write, line: &str) -> std::io::Result<()> {
+    let mut segment_start = 0;
+    let mut limit = MAX_LINE_OCTETS;
+    for (index, c) in line.char_indices() {
+        if index + c.len_utf8() - segment_start > limit {
+            writer.write_all(&line.as_bytes()[segment_start..index])?;
+            writer.write_all(b"\r\n ")?;
+            segment_start = index;
+            // L'espace de continuation compte dans la longueur de la ligne
+            limit = MAX_LINE_OCTETS - 1;
+        }
+    }
+    writer.write_all(&line.as_bytes()[segment_start..])?;
+    writer.write_all(b"\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_export_sessions_as_events() {
+        let conn = setup_test_db();
+        let long_title = format!("Write, review; ship {}", "é".repeat(60));
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (title, project_id) VALUES (?1, 1)",
+            [&long_title],
+        )
+        .unwrap();
+        conn.execute_batch(
+            "INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type)
+                 VALUES (1, '2025-11-10 09:00:00', '2025-11-10 09:25:00', 25, 'work');
+             INSERT INTO pomodoro_sessions (task_id, started_at, completed_at, duration_minutes, session_type, interrupted)
+                 VALUES (1, '2025-11-10 10:00:00', '2025-11-10 10:05:00', 25, 'work', 1);",
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        let count = write_sessions_ics(&conn, None, None, &mut output).unwrap();
+        assert_eq!(count, 1);
+
+        let ics = String::from_utf8(output).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:session-1@tomatotask\r\n"));
+        assert!(ics.contains("DTSTART:20251110T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20251110T092500Z\r\n"));
+        assert!(ics.contains("CATEGORIES:Client X\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_OCTETS));
+
+        let unfolded = unfold_lines(&ics);
+        let summary = unfolded.iter().find(|l| l.starts_with("SUMMARY:")).unwrap();
+        assert_eq!(unescape_text(&summary["SUMMARY:".len()..]), long_title);
+    }
+
+    #[test]
+    fn test_import_todos_keeps_uids() {
+        let conn = setup_test_db();
+        let calendar = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VTODO\r\n\
+            UID:todo-1@example.com\r\n\
+            SUMMARY:Prepare slides\\, v2\r\n\
+            DESCRIPTION:Line one\\nLine\r\n  two\r\n\
+            DUE;VALUE=DATE:20251120\r\n\
+            PRIORITY:1\r\n\
+            BEGIN:VALARM\r\n\
+            DESCRIPTION:Reminder\r\n\
+            END:VALARM\r\n\
+            END:VTODO\r\n\
+            BEGIN:VTODO\r\n\
+            SUMMARY:No UID\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n";
+
+        let first = import_ics_todos(&conn, calendar, None).unwrap();
+        assert_eq!(first.created_count, 1);
+        assert_eq!(first.skipped_count, 1);
+
+        let updated = calendar.replace("PRIORITY:1", "PRIORITY:5");
+        let second = import_ics_todos(&conn, &updated, None).unwrap();
+        assert_eq!(second.created_count, 0);
+        assert_eq!(second.updated_count, 1);
+
+        let (title, description, due_date, priority): (String, String, String, i32) = conn
+            .query_row(
+                "SELECT title, description, due_date, priority FROM tasks",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(title, "Prepare slides, v2");
+        assert_eq!(description, "Line one\nLine two");
+        assert_eq!(due_date, "2025-11-20");
+        assert_eq!(priority, 5);
+    }
+
+    #[test]
+    fn test_import_skips_invalid_todos() {
+        let conn = setup_test_db();
+        let calendar = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VTODO\r\n\
+            UID:bad@example.com\r\n\
+            SUMMARY:Bad due date\r\n\
+            DUE:2025-11-20\r\n\
+            END:VTODO\r\n\
+            BEGIN:VTODO\r\n\
+            UID:good@example.com\r\n\
+            SUMMARY:Good\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n";
+
+        conn.execute("INSERT INTO projects (name) VALUES ('Inbox')", []).unwrap();
+        let summary = import_ics_todos(&conn, calendar, Some(1)).unwrap();
+        assert_eq!(summary.created_count, 1);
+        assert_eq!(summary.skipped_count, 1);
+        assert_eq!(summary.errors.len(), 1);
+        assert!(summary.errors[0].starts_with("VTODO bad@example.com:"));
+
+        let error = import_ics_todos(&conn, calendar, Some(99)).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown project 99");
+    }
+}
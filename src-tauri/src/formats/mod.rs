@@ -0,0 +1,16 @@
+// Formats d'échange de données avec d'autres outils (tableurs, agendas...)
+pub mod csv;
+pub mod ical;
+pub mod todotxt;
+
+/// Erreur d'export ou d'import dans un format externe
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Options ou contenu invalides
+    #[error("{0}")]
+    Invalid(String),
+}
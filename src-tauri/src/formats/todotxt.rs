@@ -0,0 +1,439 @@
+// Import et export des tâches au format todo.txt
+//
+// Une ligne par tâche : `x` (complétée), priorité `(A)`, dates, titre,
+// `+projet`, `@contexte` et attributs `clé:valeur` (`due:`, `est:`...).
+// Les contextes et attributs sans équivalent sont conservés dans
+// `tasks.extra_tags` pour être réexportés tels quels.
+use super::FormatError;
+use crate::db::queries::{create_project, current_utc_offset_minutes, local_day};
+use crate::db::{CreateProjectInput, TaskImportSummary};
+use chrono::{Local, NaiveDate, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::io::Write;
+
+/// Format des dates todo.txt
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Tâche todo.txt décomposée
+#[derive(Debug, Default, PartialEq)]
+struct TodoLine {
+    completed: bool,
+    completion_date: Option<NaiveDate>,
+    creation_date: Option<NaiveDate>,
+    /// Priorité 1 à 9 (A = 1 ; les lettres après I sont ramenées à 9)
+    priority: Option<i32>,
+    title: String,
+    /// Premier `+projet` de la ligne
+    project: Option<String>,
+    due_date: Option<NaiveDate>,
+    estimated_pomodoros: Option<i32>,
+    /// Contextes, projets supplémentaires et attributs inconnus
+    extra_tags: Vec<String>,
+}
+
+/// Écrit les tâches au format todo.txt, les tâches ouvertes en premier
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `include_completed` - Inclut les tâches complétées (lignes `x`)
+/// * `writer` - Destination du fichier
+pub fn write_todotxt(
+    conn: &Connection,
+    include_completed: bool,
+    writer: &mut impl Write,
+) -> Result<usize, FormatError> {
+    let completed_day = local_day("t.completed_at", "t.completed_utc_offset_minutes");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.title, p.name, t.is_completed, {completed_day}, DATE(t.created_at),
+                t.priority, t.due_date, t.estimated_pomodoros, t.extra_tags
+         FROM tasks t
+         LEFT JOIN projects p ON p.id = t.project_id
+         WHERE ?1 OR t.is_completed = 0
+         ORDER BY t.is_completed, COALESCE(t.priority, 10), t.id"
+    ))?;
+
+    let mut rows = stmt.query([include_completed])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        let date = |value: Option<String>| {
+            value.and_then(|value| NaiveDate::parse_from_str(&value, DATE_FORMAT).ok())
+        };
+        let todo = TodoLine {
+            title: row.get(0)?,
+            project: row.get(1)?,
+            completed: row.get::<_, i32>(2)? != 0,
+            completion_date: date(row.get(3)?),
+            creation_date: date(row.get(4)?),
+            priority: row.get(5)?,
+            due_date: date(row.get(6)?),
+            estimated_pomodoros: row.get(7)?,
+            extra_tags: row
+                .get::<_, String>(8)?
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+        };
+        writeln!(writer, "{}", format_line(&todo))?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Importe un fichier todo.txt, dans une seule transaction
+///
+/// Chaque ligne est rapprochée d'une tâche existante de même titre et de même
+/// projet (les projets inconnus sont créés), ce qui permet de réimporter le
+/// fichier sans doublon. Une tâche existante n'est jamais rouverte.
+///
+/// # Arguments
+/// * `conn` - Connexion à la base de données
+/// * `content` - Contenu du fichier todo.txt
+pub fn import_todotxt(conn: &Connection, content: &str) -> Result<TaskImportSummary, FormatError> {
+    let tx = conn.unchecked_transaction()?;
+    let mut summary = TaskImportSummary::default();
+
+    for line in content.lines() {
+        let Some(todo) = parse_line(line) else {
+            continue;
+        };
+        if todo.title.is_empty() {
+            summary.skipped_count += 1;
+            continue;
+        }
+
+        let project_id = match &todo.project {
+            Some(name) => Some(find_or_create_project(&tx, name)?),
+            None => None,
+        };
+        let (completed_at, completed_offset) = match todo.completion_date {
+            Some(date) => {
+                let (timestamp, offset) = local_noon_utc(date);
+                (Some(timestamp), Some(offset))
+            }
+            None => (None, None),
+        };
+        let due_date = todo.due_date.map(|date| date.format(DATE_FORMAT).to_string());
+        let extra_tags = todo.extra_tags.join(" ");
+
+        let existing_id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM tasks
+                 WHERE title = ?1 AND project_id IS ?2
+                 ORDER BY is_completed, id
+                 LIMIT 1",
+                params![todo.title, project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match existing_id {
+            Some(task_id) => {
+                tx.execute(
+                    "UPDATE tasks
+                     SET priority = ?1,
+                         due_date = ?2,
+                         estimated_pomodoros = COALESCE(?3, estimated_pomodoros),
+                         extra_tags = ?4,
+                         completed_at = CASE WHEN ?5 AND is_completed = 0
+                                             THEN COALESCE(?6, datetime('now')) ELSE completed_at END,
+                         completed_utc_offset_minutes = CASE WHEN ?5 AND is_completed = 0
+                                             THEN COALESCE(?7, ?8) ELSE completed_utc_offset_minutes END,
+                         is_completed = CASE WHEN ?5 THEN 1 ELSE is_completed END,
+                         updated_at = datetime('now')
+                     WHERE id = ?9",
+                    params![
+                        todo.priority,
+                        due_date,
+                        todo.estimated_pomodoros,
+                        extra_tags,
+                        todo.completed,
+                        completed_at,
+                        completed_offset,
+                        current_utc_offset_minutes(),
+                        task_id,
+                    ],
+                )?;
+                summary.updated_count += 1;
+            }
+            None => {
+                let created_at = todo.creation_date.map(|date| local_noon_utc(date).0);
+                tx.execute(
+                    "INSERT INTO tasks (title, project_id, estimated_pomodoros, priority, due_date,
+                                        extra_tags, is_completed, completed_at,
+                                        completed_utc_offset_minutes, created_at)
+                     VALUES (?1, ?2, COALESCE(?3, 0), ?4, ?5, ?6, ?7,
+                             CASE WHEN ?7 THEN COALESCE(?8, datetime('now')) END,
+                             CASE WHEN ?7 THEN COALESCE(?9, ?10) END,
+                             COALESCE(?11, datetime('now')))",
+                    params![
+                        todo.title,
+                        project_id,
+                        todo.estimated_pomodoros,
+                        todo.priority,
+                        due_date,
+                        extra_tags,
+                        todo.completed,
+                        completed_at,
+                        completed_offset,
+                        current_utc_offset_minutes(),
+                        created_at,
+                    ],
+                )?;
+                summary.created_count += 1;
+            }
+        }
+    }
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Retrouve un projet par son nom todo.txt ou le crée
+///
+/// Les espaces d'un nom de projet sont remplacés par `_` à l'export.
+fn find_or_create_project(conn: &Connection, name: &str) -> rusqlite::Result<i64> {
+    let existing = conn
+        .query_row(
+            "SELECT id FROM projects
+             WHERE name = ?1 OR REPLACE(name, ' ', '_') = ?1
+             ORDER BY name = ?1 DESC, id
+             LIMIT 1",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match existing {
+        Some(project_id) => Ok(project_id),
+        None => {
+            let input = CreateProjectInput {
+                name: name.to_string(),
+                color: None,
+                parent_project_id: None,
+            };
+            Ok(create_project(conn, &input)?.id)
+        }
+    }
+}
+
+/// Horodatage UTC (et décalage local) de midi local d'un jour
+///
+/// Midi évite qu'un jour importé bascule sur la veille avec l'heure de début
+/// de journée.
+fn local_noon_utc(date: NaiveDate) -> (String, i32) {
+    let noon = date.and_hms_opt(12, 0, 0).unwrap_or_default();
+    match Local.from_local_datetime(&noon).earliest() {
+        Some(local) => (
+            local.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string(),
+            local.offset().local_minus_utc() / 60,
+        ),
+        None => (noon.format("%Y-%m-%d %H:%M:%S").to_string(), 0),
+    }
+}
+
+/// Décompose une ligne todo.txt (None pour une ligne vide)
+fn parse_line(line: &str) -> Option<TodoLine> {
+    let mut tokens = line.split_whitespace().peekable();
+    tokens.peek()?;
+
+    let mut todo = TodoLine::default();
+    if tokens.next_if_eq(&"x").is_some() {
+        todo.completed = true;
+        todo.completion_date = tokens.next_if(|t| parse_date(t).is_some()).and_then(parse_date);
+    } else {
+        todo.priority = tokens.next_if(|t| parse_priority(t).is_some()).and_then(parse_priority);
+    }
+    todo.creation_date = tokens.next_if(|t| parse_date(t).is_some()).and_then(parse_date);
+
+    let mut words = Vec::new();
+    for token in tokens {
+        if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            if todo.project.is_none() {
+                todo.project = Some(project.to_string());
+                continue;
+            }
+            todo.extra_tags.push(token.to_string());
+        } else if token.len() > 1 && token.starts_with('@') {
+            todo.extra_tags.push(token.to_string());
+        } else if let Some((key, value)) = split_attribute(token) {
+            match key {
+                "due" if parse_date(value).is_some() => todo.due_date = parse_date(value),
+                "est" if value.parse::<u16>().is_ok() => todo.estimated_pomodoros = value.parse().ok(),
+                "pri" if priority_from_letter(value).is_some() => {
+                    todo.priority = priority_from_letter(value)
+                }
+                _ => todo.extra_tags.push(token.to_string()),
+            }
+        } else {
+            words.push(token);
+        }
+    }
+    todo.title = words.join(" ");
+
+    Some(todo)
+}
+
+/// Formate une tâche en ligne todo.txt
+///
+/// Les tâches complétées gardent leur priorité dans un attribut `pri:`.
+fn format_line(todo: &TodoLine) -> String {
+    let mut parts = Vec::new();
+    let letter = todo.priority.and_then(priority_letter);
+
+    if todo.completed {
+        parts.push("x".to_string());
+        if let Some(date) = todo.completion_date {
+            parts.push(date.format(DATE_FORMAT).to_string());
+        }
+    } else if let Some(letter) = letter {
+        parts.push(format!("({})", letter));
+    }
+    if let Some(date) = todo.creation_date {
+        // Une date de création seule serait lue comme date de complétion
+        if !todo.completed || todo.completion_date.is_some() {
+            parts.push(date.format(DATE_FORMAT).to_string());
+        }
+    }
+
+    parts.push(todo.title.split_whitespace().collect::<Vec<_>>().join(" "));
+    if let Some(project) = &todo.project {
+        parts.push(format!("+{}", project.split_whitespace().collect::<Vec<_>>().join("_")));
+    }
+    parts.extend(todo.extra_tags.iter().cloned());
+    if let Some(date) = todo.due_date {
+        parts.push(format!("due:{}", date.format(DATE_FORMAT)));
+    }
+    if let Some(estimate) = todo.estimated_pomodoros.filter(|e| *e > 0) {
+        parts.push(format!("est:{}", estimate));
+    }
+    if let (true, Some(letter)) = (todo.completed, letter) {
+        parts.push(format!("pri:{}", letter));
+    }
+
+    parts.join(" ")
+}
+
+/// Découpe un attribut `clé:valeur`
+///
+/// La clé doit être un identifiant commençant par une lettre : les heures
+/// (`10:30`) et les URL restent dans le titre.
+fn split_attribute(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once(':')?;
+    let valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let valid = valid_key && !value.is_empty() && !value.contains(':');
+    (valid && !value.starts_with("//")).then_some((key, value))
+}
+
+fn parse_date(token: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(token, DATE_FORMAT).ok()
+}
+
+/// Lit une priorité `(A)`
+fn parse_priority(token: &str) -> Option<i32> {
+    priority_from_letter(token.strip_prefix('(')?.strip_suffix(')')?)
+}
+
+fn priority_from_letter(letter: &str) -> Option<i32> {
+    match letter.as_bytes() {
+        [c @ b'A'..=b'Z'] => Some(i32::from(c - b'A' + 1).min(9)),
+        _ => None,
+    }
+}
+
+fn priority_letter(priority: i32) -> Option<char> {
+    (1..=9)
+        .contains(&priority)
+        .then(|| char::from(b'A' + priority as u8 - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_and_format_line() {
+        let line = "(B) 2025-11-01 Call back the client +Client_X @phone due:2025-11-20 est:3 \
+                    see https://example.com";
+        let todo = parse_line(line).unwrap();
+        assert_eq!(todo.priority, Some(2));
+        assert_eq!(todo.creation_date, NaiveDate::from_ymd_opt(2025, 11, 1));
+        assert_eq!(todo.title, "Call back the client see https://example.com");
+        assert_eq!(todo.project.as_deref(), Some("Client_X"));
+        assert_eq!(todo.due_date, NaiveDate::from_ymd_opt(2025, 11, 20));
+        assert_eq!(todo.estimated_pomodoros, Some(3));
+        assert_eq!(todo.extra_tags, vec!["@phone"]);
+        assert_eq!(parse_line(&format_line(&todo)), Some(todo));
+
+        let done = parse_line("x 2025-11-13 2025-11-01 Ship it pri:A @work").unwrap();
+        assert!(done.completed);
+        assert_eq!(done.priority, Some(1));
+        assert_eq!(
+            format_line(&done),
+            "x 2025-11-13 2025-11-01 Ship it @work pri:A"
+        );
+        assert_eq!(parse_line("   "), None);
+
+        let timed = parse_line("Standup at 10:30 with 2:1 ratio rec:1w").unwrap();
+        assert_eq!(timed.title, "Standup at 10:30 with 2:1 ratio");
+        assert_eq!(timed.extra_tags, vec!["rec:1w"]);
+        assert_eq!(parse_line(&format_line(&timed)), Some(timed));
+    }
+
+    #[test]
+    fn test_import_reconciles_and_exports() {
+        let conn = setup_test_db();
+        conn.execute("INSERT INTO projects (name) VALUES ('Client X')", []).unwrap();
+        let file = "(A) Call back +Client_X @phone est:2\n\
+                    Buy milk\n\
+                    \n\
+                    x 2025-11-13 Ship v2 +Launch\n\
+                    (C) +Client_X\n";
+
+        let first = import_todotxt(&conn, file).unwrap();
+        assert_eq!(first.created_count, 3);
+        assert_eq!(first.skipped_count, 1);
+
+        let second = import_todotxt(&conn, &file.replace("(A) Call", "(D) Call")).unwrap();
+        assert_eq!(second.created_count, 0);
+        assert_eq!(second.updated_count, 3);
+
+        let mut projects: Vec<String> = crate::db::queries::get_all_projects(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|project| project.name)
+            .collect();
+        projects.sort();
+        assert_eq!(projects, ["Client X", "Launch"]);
+
+        let mut output = Vec::new();
+        assert_eq!(write_todotxt(&conn, false, &mut output).unwrap(), 2);
+        let exported = String::from_utf8(output).unwrap();
+        let created = conn
+            .query_row("SELECT DATE(created_at) FROM tasks WHERE id = 1", [], |row| {
+                row.get::<_, String>(0)
+            })
+            .unwrap();
+        assert_eq!(
+            exported,
+            format!(
+                "(D) {created} Call back +Client_X @phone est:2\n{created} Buy milk\n"
+            )
+        );
+
+        let mut output = Vec::new();
+        assert_eq!(write_todotxt(&conn, true, &mut output).unwrap(), 3);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("x 2025-11-13 "));
+    }
+}
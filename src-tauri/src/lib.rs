@@ -3,6 +3,8 @@
 
 mod commands;
 mod db;
+mod formats;
+mod reports;
 mod tray;
 mod notifications;
 
@@ -13,11 +15,12 @@ use tauri::Manager;
 /// Initialise et démarre l'application Tauri
 ///
 /// Cette fonction:
-/// 1. Configure le chemin de la base de données
+/// 1. Configure le chemin de la base de données (selon l'espace de travail actif)
 /// 2. Exécute les migrations de schéma
-/// 3. Initialise la connexion DB comme état partagé
-/// 4. Enregistre toutes les commandes Tauri
-/// 5. Configure le système de logs en mode debug
+/// 3. Lance les sauvegardes automatiques de la base
+/// 4. Initialise la connexion DB comme état partagé
+/// 5. Enregistre toutes les commandes Tauri
+/// 6. Configure le système de logs en mode debug
 #[allow(clippy::missing_panics_doc)]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -48,8 +51,16 @@ pub fn run() {
                     e
                 })?;
 
-            // Chemin complet vers la base de données
-            let db_path: PathBuf = app_dir.join("tomatotask.db");
+            // Charge les espaces de travail et le chemin de la base de l'espace actif
+            let workspaces = db::workspaces::WorkspaceManager::load(app_dir.clone())
+                .map_err(|e| {
+                    eprintln!("FATAL: Failed to load workspaces: {}", e);
+                    e
+                })?;
+            let db_path: PathBuf = workspaces.active_database_path().map_err(|e| {
+                eprintln!("FATAL: Failed to resolve the active workspace: {}", e);
+                e
+            })?;
             println!("Database path: {:?}", db_path);
 
             // Initialise la connexion à la base de données
@@ -59,23 +70,34 @@ pub fn run() {
                     e
                 })?;
 
-            // Exécute les migrations de schéma
-            let conn = db.get_connection();
-            let conn = conn.lock().map_err(|e| {
-                eprintln!("FATAL: Failed to lock database connection: {}", e);
-                format!("Failed to lock database connection: {}", e)
-            })?;
-            migrations::run_migrations(&conn)
-                .map_err(|e| {
-                    eprintln!("FATAL: Failed to run database migrations: {}", e);
-                    e
+            // Exécute les migrations de schéma (une base chiffrée est migrée
+            // une fois déverrouillée, voir `unlock_database`)
+            if db::encryption::is_encrypted(&db_path) {
+                println!("Database is encrypted, waiting for its passphrase");
+            } else {
+                let conn = db.get_connection();
+                let conn = conn.lock().map_err(|e| {
+                    eprintln!("FATAL: Failed to lock database connection: {}", e);
+                    format!("Failed to lock database connection: {}", e)
                 })?;
-            drop(conn); // Libère le lock
+                migrations::run_migrations(&conn)
+                    .map_err(|e| {
+                        eprintln!("FATAL: Failed to run database migrations: {}", e);
+                        e
+                    })?;
+                drop(conn); // Libère le lock
+
+                println!("Database initialized successfully");
+            }
 
-            println!("Database initialized successfully");
+            // Sauvegarde la base au démarrage puis selon l'intervalle configuré
+            let backup_dir = db::backup::DefaultBackupDir::new(workspaces.active_backup_dir()?);
+            db::backup::spawn_backup_scheduler(db.clone(), backup_dir.clone());
+            app.manage(backup_dir);
 
-            // Enregistre la connexion DB comme état partagé
+            // Enregistre la connexion DB et les espaces de travail comme état partagé
             app.manage(db);
+            app.manage(workspaces);
 
             Ok(())
         })
@@ -91,20 +113,94 @@ pub fn run() {
             commands::get_tasks_by_project,
             // Commandes de gestion des projets
             commands::get_projects,
+            commands::get_projects_by_status,
+            commands::get_project_tree,
             commands::create_project,
             commands::update_project,
+            commands::move_project,
+            commands::set_project_status,
+            commands::preview_project_deletion,
             commands::delete_project,
+            // Commandes de gestion des objectifs de projet
+            commands::get_project_goals,
+            commands::set_project_goal,
+            commands::update_project_goal,
+            commands::clear_project_goal,
+            commands::get_project_goal_progress,
+            // Commandes de gestion des jalons
+            commands::get_milestones,
+            commands::create_milestone,
+            commands::update_milestone,
+            commands::delete_milestone,
+            commands::get_milestone_tasks,
+            commands::add_task_to_milestone,
+            commands::remove_task_from_milestone,
+            commands::get_milestone_burndown,
             // Commandes de gestion des sessions Pomodoro
             commands::create_session,
             commands::complete_session,
             commands::interrupt_session,
+            commands::rate_session,
             commands::get_sessions_by_date_range,
             // Commandes de gestion des paramètres
             commands::get_settings,
             commands::update_settings,
+            // Commandes d'objectif quotidien et de séries
+            commands::get_streaks,
+            commands::get_rest_days,
+            commands::add_rest_day,
+            commands::remove_rest_day,
             // Commandes de résumé/analytics
             commands::get_daily_summary,
             commands::get_weekly_summary,
+            commands::get_range_summary,
+            commands::get_period_comparison,
+            commands::get_project_stats,
+            commands::get_activity_heatmap,
+            commands::get_productivity_distribution,
+            commands::get_break_compliance,
+            // Commandes de rapports
+            commands::generate_report,
+            commands::get_report_template,
+            commands::set_report_template,
+            commands::reset_report_template,
+            // Commandes d'export/import des données
+            commands::export_data,
+            commands::import_data,
+            commands::export_sessions_csv,
+            commands::export_tasks_csv,
+            commands::export_sessions_ics,
+            commands::import_ics_tasks,
+            commands::export_tasks_todotxt,
+            commands::import_tasks_todotxt,
+            // Commandes de sauvegarde et de maintenance de la base
+            commands::get_backup_settings,
+            commands::update_backup_settings,
+            commands::create_backup,
+            commands::list_backups,
+            commands::restore_backup,
+            commands::check_database,
+            // Commandes de rétention de l'historique
+            commands::get_retention_settings,
+            commands::update_retention_settings,
+            commands::preview_purge,
+            commands::purge_history,
+            // Commandes de chiffrement de la base
+            commands::get_encryption_status,
+            commands::unlock_database,
+            commands::encrypt_database,
+            commands::change_database_passphrase,
+            commands::export_decrypted_database,
+            // Commandes des espaces de travail
+            commands::list_workspaces,
+            commands::create_workspace,
+            commands::rename_workspace,
+            commands::switch_workspace,
+            commands::delete_workspace,
+            // Commandes de synchronisation entre appareils
+            commands::get_sync_status,
+            commands::set_sync_folder,
+            commands::sync_now,
             // Commandes de notifications (temporairement désactivées)
             // notifications::send_custom_notification,
             // Commandes de system tray
@@ -0,0 +1,390 @@
+// Rendu des rapports de période en Markdown ou HTML
+//
+// Les modèles utilisent des variables `{{nom}}` remplacées par les valeurs du
+// rapport. Les sections (comparaison, projets, tâches) sont rendues dans le
+// format du modèle avant substitution.
+use crate::db::{MetricDelta, ReportData, ReportFormat, SummaryGranularity};
+
+/// Modèle Markdown intégré
+const MARKDOWN_TEMPLATE: &str = include_str!("templates/report.md");
+
+/// Modèle HTML intégré (page autonome)
+const HTML_TEMPLATE: &str = include_str!("templates/report.html");
+
+/// Retourne le modèle intégré d'un format
+pub fn default_template(format: ReportFormat) -> &'static str {
+    match format {
+        ReportFormat::Markdown => MARKDOWN_TEMPLATE,
+        ReportFormat::Html => HTML_TEMPLATE,
+    }
+}
+
+/// Rend un rapport à partir d'un modèle
+///
+/// Le modèle est parcouru une seule fois : une variable présente dans les
+/// données utilisateur (nom de projet, titre) n'est pas remplacée. Les
+/// variables inconnues sont laissées telles quelles.
+///
+/// # Arguments
+/// * `data` - Données du rapport
+/// * `format` - Format de sortie (détermine le rendu des sections et l'échappement)
+/// * `template` - Contenu du modèle
+pub fn render_report(data: &ReportData, format: ReportFormat, template: &str) -> String {
+    let comparison = &data.comparison;
+    let focus_minutes = comparison.total_focus_minutes.current as i32;
+
+    let variables = [
+        ("period", period_label(comparison.period).to_string()),
+        ("start_date", comparison.current_start.clone()),
+        ("end_date", comparison.current_end.clone()),
+        ("previous_start_date", comparison.previous_start.clone()),
+        ("previous_end_date", comparison.previous_end.clone()),
+        ("total_focus_minutes", focus_minutes.to_string()),
+        ("focus_time", format_minutes(focus_minutes)),
+        (
+            "completed_pomodoros_count",
+            comparison.completed_pomodoros_count.current.to_string(),
+        ),
+        ("completed_tasks_count", comparison.completed_tasks_count.current.to_string()),
+        ("interrupted_count", comparison.interrupted_count.current.to_string()),
+        (
+            "interruption_rate",
+            format!("{:.0}%", comparison.interruption_rate.current * 100.0),
+        ),
+        ("comparison", render_comparison(data, format)),
+        ("projects", render_projects(data, format)),
+        ("completed_tasks", render_completed_tasks(data, format)),
+    ];
+
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let variable = after.find("}}").and_then(|end| {
+            variables
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (end, value))
+        });
+        match variable {
+            Some((end, value)) => {
+                output.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Nom de la période utilisé dans les titres
+fn period_label(period: SummaryGranularity) -> &'static str {
+    match period {
+        SummaryGranularity::Day => "day",
+        SummaryGranularity::Week => "week",
+        SummaryGranularity::Month => "month",
+        SummaryGranularity::Year => "year",
+    }
+}
+
+/// Formate une durée en minutes ("2h 05m", "40m")
+fn format_minutes(minutes: i32) -> String {
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Formate l'évolution d'un indicateur par rapport à la période précédente
+fn format_change(delta: &MetricDelta) -> String {
+    match delta.percent_change {
+        Some(percent) => format!("{:+} ({:+.0}%)", delta.change, percent),
+        None => format!("{:+}", delta.change),
+    }
+}
+
+/// Échappe le texte utilisateur selon le format de sortie
+fn escape(text: &str, format: ReportFormat) -> String {
+    match format {
+        // Un retour à la ligne couperait la ligne du tableau ou de la liste
+        ReportFormat::Markdown => text
+            .replace('|', "\\|")
+            .replace("\r\n", " ")
+            .replace(['\r', '\n'], " "),
+        ReportFormat::Html => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;"),
+    }
+}
+
+/// Rend un tableau (en-têtes puis lignes) dans le format demandé
+///
+/// Les colonnes après la première sont alignées à droite ; les cellules
+/// doivent déjà être échappées.
+fn render_table(headers: &[&str], rows: &[Vec<String>], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => {
+            let mut lines = vec![
+                format!("| {} |", headers.join(" | ")),
+                format!("|---|{}", "---:|".repeat(headers.len() - 1)),
+            ];
+            lines.extend(rows.iter().map(|row| format!("| {} |", row.join(" | "))));
+            lines.join("\n")
+        }
+        ReportFormat::Html => {
+            let cell = |tag: &str, index: usize, content: &str| {
+                if index == 0 {
+                    format!("<{tag}>{content}</{tag}>")
+                } else {
+                    format!("<{tag} class=\"number\">{content}</{tag}>")
+                }
+            };
+            let header: String = headers
+                .iter()
+                .enumerate()
+                .map(|(i, h)| cell("th", i, h))
+                .collect();
+            let body: String = rows
+                .iter()
+                .map(|row| {
+                    let cells: String =
+                        row.iter().enumerate().map(|(i, c)| cell("td", i, c)).collect();
+                    format!("<tr>{cells}</tr>\n")
+                })
+                .collect();
+            format!("<table>\n<tr>{header}</tr>\n{body}</table>")
+        }
+    }
+}
+
+/// Rend un message lorsqu'une section est vide
+fn render_empty(message: &str, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => format!("_{}_", message),
+        ReportFormat::Html => format!("<p class=\"muted\">{}</p>", message),
+    }
+}
+
+/// Tableau comparant la période à la précédente
+fn render_comparison(data: &ReportData, format: ReportFormat) -> String {
+    let comparison = &data.comparison;
+    let minutes = |value: f64| format_minutes(value as i32);
+    let percent = |value: f64| format!("{:.0}%", value * 100.0);
+    let rate = &comparison.interruption_rate;
+
+    let rows = vec![
+        vec![
+            "Focus time".to_string(),
+            minutes(comparison.total_focus_minutes.current),
+            minutes(comparison.total_focus_minutes.previous),
+            format_change(&comparison.total_focus_minutes),
+        ],
+        vec![
+            "Pomodoros".to_string(),
+            comparison.completed_pomodoros_count.current.to_string(),
+            comparison.completed_pomodoros_count.previous.to_string(),
+            format_change(&comparison.completed_pomodoros_count),
+        ],
+        vec![
+            "Tasks completed".to_string(),
+            comparison.completed_tasks_count.current.to_string(),
+            comparison.completed_tasks_count.previous.to_string(),
+            format_change(&comparison.completed_tasks_count),
+        ],
+        vec![
+            "Interruption rate".to_string(),
+            percent(rate.current),
+            percent(rate.previous),
+            format!("{:+.0} pts", rate.change * 100.0),
+        ],
+    ];
+
+    render_table(&["", "Current", "Previous", "Change"], &rows, format)
+}
+
+/// Tableau du temps de focus par projet et par tâche
+fn render_projects(data: &ReportData, format: ReportFormat) -> String {
+    let breakdown = &data.breakdown;
+    if breakdown.projects.is_empty() && breakdown.unassigned_pomodoros_count == 0 {
+        return render_empty("No focus sessions.", format);
+    }
+
+    let mut rows = Vec::new();
+    for project in &breakdown.projects {
+        let name = project
+            .project_name
+            .as_deref()
+            .map(|name| escape(name, format))
+            .unwrap_or_else(|| "No project".to_string());
+        let name = match format {
+            ReportFormat::Markdown => format!("**{}**", name),
+            ReportFormat::Html => format!("<strong>{}</strong>", name),
+        };
+        rows.push(vec![
+            name,
+            project.completed_pomodoros_count.to_string(),
+            format_minutes(project.total_focus_minutes),
+        ]);
+
+        let indent = match format {
+            ReportFormat::Markdown => "↳ ",
+            ReportFormat::Html => "&nbsp;&nbsp;↳ ",
+        };
+        for task in &project.tasks {
+            rows.push(vec![
+                format!("{}{}", indent, escape(&task.title, format)),
+                task.completed_pomodoros_count.to_string(),
+                format_minutes(task.total_focus_minutes),
+            ]);
+        }
+    }
+
+    if breakdown.unassigned_pomodoros_count > 0 {
+        rows.push(vec![
+            "Unassigned sessions".to_string(),
+            breakdown.unassigned_pomodoros_count.to_string(),
+            format_minutes(breakdown.unassigned_focus_minutes),
+        ]);
+    }
+
+    render_table(&["Project / task", "Pomodoros", "Focus time"], &rows, format)
+}
+
+/// Liste des tâches complétées sur la période
+fn render_completed_tasks(data: &ReportData, format: ReportFormat) -> String {
+    if data.completed_tasks.is_empty() {
+        return render_empty("No tasks completed.", format);
+    }
+
+    let items: Vec<String> = data
+        .completed_tasks
+        .iter()
+        .map(|task| {
+            let title = escape(&task.title, format);
+            let project = task
+                .project_name
+                .as_deref()
+                .map(|name| format!(" ({})", escape(name, format)))
+                .unwrap_or_default();
+            match format {
+                ReportFormat::Markdown => {
+                    format!("- [x] {}{} — {}", title, project, task.completed_date)
+                }
+                ReportFormat::Html => format!(
+                    "<li>{}{} <span class=\"muted\">— {}</span></li>",
+                    title, project, task.completed_date
+                ),
+            }
+        })
+        .collect();
+
+    match format {
+        ReportFormat::Markdown => items.join("\n"),
+        ReportFormat::Html => format!("<ul>\n{}\n</ul>", items.join("\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        CompletedTaskEntry, PeriodComparison, ProjectBreakdown, SummaryBreakdown, TaskBreakdown,
+    };
+
+    fn sample_report() -> ReportData {
+        let delta = |current: f64, previous: f64| MetricDelta {
+            current,
+            previous,
+            change: current - previous,
+            percent_change: (previous != 0.0).then(|| (current - previous) * 100.0 / previous),
+        };
+
+        ReportData {
+            comparison: PeriodComparison {
+                period: SummaryGranularity::Week,
+                current_start: "2025-11-10".to_string(),
+                current_end: "2025-11-16".to_string(),
+                previous_start: "2025-11-03".to_string(),
+                previous_end: "2025-11-09".to_string(),
+                partial: false,
+                total_focus_minutes: delta(75.0, 50.0),
+                completed_pomodoros_count: delta(3.0, 2.0),
+                completed_tasks_count: delta(1.0, 0.0),
+                interrupted_count: delta(1.0, 0.0),
+                interruption_rate: delta(0.25, 0.0),
+            },
+            breakdown: SummaryBreakdown {
+                projects: vec![ProjectBreakdown {
+                    project_id: Some(1),
+                    project_name: Some("R&D <core>".to_string()),
+                    completed_pomodoros_count: 3,
+                    total_focus_minutes: 75,
+                    tasks: vec![TaskBreakdown {
+                        task_id: 1,
+                        title: "Spec".to_string(),
+                        completed_pomodoros_count: 3,
+                        total_focus_minutes: 75,
+                    }],
+                }],
+                unassigned_pomodoros_count: 0,
+                unassigned_focus_minutes: 0,
+            },
+            completed_tasks: vec![CompletedTaskEntry {
+                task_id: 1,
+                title: "Spec".to_string(),
+                project_name: Some("R&D <core>".to_string()),
+                completed_date: "2025-11-13".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_report() {
+        let report = render_report(
+            &sample_report(),
+            ReportFormat::Markdown,
+            default_template(ReportFormat::Markdown),
+        );
+
+        assert!(!report.contains("{{"));
+        assert!(report.contains("Focus time: **1h 15m**"));
+        assert!(report.contains("(25% of work sessions)"));
+        assert!(report.contains("| Pomodoros | 3 | 2 | +1 (+50%) |"));
+        assert!(report.contains("| **R&D <core>** | 3 | 1h 15m |"));
+        assert!(report.contains("- [x] Spec (R&D <core>) — 2025-11-13"));
+    }
+
+    #[test]
+    fn test_render_html_report_with_custom_template() {
+        let report = render_report(
+            &sample_report(),
+            ReportFormat::Html,
+            "<h1>{{period}} {{start_date}}</h1>{{completed_tasks}}{{unknown}}",
+        );
+
+        assert!(report.starts_with("<h1>week 2025-11-10</h1><ul>"));
+        assert!(report.contains("Spec (R&amp;D &lt;core&gt;)"));
+        assert!(report.ends_with("{{unknown}}"));
+    }
+
+    #[test]
+    fn test_user_data_is_not_expanded() {
+        let mut data = sample_report();
+        data.breakdown.projects[0].project_name = Some("{{completed_tasks_count}}".to_string());
+        data.breakdown.projects[0].tasks[0].title = "Line one\nline | two".to_string();
+
+        let report = render_report(&data, ReportFormat::Markdown, "{{projects}} {{period}}");
+
+        assert!(report.contains("| **{{completed_tasks_count}}** | 3 | 1h 15m |"));
+        assert!(report.contains("Line one line \\| two"));
+        assert!(report.ends_with(" week"));
+    }
+}